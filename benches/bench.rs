@@ -32,6 +32,18 @@ fn precomputed_large(b: &mut Bencher) {
     b.iter(|| r.resize(src.as_gray(), dst.as_gray_mut()).unwrap());
 }
 
+#[bench]
+fn fixed_point_large(b: &mut Bencher) {
+    let (info, src) = get_image();
+    let (w1, h1) = (info.width as usize, info.height as usize);
+    let (w2, h2) = (1600, 1200);
+    let mut dst = vec![120; w2 * h2];
+
+    let mut r = resize::FixedPointResizer::new(w1, h1, w2, h2, Gray8, Triangle).unwrap();
+
+    b.iter(|| r.resize(src.as_gray(), dst.as_gray_mut()).unwrap());
+}
+
 #[bench]
 fn tiny(b: &mut Bencher) {
     let (info, src0) = get_image();
@@ -0,0 +1,91 @@
+use crate::px::AlphaFormat;
+use crate::{Error, Options, PixelFormat, Result, Scale, Type};
+use core::num::NonZeroUsize;
+use fallible_collections::FallibleVec;
+use std::vec::Vec;
+
+/// Resampler that uses one filter for color channels and another one for alpha.
+///
+/// A soft alpha filter avoids ringing around the edges of masks, while colors stay sharp.
+/// Colors are resampled first, with the alpha computed by the color filter if the format
+/// premultiplies, then alpha is resampled separately and replaces it.
+#[derive(Debug)]
+pub struct AlphaResizer<Format: AlphaFormat> {
+    color: Scale,
+    alpha: Scale,
+    pix_fmt: Format,
+    tmp: Vec<Format::Accumulator>,
+    alpha_tmp: Vec<f32>,
+    alpha_dst: Vec<f32>,
+}
+
+impl<Format: AlphaFormat> AlphaResizer<Format> {
+    /// Create a new resizer with `color_filter` for color channels and `alpha_filter` for the alpha channel.
+    pub fn new(source_width: usize, source_heigth: usize, dest_width: usize, dest_height: usize, pixel_format: Format, color_filter: Type, alpha_filter: Type) -> Result<Self> {
+        let options = Options::default();
+        Ok(Self {
+            color: Scale::with_options(source_width, source_heigth, dest_width, dest_height, &color_filter, &color_filter, &options)?,
+            alpha: Scale::with_options(source_width, source_heigth, dest_width, dest_height, &alpha_filter, &alpha_filter, &options)?,
+            pix_fmt: pixel_format,
+            tmp: Vec::new(),
+            alpha_tmp: Vec::new(),
+            alpha_dst: Vec::new(),
+        })
+    }
+
+    /// Resize `src` image data into `dst`.
+    #[inline]
+    pub fn resize(&mut self, src: &[Format::InputPixel], dst: &mut [Format::OutputPixel]) -> Result<()> {
+        self.resize_internal(src, self.color.w1, dst)
+    }
+
+    /// Resize `src` image data into `dst`, skipping `stride` pixels each row.
+    #[inline]
+    pub fn resize_stride(&mut self, src: &[Format::InputPixel], src_stride: usize, dst: &mut [Format::OutputPixel]) -> Result<()> {
+        let src_stride = NonZeroUsize::new(src_stride).ok_or(Error::InvalidParameters)?;
+        self.resize_internal(src, src_stride, dst)
+    }
+
+    fn resize_internal(&mut self, src: &[Format::InputPixel], src_stride: NonZeroUsize, dst: &mut [Format::OutputPixel]) -> Result<()> {
+        self.color.check_buffers(src.len(), src_stride, dst.len())?;
+        self.color.resample_both_axes(&self.pix_fmt, &mut self.tmp, src, src_stride, dst)?;
+
+        self.alpha_dst.clear();
+        FallibleVec::try_reserve(&mut self.alpha_dst, dst.len())?;
+        self.alpha_dst.resize(dst.len(), 0.);
+        self.alpha.resample_both_axes(&AlphaOnly(&self.pix_fmt), &mut self.alpha_tmp, src, src_stride, &mut self.alpha_dst)?;
+        for (px, &a) in dst.iter_mut().zip(self.alpha_dst.iter()) {
+            self.pix_fmt.set_alpha(px, a);
+        }
+        Ok(())
+    }
+}
+
+/// Resamples only the alpha channel of `Format`
+struct AlphaOnly<'a, Format>(&'a Format);
+
+impl<Format: AlphaFormat> PixelFormat for AlphaOnly<'_, Format> {
+    type InputPixel = Format::InputPixel;
+    type OutputPixel = f32;
+    type Accumulator = f32;
+
+    #[inline(always)]
+    fn new() -> f32 {
+        0.
+    }
+
+    #[inline(always)]
+    fn add(&self, acc: &mut f32, inp: Self::InputPixel, coeff: f32) {
+        *acc += self.0.alpha(inp) * coeff;
+    }
+
+    #[inline(always)]
+    fn add_acc(acc: &mut f32, inp: f32, coeff: f32) {
+        *acc += inp * coeff;
+    }
+
+    #[inline(always)]
+    fn into_pixel(&self, acc: f32) -> f32 {
+        acc
+    }
+}
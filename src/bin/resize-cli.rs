@@ -0,0 +1,109 @@
+//! Resizes a PNG file. Build with `cargo run --features cli --bin resize-cli -- in.png 320x240 out.png`
+use png::{BitDepth, ColorType, Transformations};
+use resize::{Pixel, PixelFormat, Resizer, Type};
+use rgb::FromSlice;
+use std::convert::TryInto;
+use std::env;
+use std::error::Error;
+use std::fs::File;
+use std::io::BufWriter;
+use std::process;
+
+const USAGE: &str = "Usage: resize-cli [--filter FILTER] in.png WIDTHxHEIGHT out.png
+
+FILTER is e.g. lanczos3 (default), mitchell, triangle, area or cubic:0.33,0.33.
+Either WIDTH or HEIGHT can be left out to keep the aspect ratio, e.g. 320x";
+
+struct Args {
+    filter: Type,
+    input: String,
+    width: Option<usize>,
+    height: Option<usize>,
+    output: String,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut filter = Type::Lanczos3;
+    let mut positional = Vec::new();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--filter" | "-f" => {
+                let name = args.next().ok_or("--filter needs a value")?;
+                filter = name.parse().map_err(|_| format!("unknown filter '{}'", name))?;
+            },
+            "--help" | "-h" => return Err(String::new()),
+            _ => positional.push(arg),
+        }
+    }
+    let [input, size, output]: [String; 3] = positional.try_into().map_err(|_| "expected 3 arguments".to_string())?;
+    let (width, height) = size.split_once('x').ok_or_else(|| format!("size '{}' isn't WIDTHxHEIGHT", size))?;
+    let dimension = |s: &str| -> Result<Option<usize>, String> {
+        if s.is_empty() {
+            return Ok(None);
+        }
+        s.parse().ok().filter(|&n| n > 0).map(Some).ok_or_else(|| format!("invalid dimension '{}'", s))
+    };
+    let (width, height) = (dimension(width)?, dimension(height)?);
+    if width.is_none() && height.is_none() {
+        return Err("at least one of WIDTH and HEIGHT is needed".into());
+    }
+    Ok(Args { filter, input, width, height, output })
+}
+
+#[allow(clippy::too_many_arguments)]
+fn resize_pixels<Format: PixelFormat>(w1: usize, h1: usize, w2: usize, h2: usize, format: Format, filter: Type, src: &[Format::InputPixel], dst: &mut [Format::OutputPixel]) -> Result<(), resize::Error> {
+    Resizer::build(w1, h1, w2, h2).filter(filter).construct(format)?.resize(src, dst)
+}
+
+fn run(args: Args) -> Result<(), Box<dyn Error>> {
+    let mut decoder = png::Decoder::new(File::open(&args.input)?);
+    // Palettes and low bit depths are expanded to 8 bits per channel
+    decoder.set_transformations(Transformations::EXPAND | Transformations::STRIP_16);
+    let (info, mut reader) = decoder.read_info()?;
+    let mut src = vec![0; reader.output_buffer_size()];
+    reader.next_frame(&mut src)?;
+    let (color_type, bit_depth) = reader.output_color_type();
+    assert_eq!(BitDepth::Eight, bit_depth);
+
+    let (w1, h1) = (info.width as usize, info.height as usize);
+    let (w2, h2) = match (args.width, args.height) {
+        (Some(w), Some(h)) => (w, h),
+        (Some(w), None) => (w, (h1 * w + w1 / 2) / w1),
+        (None, Some(h)) => ((w1 * h + h1 / 2) / h1, h),
+        (None, None) => unreachable!(),
+    };
+    let (w2, h2) = (w2.max(1), h2.max(1));
+    let mut dst = vec![0u8; w2 * h2 * color_type.samples()];
+
+    // Images with alpha are resized premultiplied, so that transparent pixels don't bleed their color
+    match color_type {
+        ColorType::Grayscale => resize_pixels(w1, h1, w2, h2, Pixel::Gray8, args.filter, src.as_gray(), dst.as_gray_mut()),
+        ColorType::GrayscaleAlpha => resize_pixels(w1, h1, w2, h2, Pixel::GrayAlpha8P, args.filter, src.as_gray_alpha(), dst.as_gray_alpha_mut()),
+        ColorType::RGB => resize_pixels(w1, h1, w2, h2, Pixel::RGB8, args.filter, src.as_rgb(), dst.as_rgb_mut()),
+        ColorType::RGBA => resize_pixels(w1, h1, w2, h2, Pixel::RGBA8P, args.filter, src.as_rgba(), dst.as_rgba_mut()),
+        ColorType::Indexed => unreachable!("palette is expanded"),
+    }.map_err(|err| format!("can't resize to {}x{}: {}", w2, h2, err))?;
+
+    let mut encoder = png::Encoder::new(BufWriter::new(File::create(&args.output)?), w2 as u32, h2 as u32);
+    encoder.set_color(color_type);
+    encoder.set_depth(BitDepth::Eight);
+    encoder.write_header()?.write_image_data(&dst)?;
+    Ok(())
+}
+
+fn main() {
+    let args = match parse_args(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(err) => {
+            if !err.is_empty() {
+                eprintln!("error: {}\n", err);
+            }
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        },
+    };
+    if let Err(err) = run(args) {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    }
+}
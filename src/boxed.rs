@@ -0,0 +1,106 @@
+use crate::px::IntegerFormat;
+use crate::{check_area, image_len, Error, Result};
+use core::any::TypeId;
+use core::marker::PhantomData;
+use core::num::NonZeroUsize;
+use fallible_collections::FallibleVec;
+use std::vec::Vec;
+
+/// Downscaler by integer factors (2:1, 3:1, etc.) that averages blocks of pixels with integer arithmetic.
+///
+/// Averages are rounded exactly. [`Resizer`](crate::Resizer) averages blocks the same way for [`Type::Area`](crate::Type::Area)
+/// with these sizes, when its format implements [`IntegerFormat`], so this only saves computing coefficients.
+#[derive(Debug)]
+pub struct BoxDownscaler<Format: IntegerFormat> {
+    w1: NonZeroUsize,
+    w2: usize,
+    h2: usize,
+    factor_x: usize,
+    factor_y: usize,
+    sums: Vec<Format::Sums>,
+    _format: PhantomData<Format>,
+}
+
+impl<Format: IntegerFormat> BoxDownscaler<Format> {
+    /// Create a new downscaler. Source dimensions must be multiples of destination dimensions.
+    pub fn new(source_width: usize, source_heigth: usize, dest_width: usize, dest_height: usize, _pixel_format: Format) -> Result<Self> {
+        let w1 = NonZeroUsize::new(source_width).ok_or(Error::InvalidParameters)?;
+        if source_heigth == 0 || dest_width == 0 || dest_height == 0 ||
+            !source_width.is_multiple_of(dest_width) || !source_heigth.is_multiple_of(dest_height) {
+            return Err(Error::InvalidParameters);
+        }
+        check_area(source_width, source_heigth)?;
+        Ok(Self {
+            w1,
+            w2: dest_width,
+            h2: dest_height,
+            factor_x: source_width / dest_width,
+            factor_y: source_heigth / dest_height,
+            sums: Vec::new(),
+            _format: PhantomData,
+        })
+    }
+
+    /// Resize `src` image data into `dst`.
+    #[inline]
+    pub fn resize(&mut self, src: &[Format::Pixel], dst: &mut [Format::Pixel]) -> Result<()> {
+        self.resize_stride(src, self.w1.get(), dst)
+    }
+
+    /// Resize `src` image data into `dst`, skipping `stride` pixels each row.
+    pub fn resize_stride(&mut self, src: &[Format::Pixel], src_stride: usize, dst: &mut [Format::Pixel]) -> Result<()> {
+        let (w1, h1) = (self.w1.get(), self.h2 * self.factor_y);
+        if src_stride < w1 || !matches!(image_len(w1, h1, src_stride), Some(len) if src.len() >= len) || image_len(self.w2, self.h2, self.w2) != Some(dst.len()) {
+            return Err(Error::InvalidParameters);
+        }
+        self.sums.clear();
+        FallibleVec::try_reserve(&mut self.sums, self.w2)?;
+        self.sums.resize(self.w2, Format::new());
+
+        let count = (self.factor_x * self.factor_y) as u64;
+        for (y2, dst_row) in dst.chunks_exact_mut(self.w2).enumerate() {
+            self.sums.iter_mut().for_each(|s| *s = Format::new());
+            for y1 in y2 * self.factor_y..(y2 + 1) * self.factor_y {
+                let src_row = &src[y1 * src_stride..y1 * src_stride + w1];
+                for (sum, block) in self.sums.iter_mut().zip(src_row.chunks_exact(self.factor_x)) {
+                    for &px in block {
+                        Format::add(sum, px);
+                    }
+                }
+            }
+            for (out, &sum) in dst_row.iter_mut().zip(self.sums.iter()) {
+                *out = Format::into_pixel(average(sum, count));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Divides sums of `count` pixels, rounding halves up
+#[inline(always)]
+fn average<Sums: AsMut<[u64]>>(mut sums: Sums, count: u64) -> Sums {
+    for c in sums.as_mut() {
+        *c = (*c + count / 2) / count;
+    }
+    sums
+}
+
+/// Average of the `factors`-sized block at the start of `src`, with rows `stride` apart, if `P` and `Q` are both pixels of `Format`
+#[inline(always)]
+pub(crate) fn average_as<Format: IntegerFormat, P: Copy + 'static, Q: Copy + 'static>(src: &[P], stride: usize, [factor_x, factor_y]: [usize; 2]) -> Option<Q>
+where Format::Pixel: 'static {
+    if TypeId::of::<P>() != TypeId::of::<Format::Pixel>() || TypeId::of::<Q>() != TypeId::of::<Format::Pixel>() {
+        return None;
+    }
+    // Safety: `P` is the same type as `Format::Pixel`
+    let src = unsafe { core::slice::from_raw_parts(src.as_ptr().cast::<Format::Pixel>(), src.len()) };
+    let mut sum = Format::new();
+    for row in src.chunks(stride).take(factor_y) {
+        for &px in &row[..factor_x] {
+            Format::add(&mut sum, px);
+        }
+    }
+    let px = Format::into_pixel(average(sum, (factor_x * factor_y) as u64));
+    // Safety: `Q` is the same type as `Format::Pixel`
+    Some(unsafe { core::mem::transmute_copy::<Format::Pixel, Q>(&px) })
+}
@@ -0,0 +1,157 @@
+use crate::{Alignment, CoefficientCache, Edge, Options, PixelFormat, Resizer, Result, Rotation, Type};
+use core::marker::PhantomData;
+
+/// Configuration of a [`Resizer`], created with [`Resizer::build`].
+///
+/// Settings that aren't set keep their defaults, so new ones can be added without breaking existing code.
+///
+/// ```rust
+/// use resize::{Pixel::RGB8, Type::Lanczos3, Edge, Resizer};
+/// let resizer = Resizer::build(640, 480, 320, 240)
+///     .filter(Lanczos3)
+///     .edge(Edge::Mirror)
+///     .construct(RGB8)?;
+/// # Ok::<_, resize::Error>(())
+/// ```
+#[must_use]
+pub struct ResizerBuilder<Format> {
+    source: (usize, usize),
+    dest: (usize, usize),
+    horizontal: Type,
+    /// Same as horizontal when `None`
+    vertical: Option<Type>,
+    options: Options,
+    format: PhantomData<fn() -> Format>,
+}
+
+impl<Format: PixelFormat> Resizer<Format> {
+    /// Start configuring a resizer for the given dimensions. The default filter is [`Type::Lanczos3`].
+    #[inline]
+    pub fn build(source_width: usize, source_heigth: usize, dest_width: usize, dest_height: usize) -> ResizerBuilder<Format> {
+        ResizerBuilder {
+            source: (source_width, source_heigth),
+            dest: (dest_width, dest_height),
+            horizontal: Type::Lanczos3,
+            vertical: None,
+            options: Options::default(),
+            format: PhantomData,
+        }
+    }
+}
+
+impl<Format: PixelFormat> ResizerBuilder<Format> {
+    /// Filter used for both axes
+    #[inline]
+    pub fn filter(mut self, filter_type: Type) -> Self {
+        self.horizontal = filter_type;
+        self.vertical = None;
+        self
+    }
+
+    /// Use `horizontal` filter for rows and `vertical` filter for columns, see [`Resizer::with_axis_filters`]
+    #[inline]
+    pub fn axis_filters(mut self, horizontal: Type, vertical: Type) -> Self {
+        self.horizontal = horizontal;
+        self.vertical = Some(vertical);
+        self
+    }
+
+    /// Replaces all [`Options`] at once
+    #[inline]
+    pub fn options(mut self, options: Options) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Sets [`Options::sharpen`]
+    #[inline]
+    pub fn sharpen(mut self, amount: f32) -> Self {
+        self.options.sharpen = amount;
+        self
+    }
+
+    /// Sets [`Options::edge`]
+    #[inline]
+    pub fn edge(mut self, edge: Edge) -> Self {
+        self.options.edge = edge;
+        self
+    }
+
+    /// Sets [`Options::alignment`]
+    #[inline]
+    pub fn alignment(mut self, alignment: Alignment) -> Self {
+        self.options.alignment = alignment;
+        self
+    }
+
+    /// Sets [`Options::offset`]
+    #[inline]
+    pub fn offset(mut self, x: f64, y: f64) -> Self {
+        self.options.offset = (x, y);
+        self
+    }
+
+    /// Sets [`Options::kernel_lut`]
+    #[inline]
+    pub fn kernel_lut(mut self, kernel_lut: bool) -> Self {
+        self.options.kernel_lut = kernel_lut;
+        self
+    }
+
+    /// Sets [`Options::multi_step`]
+    #[inline]
+    pub fn multi_step(mut self, multi_step: bool) -> Self {
+        self.options.multi_step = multi_step;
+        self
+    }
+
+    /// Sets [`Options::flip_horizontal`] and [`Options::flip_vertical`]
+    #[inline]
+    pub fn flip(mut self, horizontal: bool, vertical: bool) -> Self {
+        self.options.flip_horizontal = horizontal;
+        self.options.flip_vertical = vertical;
+        self
+    }
+
+    /// Sets [`Options::rotation`]
+    #[inline]
+    pub fn rotation(mut self, rotation: Rotation) -> Self {
+        self.options.rotation = rotation;
+        self
+    }
+
+    /// Sets [`Options::threads`](crate::Options::threads)
+    #[inline]
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.options.threads = threads;
+        self
+    }
+
+    /// Create the resizer. Fails with [`Error::InvalidParameters`](crate::Error::InvalidParameters) on invalid settings.
+    #[inline]
+    pub fn construct(self, pixel_format: Format) -> Result<Resizer<Format>> {
+        self.construct_inner(pixel_format, None)
+    }
+
+    /// Create the resizer, sharing coefficients through the `cache`, see [`Resizer::with_cache`]
+    #[inline]
+    pub fn construct_with_cache(self, pixel_format: Format, cache: &mut CoefficientCache) -> Result<Resizer<Format>> {
+        self.construct_inner(pixel_format, Some(cache))
+    }
+
+    fn construct_inner(self, pixel_format: Format, cache: Option<&mut CoefficientCache>) -> Result<Resizer<Format>> {
+        let ((w1, h1), (w2, h2)) = (self.source, self.dest);
+        let vertical = self.vertical.as_ref().unwrap_or(&self.horizontal);
+        Resizer::with_filters_cached(w1, h1, w2, h2, pixel_format, &self.horizontal, vertical, &self.options, cache)
+    }
+}
+
+impl<Format> core::fmt::Debug for ResizerBuilder<Format> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ResizerBuilder")
+            .field("source", &self.source)
+            .field("dest", &self.dest)
+            .field("options", &self.options)
+            .finish()
+    }
+}
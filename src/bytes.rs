@@ -0,0 +1,39 @@
+//! Safe reinterpretation of raw bytes as pixels, enabled with the `bytemuck` feature.
+//!
+//! Decoders usually output `&[u8]` even for 16-bit or float images. These check length and alignment
+//! instead of requiring an `unsafe` cast.
+use crate::{Error, PixelFormat, Resizer, Result};
+use bytemuck::Pod;
+
+/// Reinterpret bytes as pixels, e.g. `pixels::<RGB16>(bytes)`.
+///
+/// Fails with [`Error::InvalidParameters`] if the length isn't a multiple of the pixel size,
+/// or if `bytes` isn't aligned for the pixel's component type.
+#[inline]
+pub fn pixels<P: Pod>(bytes: &[u8]) -> Result<&[P]> {
+    bytemuck::try_cast_slice(bytes).map_err(|_| Error::InvalidParameters)
+}
+
+/// Reinterpret mutable bytes as pixels, see [`pixels`]
+#[inline]
+pub fn pixels_mut<P: Pod>(bytes: &mut [u8]) -> Result<&mut [P]> {
+    bytemuck::try_cast_slice_mut(bytes).map_err(|_| Error::InvalidParameters)
+}
+
+impl<Format: PixelFormat> Resizer<Format> where Format::InputPixel: Pod, Format::OutputPixel: Pod {
+    /// Resize raw bytes of `src` image into raw bytes of `dst`.
+    ///
+    /// Buffers of 16-bit and float formats must be aligned, see [`pixels`].
+    ///
+    /// ```rust
+    /// let src = vec![0u8; 64 * 48 * 3];
+    /// let mut dst = vec![0u8; 16 * 12 * 3];
+    /// let mut resizer = resize::new(64, 48, 16, 12, resize::Pixel::RGB8, resize::Type::Lanczos3)?;
+    /// resizer.resize_bytes(&src, &mut dst)?;
+    /// # Ok::<_, resize::Error>(())
+    /// ```
+    #[inline]
+    pub fn resize_bytes(&mut self, src: &[u8], dst: &mut [u8]) -> Result<()> {
+        self.resize(pixels(src)?, pixels_mut(dst)?)
+    }
+}
@@ -0,0 +1,150 @@
+use crate::{CoeffsLine, Edge, Alignment, Options, Result, Type, Window};
+use fallible_collections::TryHashMap;
+use std::vec::Vec;
+
+/// Coefficients shared between resizers, so that resizers for recurring dimensions don't compute them again.
+///
+/// Pass it to [`Resizer::with_cache`](crate::Resizer::with_cache). It's keyed by sizes, filter and options of each axis.
+/// [`Type::Custom`] filters can't be compared, so they aren't cached.
+pub struct CoefficientCache {
+    axes: TryHashMap<AxisKey, Vec<CoeffsLine>>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct AxisKey {
+    s1: usize,
+    s2: usize,
+    filter: FilterKey,
+    edge: Edge,
+    alignment: Alignment,
+    offset: u64,
+    sharpen: u32,
+    kernel_lut: bool,
+}
+
+impl AxisKey {
+    pub(crate) fn new(s1: usize, s2: usize, filter: &Type, options: &Options, offset: f64) -> Option<Self> {
+        Some(Self::with_filter(s1, s2, FilterKey::new(filter)?, options, offset))
+    }
+
+    pub(crate) fn with_filter(s1: usize, s2: usize, filter: FilterKey, options: &Options, offset: f64) -> Self {
+        Self {
+            s1,
+            s2,
+            filter,
+            edge: options.edge,
+            alignment: options.alignment,
+            offset: offset.to_bits(),
+            sharpen: options.sharpen.to_bits(),
+            kernel_lut: options.kernel_lut,
+        }
+    }
+}
+
+/// Variant and parameters of a filter, which unlike [`Type`] can be copied and compared.
+/// Closures of [`Type::Custom`] can't, so they don't have a key.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct FilterKey(u8, [u32; 2]);
+
+impl FilterKey {
+    pub(crate) fn new(filter: &Type) -> Option<Self> {
+        let (tag, params) = match *filter {
+            Type::Point => (0, [0, 0]),
+            Type::Triangle => (1, [0, 0]),
+            Type::Catrom => (2, [0, 0]),
+            Type::Mitchell => (3, [0, 0]),
+            Type::BSpline => (4, [0, 0]),
+            Type::Hermite => (5, [0, 0]),
+            Type::Cubic { b, c } => (6, [b.to_bits(), c.to_bits()]),
+            Type::Lanczos3 => (7, [0, 0]),
+            Type::Gaussian(sigma) => (8, [sigma.to_bits(), 0]),
+            Type::WindowedSinc(window, radius) => (9, [window as u32, radius.to_bits()]),
+            Type::Spline16 => (10, [0, 0]),
+            Type::Spline36 => (11, [0, 0]),
+            Type::Spline64 => (12, [0, 0]),
+            Type::MagicKernelSharp2013 => (13, [0, 0]),
+            Type::MagicKernelSharp2021 => (14, [0, 0]),
+            Type::Area => (15, [0, 0]),
+            Type::Custom(_) => return None,
+        };
+        Some(Self(tag, params))
+    }
+
+    /// The filter this key has been made from
+    pub(crate) fn to_type(self) -> Type {
+        let Self(tag, [a, b]) = self;
+        match tag {
+            0 => Type::Point,
+            1 => Type::Triangle,
+            2 => Type::Catrom,
+            3 => Type::Mitchell,
+            4 => Type::BSpline,
+            5 => Type::Hermite,
+            6 => Type::Cubic { b: f32::from_bits(a), c: f32::from_bits(b) },
+            7 => Type::Lanczos3,
+            8 => Type::Gaussian(f32::from_bits(a)),
+            9 => Type::WindowedSinc(match a {
+                0 => Window::Hann,
+                1 => Window::Hamming,
+                _ => Window::Blackman,
+            }, f32::from_bits(b)),
+            10 => Type::Spline16,
+            11 => Type::Spline36,
+            12 => Type::Spline64,
+            13 => Type::MagicKernelSharp2013,
+            14 => Type::MagicKernelSharp2021,
+            _ => Type::Area,
+        }
+    }
+}
+
+impl CoefficientCache {
+    /// Create an empty cache
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self { axes: TryHashMap::default() }
+    }
+
+    /// Number of cached axes
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.axes.len()
+    }
+
+    /// Whether nothing has been cached yet
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.axes.len() == 0
+    }
+
+    /// Forget all cached coefficients
+    #[inline]
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    pub(crate) fn get(&self, key: &AxisKey) -> Option<Vec<CoeffsLine>> {
+        self.axes.get(key).cloned()
+    }
+
+    pub(crate) fn insert(&mut self, key: AxisKey, lines: Vec<CoeffsLine>) -> Result<()> {
+        self.axes.insert(key, lines)?;
+        Ok(())
+    }
+}
+
+impl Default for CoefficientCache {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl core::fmt::Debug for CoefficientCache {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("CoefficientCache").field("len", &self.len()).finish()
+    }
+}
@@ -0,0 +1,182 @@
+//! C API, enabled with the `capi` feature.
+//!
+//! ```c
+//! ResizeHandle *r = resize_new(640, 480, 320, 240, RESIZE_PIXEL_FORMAT_RGB8, RESIZE_FILTER_LANCZOS3);
+//! if (!r) { /* invalid parameters or out of memory */ }
+//! int err = resize_run(r, src, src_stride_bytes, dst, dst_stride_bytes);
+//! resize_free(r);
+//! ```
+//!
+//! Strides are in bytes, and must be a multiple of the pixel size.
+//! Buffers of 16-bit and float formats must be aligned to their component type.
+use crate::{image_len, Error, PixelFormat, Resizer, Result, Type};
+use core::ffi::c_int;
+use core::mem::{align_of, size_of};
+use fallible_collections::FallibleVec;
+use std::boxed::Box;
+use std::vec::Vec;
+
+/// Layout of pixels in buffers passed to [`resize_run`]
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum ResizePixelFormat {
+    Gray8,
+    Gray16,
+    GrayF32,
+    GrayAlpha8,
+    GrayAlpha8P,
+    GrayAlpha16,
+    RGB8,
+    RGB16,
+    RGBF32,
+    RGBA8,
+    RGBA8P,
+    RGBA16,
+    RGBA16P,
+    RGBAF32,
+    BGR8,
+    BGRA8,
+    BGRA8P,
+}
+
+/// Resampling filter, see [`Type`]
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum ResizeFilter {
+    Point,
+    Triangle,
+    Catrom,
+    Mitchell,
+    Lanczos3,
+    Area,
+}
+
+/// Returned by [`resize_run`] on success
+pub const RESIZE_OK: c_int = 0;
+/// Returned by [`resize_run`] when dimensions, strides or pointers are wrong
+pub const RESIZE_ERROR_INVALID_PARAMETERS: c_int = 1;
+/// Returned by [`resize_run`] when an allocation failed
+pub const RESIZE_ERROR_OUT_OF_MEMORY: c_int = 2;
+
+/// Opaque resizer created by [`resize_new`]
+pub struct ResizeHandle(Box<dyn Run>);
+
+trait Run {
+    unsafe fn run(&mut self, src: *const u8, src_stride: usize, dst: *mut u8, dst_stride: usize) -> Result<()>;
+}
+
+struct Handle<F: PixelFormat> {
+    resizer: Resizer<F>,
+    /// Destination pixels before they're copied to rows of a padded `dst`
+    out: Vec<F::OutputPixel>,
+}
+
+/// Stride in pixels, if the byte stride is usable for a buffer at `ptr`
+fn pixel_stride<P>(ptr: *const u8, stride: usize, width: usize) -> Result<usize> {
+    if ptr.is_null() || !(ptr as usize).is_multiple_of(align_of::<P>()) || !stride.is_multiple_of(size_of::<P>()) || stride / size_of::<P>() < width {
+        return Err(Error::InvalidParameters);
+    }
+    Ok(stride / size_of::<P>())
+}
+
+impl<F: PixelFormat> Run for Handle<F> where F::OutputPixel: Copy + Default {
+    unsafe fn run(&mut self, src: *const u8, src_stride: usize, dst: *mut u8, dst_stride: usize) -> Result<()> {
+        let (w1, h1) = self.resizer.source_dimensions();
+        let (w2, h2) = self.resizer.scale.dest_dimensions();
+        let src_stride = pixel_stride::<F::InputPixel>(src, src_stride, w1)?;
+        let dst_stride = pixel_stride::<F::OutputPixel>(dst, dst_stride, w2)?;
+        let src_len = image_len(w1, h1, src_stride).ok_or(Error::InvalidParameters)?;
+        let dst_len = image_len(w2, h2, dst_stride).ok_or(Error::InvalidParameters)?;
+        let src = core::slice::from_raw_parts(src.cast::<F::InputPixel>(), src_len);
+        let dst = core::slice::from_raw_parts_mut(dst.cast::<F::OutputPixel>(), dst_len);
+        if dst_stride == w2 {
+            return self.resizer.resize_stride(src, src_stride, dst);
+        }
+        if self.out.is_empty() {
+            FallibleVec::try_reserve(&mut self.out, w2 * h2)?;
+            self.out.resize(w2 * h2, F::OutputPixel::default());
+        }
+        self.resizer.resize_stride(src, src_stride, &mut self.out)?;
+        for (dst_row, row) in dst.chunks_mut(dst_stride).zip(self.out.chunks_exact(w2)) {
+            dst_row[..w2].copy_from_slice(row);
+        }
+        Ok(())
+    }
+}
+
+fn handle<F: PixelFormat + 'static>(w1: usize, h1: usize, w2: usize, h2: usize, pixel_format: F, filter: Type) -> Result<Box<dyn Run>>
+where F::OutputPixel: Copy + Default {
+    Ok(Box::new(Handle { resizer: Resizer::new(w1, h1, w2, h2, pixel_format, filter)?, out: Vec::new() }))
+}
+
+/// Create a resizer from `w1`×`h1` to `w2`×`h2` pixels. Returns `NULL` on invalid parameters or when out of memory.
+///
+/// Free it with [`resize_free`].
+#[no_mangle]
+pub extern "C" fn resize_new(w1: usize, h1: usize, w2: usize, h2: usize, format: ResizePixelFormat, filter: ResizeFilter) -> *mut ResizeHandle {
+    use crate::Pixel;
+    let filter = match filter {
+        ResizeFilter::Point => Type::Point,
+        ResizeFilter::Triangle => Type::Triangle,
+        ResizeFilter::Catrom => Type::Catrom,
+        ResizeFilter::Mitchell => Type::Mitchell,
+        ResizeFilter::Lanczos3 => Type::Lanczos3,
+        ResizeFilter::Area => Type::Area,
+    };
+    let handle = match format {
+        ResizePixelFormat::Gray8 => handle(w1, h1, w2, h2, Pixel::Gray8, filter),
+        ResizePixelFormat::Gray16 => handle(w1, h1, w2, h2, Pixel::Gray16, filter),
+        ResizePixelFormat::GrayF32 => handle(w1, h1, w2, h2, Pixel::GrayF32, filter),
+        ResizePixelFormat::GrayAlpha8 => handle(w1, h1, w2, h2, Pixel::GrayAlpha8, filter),
+        ResizePixelFormat::GrayAlpha8P => handle(w1, h1, w2, h2, Pixel::GrayAlpha8P, filter),
+        ResizePixelFormat::GrayAlpha16 => handle(w1, h1, w2, h2, Pixel::GrayAlpha16, filter),
+        ResizePixelFormat::RGB8 => handle(w1, h1, w2, h2, Pixel::RGB8, filter),
+        ResizePixelFormat::RGB16 => handle(w1, h1, w2, h2, Pixel::RGB16, filter),
+        ResizePixelFormat::RGBF32 => handle(w1, h1, w2, h2, Pixel::RGBF32, filter),
+        ResizePixelFormat::RGBA8 => handle(w1, h1, w2, h2, Pixel::RGBA8, filter),
+        ResizePixelFormat::RGBA8P => handle(w1, h1, w2, h2, Pixel::RGBA8P, filter),
+        ResizePixelFormat::RGBA16 => handle(w1, h1, w2, h2, Pixel::RGBA16, filter),
+        ResizePixelFormat::RGBA16P => handle(w1, h1, w2, h2, Pixel::RGBA16P, filter),
+        ResizePixelFormat::RGBAF32 => handle(w1, h1, w2, h2, Pixel::RGBAF32, filter),
+        ResizePixelFormat::BGR8 => handle(w1, h1, w2, h2, Pixel::BGR8, filter),
+        ResizePixelFormat::BGRA8 => handle(w1, h1, w2, h2, Pixel::BGRA8, filter),
+        ResizePixelFormat::BGRA8P => handle(w1, h1, w2, h2, Pixel::BGRA8P, filter),
+    };
+    match handle {
+        Ok(handle) => Box::into_raw(Box::new(ResizeHandle(handle))),
+        Err(_) => core::ptr::null_mut(),
+    }
+}
+
+/// Resize the image at `src` into `dst`. Returns [`RESIZE_OK`], or one of the `RESIZE_ERROR_*` codes.
+///
+/// # Safety
+///
+/// `handle` must come from [`resize_new`]. `src` must point to `h1` rows of `src_stride` bytes,
+/// and `dst` to `h2` rows of `dst_stride` bytes (the last row may be only as long as the pixels in it).
+#[no_mangle]
+pub unsafe extern "C" fn resize_run(handle: *mut ResizeHandle, src: *const u8, src_stride: usize, dst: *mut u8, dst_stride: usize) -> c_int {
+    let handle = match handle.as_mut() {
+        Some(handle) => handle,
+        None => return RESIZE_ERROR_INVALID_PARAMETERS,
+    };
+    match handle.0.run(src, src_stride, dst, dst_stride) {
+        Ok(()) => RESIZE_OK,
+        Err(Error::InvalidParameters) => RESIZE_ERROR_INVALID_PARAMETERS,
+        Err(Error::OutOfMemory) => RESIZE_ERROR_OUT_OF_MEMORY,
+    }
+}
+
+/// Free a resizer created by [`resize_new`]. `NULL` is ignored.
+///
+/// # Safety
+///
+/// `handle` must come from [`resize_new`], and can't be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn resize_free(handle: *mut ResizeHandle) {
+    if !handle.is_null() {
+        drop(Box::from_raw(handle));
+    }
+}
@@ -0,0 +1,299 @@
+use crate::px::FixedPointFormat;
+use crate::{cos, floor, image_len, sin, Error, Result};
+use core::f64::consts::PI;
+use core::num::NonZeroUsize;
+use fallible_collections::FallibleVec;
+use std::vec::Vec;
+
+/// Resampling filters of Pillow's `Image.resize`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PillowFilter {
+    /// `Image.BOX`
+    Box,
+    /// `Image.BILINEAR`
+    Bilinear,
+    /// `Image.HAMMING`
+    Hamming,
+    /// `Image.BICUBIC`, which is the default
+    Bicubic,
+    /// `Image.LANCZOS`
+    Lanczos,
+}
+
+/// Interpolation modes of OpenCV's `cv::resize`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum OpenCvInterpolation {
+    /// `INTER_LINEAR`, which is the default
+    Linear,
+    /// `INTER_CUBIC`
+    Cubic,
+}
+
+/// Resampler for 8-bit pixels that replicates the kernels, coordinate mapping and integer rounding of other libraries,
+/// so that outputs match theirs exactly, e.g. to reproduce preprocessing that a model has been trained with.
+///
+/// * [`CompatResizer::pillow`] matches `Image.resize` of Pillow without `box` or `reducing_gap`.
+///   Pillow premultiplies `RGBA` images before resizing them, so only `RGBa` and `RGBX` data match.
+/// * [`CompatResizer::opencv`] matches `cv::resize` of OpenCV, including the truncating vertical pass of `INTER_LINEAR`
+///   that its 8-bit code uses.
+///
+/// ```rust
+/// use resize::{CompatResizer, Pixel::RGB8, PillowFilter};
+/// use rgb::FromSlice;
+/// let src = vec![0u8; 64 * 48 * 3];
+/// let mut dst = vec![0u8; 32 * 24 * 3];
+/// let mut resizer = CompatResizer::pillow(64, 48, 32, 24, RGB8, PillowFilter::Bicubic)?;
+/// resizer.resize(src.as_rgb(), dst.as_rgb_mut())?;
+/// # Ok::<_, resize::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct CompatResizer<Format: FixedPointFormat> {
+    w1: NonZeroUsize,
+    h1: NonZeroUsize,
+    coeffs_w: Vec<IntLine>,
+    coeffs_h: Vec<IntLine>,
+    /// Pillow rounds and clamps results of the first pass to 8 bits, OpenCV keeps them whole
+    round_intermediate: bool,
+    /// OpenCV's `INTER_LINEAR` drops low bits of every product of the vertical pass
+    truncate_vertical: bool,
+    tmp: Vec<Format::Accumulator>,
+}
+
+#[derive(Debug, Clone)]
+struct IntLine {
+    /// First tap, which may be before the first row when edge rows are repeated
+    start: isize,
+    coeffs: Vec<i32>,
+}
+
+/// Fractional bits of the result of both passes
+const OUTPUT_BITS: u32 = 22;
+/// Fractional bits of OpenCV's weights, which are squared by the two passes
+const OPENCV_COEFF_BITS: u32 = 11;
+
+impl<Format: FixedPointFormat> CompatResizer<Format> {
+    /// Create a resizer that matches Pillow's `Image.resize` with the given filter.
+    pub fn pillow(source_width: usize, source_heigth: usize, dest_width: usize, dest_height: usize, _pixel_format: Format, filter: PillowFilter) -> Result<Self> {
+        Self::with_lines(source_width, source_heigth,
+            pillow_lines(source_width, dest_width, filter)?, pillow_lines(source_heigth, dest_height, filter)?, true, false)
+    }
+
+    /// Create a resizer that matches OpenCV's `cv::resize` with the given interpolation.
+    pub fn opencv(source_width: usize, source_heigth: usize, dest_width: usize, dest_height: usize, _pixel_format: Format, interpolation: OpenCvInterpolation) -> Result<Self> {
+        Self::with_lines(source_width, source_heigth,
+            opencv_lines(source_width, dest_width, interpolation, true)?, opencv_lines(source_heigth, dest_height, interpolation, false)?,
+            false, interpolation == OpenCvInterpolation::Linear)
+    }
+
+    fn with_lines(source_width: usize, source_heigth: usize, coeffs_w: Vec<IntLine>, coeffs_h: Vec<IntLine>, round_intermediate: bool, truncate_vertical: bool) -> Result<Self> {
+        Ok(Self {
+            w1: NonZeroUsize::new(source_width).ok_or(Error::InvalidParameters)?,
+            h1: NonZeroUsize::new(source_heigth).ok_or(Error::InvalidParameters)?,
+            coeffs_w,
+            coeffs_h,
+            round_intermediate,
+            truncate_vertical,
+            tmp: Vec::new(),
+        })
+    }
+
+    /// Resize `src` image data into `dst`.
+    #[inline]
+    pub fn resize(&mut self, src: &[Format::Pixel], dst: &mut [Format::Pixel]) -> Result<()> {
+        self.resize_internal(src, self.w1, dst)
+    }
+
+    /// Resize `src` image data into `dst`, skipping `stride` pixels each row.
+    #[inline]
+    pub fn resize_stride(&mut self, src: &[Format::Pixel], src_stride: usize, dst: &mut [Format::Pixel]) -> Result<()> {
+        let src_stride = NonZeroUsize::new(src_stride).ok_or(Error::InvalidParameters)?;
+        self.resize_internal(src, src_stride, dst)
+    }
+
+    fn resize_internal(&mut self, src: &[Format::Pixel], stride: NonZeroUsize, dst: &mut [Format::Pixel]) -> Result<()> {
+        let (w1, h1, w2, h2) = (self.w1.get(), self.h1.get(), self.coeffs_w.len(), self.coeffs_h.len());
+        let tmp_len = image_len(w2, h1, w2).ok_or(Error::InvalidParameters)?;
+        if stride.get() < w1 || !matches!(image_len(w1, h1, stride.get()), Some(len) if src.len() >= len) || image_len(w2, h2, w2) != Some(dst.len()) {
+            return Err(Error::InvalidParameters);
+        }
+        if let Some(missing) = tmp_len.checked_sub(self.tmp.len()).filter(|&n| n > 0) {
+            FallibleVec::try_reserve(&mut self.tmp, missing)?;
+            self.tmp.resize(tmp_len, Format::new());
+        }
+
+        let round_intermediate = self.round_intermediate;
+        for (src_row, tmp_row) in src.chunks(stride.get()).zip(self.tmp[..tmp_len].chunks_exact_mut(w2)) {
+            for (tmp_px, col) in tmp_row.iter_mut().zip(&self.coeffs_w) {
+                let mut accum = Format::new();
+                if round_intermediate {
+                    accum.as_mut().iter_mut().for_each(|c| *c = 1 << (OUTPUT_BITS - 1));
+                }
+                let start = col.start as usize;
+                for (&coeff, &px) in col.coeffs.iter().zip(&src_row[start..start + col.coeffs.len()]) {
+                    Format::add(&mut accum, px, coeff);
+                }
+                if round_intermediate {
+                    accum.as_mut().iter_mut().for_each(|c| *c = (*c >> OUTPUT_BITS).clamp(0, 255));
+                }
+                *tmp_px = accum;
+            }
+        }
+
+        let truncate_vertical = self.truncate_vertical;
+        let last = h1 as isize - 1;
+        for (row, dst_row) in self.coeffs_h.iter().zip(dst.chunks_exact_mut(w2)) {
+            for (col, dst_px) in dst_row.iter_mut().enumerate() {
+                let mut accum = Format::new();
+                if !truncate_vertical {
+                    accum.as_mut().iter_mut().for_each(|c| *c = 1 << (OUTPUT_BITS - 1));
+                }
+                for (k, &coeff) in row.coeffs.iter().enumerate() {
+                    let y = (row.start + k as isize).clamp(0, last) as usize;
+                    for (c, &inp) in accum.as_mut().iter_mut().zip(self.tmp[w2 * y + col].as_ref()) {
+                        if truncate_vertical {
+                            // Like `VResizeLinearVec_32s8u`, which multiplies the high halves of 16-bit values
+                            *c += (coeff * (inp >> 4)) >> 16;
+                        } else {
+                            *c += inp * coeff;
+                        }
+                    }
+                }
+                if truncate_vertical {
+                    accum.as_mut().iter_mut().for_each(|c| *c = ((*c + 2) >> 2).clamp(0, 255));
+                } else {
+                    accum.as_mut().iter_mut().for_each(|c| *c = (*c >> OUTPUT_BITS).clamp(0, 255));
+                }
+                *dst_px = Format::into_pixel(accum);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl PillowFilter {
+    fn support(self) -> f64 {
+        match self {
+            Self::Box => 0.5,
+            Self::Bilinear | Self::Hamming => 1.,
+            Self::Bicubic => 2.,
+            Self::Lanczos => 3.,
+        }
+    }
+
+    fn kernel(self, x: f64) -> f64 {
+        let sinc = |x: f64| if x == 0. { 1. } else { unsafe { sin(x * PI) / (x * PI) } };
+        match self {
+            Self::Box => if x > -0.5 && x <= 0.5 { 1. } else { 0. },
+            Self::Bilinear => (1. - x.abs()).max(0.),
+            Self::Hamming => {
+                let x = x.abs();
+                if x == 0. { 1. } else if x >= 1. { 0. } else { unsafe { sin(x * PI) / (x * PI) * (0.54 + 0.46 * cos(x * PI)) } }
+            },
+            Self::Bicubic => {
+                let (a, x) = (-0.5, x.abs());
+                if x < 1. {
+                    ((a + 2.) * x - (a + 3.)) * x * x + 1.
+                } else if x < 2. {
+                    (((x - 5.) * x + 8.) * x - 4.) * a
+                } else {
+                    0.
+                }
+            },
+            Self::Lanczos => if (-3. ..3.).contains(&x) { sinc(x) * sinc(x / 3.) } else { 0. },
+        }
+    }
+}
+
+/// Weights computed in double precision like `precompute_coeffs` of Pillow, and rounded away from zero to 22 bits
+fn pillow_lines(in_size: usize, out_size: usize, filter: PillowFilter) -> Result<Vec<IntLine>> {
+    if in_size == 0 || out_size == 0 {
+        return Err(Error::InvalidParameters);
+    }
+    let scale = in_size as f64 / out_size as f64;
+    let filterscale = scale.max(1.);
+    let support = filter.support() * filterscale;
+    let mut lines: Vec<IntLine> = FallibleVec::try_with_capacity(out_size)?;
+    for i in 0..out_size {
+        let center = (i as f64 + 0.5) * scale;
+        // Truncated towards zero, like C casts
+        let start = ((center - support + 0.5) as isize).max(0) as usize;
+        let end = ((center + support + 0.5) as usize).min(in_size);
+        let mut weights: Vec<f64> = FallibleVec::try_with_capacity(end.saturating_sub(start))?;
+        weights.extend((start..end).map(|x| filter.kernel((x as f64 - center + 0.5) * (1. / filterscale))));
+        let sum: f64 = weights.iter().sum();
+        let mut coeffs: Vec<i32> = FallibleVec::try_with_capacity(weights.len())?;
+        coeffs.extend(weights.iter().map(|&w| {
+            let w = if sum != 0. { w / sum } else { w };
+            (w * f64::from(1 << OUTPUT_BITS) + if w < 0. { -0.5 } else { 0.5 }) as i32
+        }));
+        lines.push(IntLine { start: start as isize, coeffs });
+    }
+    Ok(lines)
+}
+
+/// Weights computed in single precision like `cv::resize`, and rounded to 11 bits.
+/// OpenCV moves taps that fall off the left and right edges of the image onto the edge pixel,
+/// but only repeats edge rows vertically, which rounds differently.
+fn opencv_lines(in_size: usize, out_size: usize, interpolation: OpenCvInterpolation, clamp_edges: bool) -> Result<Vec<IntLine>> {
+    if in_size == 0 || out_size == 0 {
+        return Err(Error::InvalidParameters);
+    }
+    let last = in_size as isize - 1;
+    let scale = 1. / (out_size as f64 / in_size as f64);
+    let mut lines: Vec<IntLine> = FallibleVec::try_with_capacity(out_size)?;
+    for i in 0..out_size {
+        let mut fraction = ((i as f64 + 0.5) * scale - 0.5) as f32;
+        let mut pos = unsafe { floor(f64::from(fraction)) } as isize;
+        fraction -= pos as f32;
+        let (first, weights) = match interpolation {
+            OpenCvInterpolation::Linear => {
+                if clamp_edges && (pos < 0 || pos >= last) {
+                    pos = pos.clamp(0, last);
+                    fraction = 0.;
+                }
+                (pos, [1. - fraction, fraction, 0., 0.])
+            },
+            OpenCvInterpolation::Cubic => {
+                const A: f32 = -0.75;
+                let x = fraction;
+                let c0 = ((A * (x + 1.) - 5. * A) * (x + 1.) + 8. * A) * (x + 1.) - 4. * A;
+                let c1 = ((A + 2.) * x - (A + 3.)) * x * x + 1.;
+                let c2 = ((A + 2.) * (1. - x) - (A + 3.)) * (1. - x) * (1. - x) + 1.;
+                (pos - 1, [c0, c1, c2, 1. - c0 - c1 - c2])
+            },
+        };
+        let taps = match interpolation {
+            OpenCvInterpolation::Linear => 2,
+            OpenCvInterpolation::Cubic => 4,
+        };
+        let weights = weights[..taps].iter().map(|&w| cv_round(w * (1 << OPENCV_COEFF_BITS) as f32));
+        if !clamp_edges {
+            // Rows past the edges are repeated by the vertical pass
+            let mut coeffs: Vec<i32> = FallibleVec::try_with_capacity(taps)?;
+            coeffs.extend(weights);
+            lines.push(IntLine { start: first, coeffs });
+            continue;
+        }
+        // Taps past the edges are folded onto the edge pixels they repeat
+        let start = first.clamp(0, last);
+        let end = (first + taps as isize - 1).clamp(0, last);
+        let mut coeffs: Vec<i32> = FallibleVec::try_with_capacity((end - start + 1) as usize)?;
+        coeffs.resize((end - start + 1) as usize, 0);
+        for (k, w) in weights.enumerate() {
+            coeffs[((first + k as isize).clamp(0, last) - start) as usize] += w;
+        }
+        lines.push(IntLine { start, coeffs });
+    }
+    Ok(lines)
+}
+
+/// Rounds to the nearest integer, with ties to even like `cvRound`
+fn cv_round(value: f32) -> i32 {
+    let value = f64::from(value);
+    let rounded = unsafe { floor(value + 0.5) };
+    if rounded - value == 0.5 && rounded % 2. != 0. {
+        rounded as i32 - 1
+    } else {
+        rounded as i32
+    }
+}
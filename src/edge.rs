@@ -0,0 +1,177 @@
+use crate::px::Components;
+use crate::{check_area, image_len, Error, PixelFormat, Resizer, Result, Type};
+use core::num::NonZeroUsize;
+use fallible_collections::FallibleVec;
+use std::boxed::Box;
+use std::vec::Vec;
+
+/// Ratio of gradients above which the edge direction is considered certain
+const EDGE_THRESHOLD: f32 = 1.15;
+
+/// Upscaler that follows the direction of edges, for enlarging photos 2× without jagged diagonals.
+///
+/// Exact 2× enlargements use Directional Cubic Convolution Interpolation (DCCI): new pixels are interpolated
+/// along the local edge direction when there's a clear one, and blended from both directions otherwise.
+/// Source pixels are kept as they are, at even coordinates of the destination, like with [`Alignment::Asymmetric`](crate::Alignment::Asymmetric).
+///
+/// Other sizes fall back to a regular [`Resizer`] with the given filter.
+///
+/// Edge directions are found from the sum of components of each pixel, so the format's accumulator must implement [`Components`].
+pub struct EdgeDirectedResizer<Format: PixelFormat> {
+    mode: Mode<Format>,
+    /// Destination in accumulators, and brightness of its pixels used for finding edges
+    grid: Vec<Format::Accumulator>,
+    luma: Vec<f32>,
+}
+
+enum Mode<Format: PixelFormat> {
+    Double { w1: NonZeroUsize, h1: usize, pix_fmt: Format },
+    Fallback(Box<Resizer<Format>>),
+}
+
+impl<Format: PixelFormat> EdgeDirectedResizer<Format> where Format::Accumulator: Components {
+    /// Create a new resizer, which uses `fallback_filter` unless the destination is exactly twice the size of the source
+    pub fn new(source_width: usize, source_heigth: usize, dest_width: usize, dest_height: usize, pixel_format: Format, fallback_filter: Type) -> Result<Self> {
+        let doubles = source_width.checked_mul(2) == Some(dest_width) && source_heigth.checked_mul(2) == Some(dest_height);
+        let mode = match NonZeroUsize::new(source_width) {
+            Some(w1) if doubles && source_heigth > 0 => {
+                check_area(dest_width, dest_height)?;
+                Mode::Double { w1, h1: source_heigth, pix_fmt: pixel_format }
+            },
+            _ => Mode::Fallback(Box::new(Resizer::new(source_width, source_heigth, dest_width, dest_height, pixel_format, fallback_filter)?)),
+        };
+        Ok(Self { mode, grid: Vec::new(), luma: Vec::new() })
+    }
+
+    /// Whether the edge-directed interpolation is used, rather than the fallback filter
+    #[inline]
+    pub fn is_edge_directed(&self) -> bool {
+        matches!(self.mode, Mode::Double { .. })
+    }
+
+    /// Resize `src` image data into `dst`.
+    #[inline]
+    pub fn resize(&mut self, src: &[Format::InputPixel], dst: &mut [Format::OutputPixel]) -> Result<()> {
+        match &mut self.mode {
+            Mode::Double { w1, .. } => {
+                let stride = w1.get();
+                self.resize_stride(src, stride, dst)
+            },
+            Mode::Fallback(resizer) => resizer.resize(src, dst),
+        }
+    }
+
+    /// Resize `src` image data into `dst`, skipping `stride` pixels each row.
+    pub fn resize_stride(&mut self, src: &[Format::InputPixel], src_stride: usize, dst: &mut [Format::OutputPixel]) -> Result<()> {
+        let Self { mode, grid, luma } = self;
+        let (w1, h1, pix_fmt) = match mode {
+            Mode::Double { w1, h1, pix_fmt } => (w1.get(), *h1, &*pix_fmt),
+            Mode::Fallback(resizer) => return resizer.resize_stride(src, src_stride, dst),
+        };
+        let (w2, h2) = (w1 * 2, h1 * 2);
+        if src_stride < w1 || !matches!(image_len(w1, h1, src_stride), Some(len) if src.len() >= len) || dst.len() != w2 * h2 {
+            return Err(Error::InvalidParameters);
+        }
+        grid.clear();
+        luma.clear();
+        FallibleVec::try_reserve(grid, dst.len())?;
+        FallibleVec::try_reserve(luma, dst.len())?;
+        grid.resize(dst.len(), Format::new());
+        luma.resize(dst.len(), 0.);
+
+        // Source pixels go to even coordinates
+        let mut max_luma = 0f32;
+        for (y, row) in src.chunks(src_stride).take(h1).enumerate() {
+            for (x, &px) in row[..w1].iter().enumerate() {
+                let i = 2 * y * w2 + 2 * x;
+                pix_fmt.add(&mut grid[i], px, 1.);
+                luma[i] = grid[i].components().iter().sum();
+                max_luma = max_luma.max(luma[i].abs());
+            }
+        }
+        // Keeps flat areas from having a direction, scaled like 1 is for 8-bit images
+        let epsilon = (max_luma / 255.).max(f32::MIN_POSITIVE);
+
+        // Centers of 2×2 source blocks, interpolated along diagonals of source pixels
+        let source = |x: isize, y: isize| 2 * (y.clamp(0, h1 as isize - 1) as usize) * w2 + 2 * (x.clamp(0, w1 as isize - 1) as usize);
+        for y in 0..h1 as isize {
+            for x in 0..w1 as isize {
+                let at = |dx: isize, dy: isize| luma[source(x + dx, y + dy)];
+                let mut rising = 0.;
+                let mut falling = 0.;
+                for a in -1..=1 {
+                    for b in -1..=1 {
+                        rising += (at(a, b + 1) - at(a + 1, b)).abs();
+                        falling += (at(a, b) - at(a + 1, b + 1)).abs();
+                    }
+                }
+                let along_rising = [source(x - 1, y + 2), source(x, y + 1), source(x + 1, y), source(x + 2, y - 1)];
+                let along_falling = [source(x - 1, y - 1), source(x, y), source(x + 1, y + 1), source(x + 2, y + 2)];
+                let i = (2 * y as usize + 1) * w2 + 2 * x as usize + 1;
+                interpolate::<Format>(grid, luma, i, [rising, falling], [along_rising, along_falling], epsilon);
+            }
+        }
+
+        // Remaining pixels have known neighbors above, below, left and right, interpolated along rows and columns
+        let reflect = |v: isize, len: usize| {
+            // Reflection keeps the parity of coordinates, so it only lands on known pixels
+            let period = 2 * (len as isize - 1);
+            let v = v.rem_euclid(period);
+            (if v >= len as isize { period - v } else { v }) as usize
+        };
+        for y in 0..h2 as isize {
+            for x in (1 - (y & 1)..w2 as isize).step_by(2) {
+                let at = |dx: isize, dy: isize| reflect(y + dy, h2) * w2 + reflect(x + dx, w2);
+                let brightness = |dx: isize, dy: isize| luma[at(dx, dy)];
+                let mut horizontal = 0.;
+                let mut vertical = 0.;
+                for (a, b) in [(-3, -2), (-1, -2), (1, -2), (-3, 0), (-1, 0), (1, 0), (-3, 2), (-1, 2), (1, 2), (-2, -1), (0, -1), (-2, 1), (0, 1)] {
+                    horizontal += (brightness(a, b) - brightness(a + 2, b)).abs();
+                    vertical += (brightness(b, a) - brightness(b, a + 2)).abs();
+                }
+                let along_row = [at(-3, 0), at(-1, 0), at(1, 0), at(3, 0)];
+                let along_column = [at(0, -3), at(0, -1), at(0, 1), at(0, 3)];
+                interpolate::<Format>(grid, luma, at(0, 0), [horizontal, vertical], [along_row, along_column], epsilon);
+            }
+        }
+
+        for (i, (out, &acc)) in dst.iter_mut().zip(grid.iter()).enumerate() {
+            pix_fmt.write_pixel(out, acc, i % w2, i / w2);
+        }
+        Ok(())
+    }
+}
+
+impl<Format: PixelFormat> core::fmt::Debug for EdgeDirectedResizer<Format> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("EdgeDirectedResizer")
+            .field("edge_directed", &matches!(self.mode, Mode::Double { .. }))
+            .finish()
+    }
+}
+
+/// Sets pixel `i` from 4 pixels along one of two directions, preferring the one with the smaller gradient
+fn interpolate<Format: PixelFormat>(grid: &mut [Format::Accumulator], luma: &mut [f32], i: usize, gradients: [f32; 2], along: [[usize; 4]; 2], epsilon: f32) {
+    let [g1, g2] = gradients.map(|g| g / epsilon + 1.);
+    let weights = if g1 > g2 * EDGE_THRESHOLD {
+        [0., 1.]
+    } else if g2 > g1 * EDGE_THRESHOLD {
+        [1., 0.]
+    } else {
+        let (w1, w2) = (1. / (g1 * g1 * g1 * g1 * g1), 1. / (g2 * g2 * g2 * g2 * g2));
+        [w1 / (w1 + w2), w2 / (w1 + w2)]
+    };
+    let mut acc = Format::new();
+    let mut sum = 0.;
+    for (along, weight) in along.iter().zip(weights) {
+        if weight == 0. {
+            continue;
+        }
+        for (&j, cubic) in along.iter().zip([-1. / 16., 9. / 16., 9. / 16., -1. / 16.]) {
+            Format::add_acc(&mut acc, grid[j], cubic * weight);
+            sum += luma[j] * cubic * weight;
+        }
+    }
+    grid[i] = acc;
+    luma[i] = sum;
+}
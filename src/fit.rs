@@ -0,0 +1,287 @@
+use crate::{image_len, Error, PixelFormat, Resizer, Result, Type};
+use core::convert::TryFrom;
+
+/// Resampler that scales an image to fit inside the destination keeping its aspect ratio,
+/// centers it, and fills the remaining borders with a background color.
+pub struct LetterboxResizer<Format: PixelFormat> {
+    resizer: Resizer<Format>,
+    width: usize,
+    height: usize,
+    x: usize,
+    y: usize,
+}
+
+impl<Format: PixelFormat> LetterboxResizer<Format> {
+    /// Create a resizer of `source_width`×`source_heigth` images into `dest_width`×`dest_height` ones.
+    pub fn new(source_width: usize, source_heigth: usize, dest_width: usize, dest_height: usize, pixel_format: Format, filter_type: Type) -> Result<Self> {
+        let (w, h) = fit_within(source_width, source_heigth, dest_width, dest_height)?;
+        Ok(Self {
+            resizer: Resizer::new(source_width, source_heigth, w, h, pixel_format, filter_type)?,
+            width: dest_width,
+            height: dest_height,
+            x: (dest_width - w) / 2,
+            y: (dest_height - h) / 2,
+        })
+    }
+
+    /// Position and size of the scaled image in the destination, as `(x, y, width, height)`
+    #[inline]
+    #[must_use]
+    pub fn placement(&self) -> (usize, usize, usize, usize) {
+        let (w, h) = self.resizer.scale.dest_dimensions();
+        (self.x, self.y, w, h)
+    }
+
+    /// Resize `src` image data into the middle of `dst`, and set the rest of `dst` to `fill`.
+    ///
+    /// ```rust
+    /// use resize::{LetterboxResizer, Pixel::Gray8, Type::Triangle};
+    /// use rgb::{alt::Gray, FromSlice};
+    /// let mut dst = [0u8; 4 * 4];
+    /// let mut resizer = LetterboxResizer::new(4, 2, 4, 4, Gray8, Triangle)?;
+    /// resizer.resize([9u8; 8].as_gray(), dst.as_gray_mut(), Gray(1))?;
+    /// assert_eq!(dst, [1, 1, 1, 1, 9, 9, 9, 9, 9, 9, 9, 9, 1, 1, 1, 1]);
+    /// # Ok::<_, resize::Error>(())
+    /// ```
+    pub fn resize(&mut self, src: &[Format::InputPixel], dst: &mut [Format::OutputPixel], fill: Format::OutputPixel) -> Result<()>
+    where Format::OutputPixel: Clone {
+        if image_len(self.width, self.height, self.width) != Some(dst.len()) {
+            return Err(Error::InvalidParameters);
+        }
+        let (x, y, w, h) = self.placement();
+        for (row_y, row) in dst.chunks_mut(self.width).enumerate() {
+            if (y..y + h).contains(&row_y) {
+                row[..x].fill(fill.clone());
+                row[x + w..].fill(fill.clone());
+            } else {
+                row.fill(fill.clone());
+            }
+        }
+        self.resizer.resize_into(src, dst, self.width, x, y)
+    }
+
+    /// The resizer of the scaled image
+    #[inline]
+    #[must_use]
+    pub fn resizer(&self) -> &Resizer<Format> {
+        &self.resizer
+    }
+}
+
+impl<Format: PixelFormat> core::fmt::Debug for LetterboxResizer<Format> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("LetterboxResizer")
+            .field("dest", &(self.width, self.height))
+            .field("placement", &self.placement())
+            .finish()
+    }
+}
+
+/// Resampler that scales an image to cover the whole destination keeping its aspect ratio, and crops the overflow.
+///
+/// The crop is centered by default, and can follow a [focal point](CoverResizer::focus) instead.
+pub struct CoverResizer<Format: PixelFormat> {
+    resizer: Resizer<Format>,
+    source_width: usize,
+    source_heigth: usize,
+    x: usize,
+    y: usize,
+}
+
+impl<Format: PixelFormat> CoverResizer<Format> {
+    /// Create a resizer of `source_width`×`source_heigth` images into `dest_width`×`dest_height` ones.
+    pub fn new(source_width: usize, source_heigth: usize, dest_width: usize, dest_height: usize, pixel_format: Format, filter_type: Type) -> Result<Self> {
+        let (w, h) = fit_within(dest_width, dest_height, source_width, source_heigth)?;
+        Ok(Self {
+            resizer: Resizer::new(w, h, dest_width, dest_height, pixel_format, filter_type)?,
+            source_width,
+            source_heigth,
+            x: (source_width - w) / 2,
+            y: (source_heigth - h) / 2,
+        })
+    }
+
+    /// Center the crop on a point of the source image, as far as the crop stays within the image.
+    ///
+    /// Coordinates are fractions of the source dimensions, so `(0.5, 0.5)` is the center, and `(0., 0.)` keeps the top-left corner.
+    #[must_use]
+    pub fn focus(mut self, x: f32, y: f32) -> Self {
+        let (w, h) = self.resizer.source_dimensions();
+        let place = |focus: f32, size: usize, crop: usize| {
+            let start = focus * size as f32 - crop as f32 / 2.;
+            ((start + 0.5).max(0.) as usize).min(size - crop)
+        };
+        self.x = place(x, self.source_width, w);
+        self.y = place(y, self.source_heigth, h);
+        self
+    }
+
+    /// Area of the source image that is scaled to the destination, as `(x, y, width, height)`
+    #[inline]
+    #[must_use]
+    pub fn crop(&self) -> (usize, usize, usize, usize) {
+        let (w, h) = self.resizer.source_dimensions();
+        (self.x, self.y, w, h)
+    }
+
+    /// Resize the cropped area of `src` image data into `dst`.
+    ///
+    /// ```rust
+    /// use resize::{CoverResizer, Pixel::Gray8, Type::Point};
+    /// use rgb::FromSlice;
+    /// let src = [1u8, 2, 3, 4, 5, 6, 7, 8];
+    /// let mut dst = [0u8; 2 * 2];
+    /// let mut resizer = CoverResizer::new(4, 2, 2, 2, Gray8, Point)?.focus(1., 0.);
+    /// resizer.resize(src.as_gray(), dst.as_gray_mut())?;
+    /// assert_eq!(dst, [3, 4, 7, 8]);
+    /// # Ok::<_, resize::Error>(())
+    /// ```
+    pub fn resize(&mut self, src: &[Format::InputPixel], dst: &mut [Format::OutputPixel]) -> Result<()> {
+        if !matches!(image_len(self.source_width, self.source_heigth, self.source_width), Some(len) if src.len() >= len) {
+            return Err(Error::InvalidParameters);
+        }
+        let src = &src[self.y * self.source_width + self.x..];
+        self.resizer.resize_stride(src, self.source_width, dst)
+    }
+
+    /// The resizer of the cropped area
+    #[inline]
+    #[must_use]
+    pub fn resizer(&self) -> &Resizer<Format> {
+        &self.resizer
+    }
+}
+
+impl<Format: PixelFormat> core::fmt::Debug for CoverResizer<Format> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("CoverResizer")
+            .field("source", &(self.source_width, self.source_heigth))
+            .field("crop", &self.crop())
+            .finish()
+    }
+}
+
+/// Destination dimensions computed from the source dimensions, for constructing a resizer.
+///
+/// ```rust
+/// use resize::Dimensions;
+/// assert_eq!(Dimensions::fit(200, 200).of(640, 480)?, (200, 150));
+/// assert_eq!(Dimensions::fill(200, 200).of(640, 480)?, (267, 200));
+/// assert_eq!(Dimensions::fit(2000, 2000).without_upscaling().of(640, 480)?, (640, 480));
+/// # Ok::<_, resize::Error>(())
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Dimensions {
+    mode: Mode,
+    width: usize,
+    height: usize,
+    upscale: bool,
+    /// Width and height of a source pixel
+    pixel_aspect: (usize, usize),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Mode {
+    Fit,
+    Fill,
+    Exact,
+}
+
+impl Dimensions {
+    /// Largest size that fits within `width`×`height` and keeps the aspect ratio, as for [`LetterboxResizer`]
+    #[inline]
+    #[must_use]
+    pub fn fit(width: usize, height: usize) -> Self {
+        Self { mode: Mode::Fit, width, height, upscale: true, pixel_aspect: (1, 1) }
+    }
+
+    /// Smallest size that covers `width`×`height` and keeps the aspect ratio. [`CoverResizer`] crops it to `width`×`height`.
+    #[inline]
+    #[must_use]
+    pub fn fill(width: usize, height: usize) -> Self {
+        Self { mode: Mode::Fill, width, height, upscale: true, pixel_aspect: (1, 1) }
+    }
+
+    /// Exactly `width`×`height`, stretching the image if the aspect ratio differs
+    #[inline]
+    #[must_use]
+    pub fn exact(width: usize, height: usize) -> Self {
+        Self { mode: Mode::Exact, width, height, upscale: true, pixel_aspect: (1, 1) }
+    }
+
+    /// Never make the image larger than the source. Images that would be enlarged keep their size instead,
+    /// and for [`Dimensions::exact`] each dimension is limited separately.
+    #[inline]
+    #[must_use]
+    pub fn without_upscaling(mut self) -> Self {
+        self.upscale = false;
+        self
+    }
+
+    /// Source pixels aren't square, but `width`:`height`, e.g. 10:11 for NTSC DVDs, or 4:3 for anamorphic HDV.
+    ///
+    /// The aspect ratio is kept for the displayed image, so the width and height are scaled by different factors.
+    /// Without upscaling, the limit is the source image displayed with square pixels, by stretching it in one direction.
+    ///
+    /// ```rust
+    /// use resize::Dimensions;
+    /// // 720×480 NTSC DVD frame displayed at 4:3
+    /// assert_eq!(Dimensions::fit(640, 480).pixel_aspect(10, 11).of(720, 480)?, (640, 469));
+    /// assert_eq!(Dimensions::fit(2000, 2000).pixel_aspect(10, 11).without_upscaling().of(720, 480)?, (720, 528));
+    /// # Ok::<_, resize::Error>(())
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn pixel_aspect(mut self, width: usize, height: usize) -> Self {
+        self.pixel_aspect = (width, height);
+        self
+    }
+
+    /// Destination width and height for a `source_width`×`source_height` image, rounded to the nearest pixel
+    pub fn of(self, source_width: usize, source_height: usize) -> Result<(usize, usize)> {
+        let (par_w, par_h) = self.pixel_aspect;
+        if source_width == 0 || source_height == 0 || self.width == 0 || self.height == 0 || par_w == 0 || par_h == 0 {
+            return Err(Error::InvalidParameters);
+        }
+        // Proportional to the displayed size
+        let aspect_width = source_width.checked_mul(par_w).ok_or(Error::InvalidParameters)?;
+        let aspect_height = source_height.checked_mul(par_h).ok_or(Error::InvalidParameters)?;
+        let (w, h) = match self.mode {
+            Mode::Fit => fit_within(aspect_width, aspect_height, self.width, self.height)?,
+            Mode::Fill => cover(aspect_width, aspect_height, self.width, self.height)?,
+            Mode::Exact if !self.upscale => return Ok((self.width.min(source_width), self.height.min(source_height))),
+            Mode::Exact => (self.width, self.height),
+        };
+        let (max_w, max_h) = cover(aspect_width, aspect_height, source_width, source_height)?;
+        if !self.upscale && (w > max_w || h > max_h) {
+            return Ok((max_w, max_h));
+        }
+        Ok((w, h))
+    }
+}
+
+/// Smallest dimensions with the aspect ratio of `w1`×`h1` that cover `w2`×`h2`, rounded to the nearest pixel
+fn cover(w1: usize, h1: usize, w2: usize, h2: usize) -> Result<(usize, usize)> {
+    let (w1, h1, w2, h2) = (w1 as u128, h1 as u128, w2 as u128, h2 as u128);
+    let (w, h) = if w1 * h2 > w2 * h1 {
+        ((w1 * h2 + h1 / 2) / h1, h2)
+    } else {
+        (w2, (h1 * w2 + w1 / 2) / w1)
+    };
+    let size = |n: u128| usize::try_from(n).map_err(|_| Error::InvalidParameters);
+    Ok((size(w)?, size(h)?))
+}
+
+/// Largest dimensions with the aspect ratio of `w1`×`h1` that fit in `w2`×`h2`, rounded to the nearest pixel
+fn fit_within(w1: usize, h1: usize, w2: usize, h2: usize) -> Result<(usize, usize)> {
+    if w1 == 0 || h1 == 0 || w2 == 0 || h2 == 0 {
+        return Err(Error::InvalidParameters);
+    }
+    let (w1, h1, w2, h2) = (w1 as u128, h1 as u128, w2 as u128, h2 as u128);
+    let (w, h) = if w1 * h2 > w2 * h1 {
+        (w2, ((h1 * w2 + w1 / 2) / w1).max(1))
+    } else {
+        (((w1 * h2 + h1 / 2) / h1).max(1), h2)
+    };
+    Ok((w as usize, h as usize))
+}
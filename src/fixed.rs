@@ -0,0 +1,149 @@
+use crate::px::FixedPointFormat;
+use crate::{round, CoeffsLine, Error, Options, Result, Scale, Type};
+use core::convert::TryFrom;
+use core::num::NonZeroUsize;
+use fallible_collections::{FallibleVec, TryHashMap};
+use std::sync::Arc;
+use std::vec::Vec;
+
+/// Fractional bits of the fixed-point weights, which are stored as `i16` to suit SIMD multiply-add instructions
+const COEFF_BITS: u32 = 14;
+/// Fractional bits kept between the passes
+const INTERMEDIATE_BITS: u32 = 7;
+
+/// Resampler for 8-bit pixels that uses integer arithmetic instead of floating-point.
+///
+/// Weights are rounded to 14 bits, and results of the first pass are kept with 7 fractional bits,
+/// which is faster and precise enough for 8-bit output, similar to libswscale.
+///
+/// Weights must be in the ±2 range, so heavy [sharpening](Options::sharpen) can't be used.
+#[derive(Debug)]
+pub struct FixedPointResizer<Format: FixedPointFormat> {
+    scale: Scale,
+    coeffs_w: Vec<FixedLine>,
+    coeffs_h: Vec<FixedLine>,
+    tmp: Vec<Format::Accumulator>,
+}
+
+#[derive(Debug, Clone)]
+struct FixedLine {
+    start: usize,
+    coeffs: Arc<[i16]>,
+}
+
+impl<Format: FixedPointFormat> FixedPointResizer<Format> {
+    /// Create a new resizer instance.
+    #[inline]
+    pub fn new(source_width: usize, source_heigth: usize, dest_width: usize, dest_height: usize, pixel_format: Format, filter_type: Type) -> Result<Self> {
+        Self::with_options(source_width, source_heigth, dest_width, dest_height, pixel_format, filter_type, &Options::default())
+    }
+
+    /// Create a new resizer instance with non-default [`Options`].
+    pub fn with_options(source_width: usize, source_heigth: usize, dest_width: usize, dest_height: usize, _pixel_format: Format, filter_type: Type, options: &Options) -> Result<Self> {
+        let scale = Scale::with_options(source_width, source_heigth, dest_width, dest_height, &filter_type, &filter_type, options)?;
+        if scale.transpose {
+            // Rotation by 90° isn't supported by this resizer
+            return Err(Error::InvalidParameters);
+        }
+        Ok(Self {
+            coeffs_w: quantize(&scale.coeffs_w)?,
+            coeffs_h: quantize(&scale.coeffs_h)?,
+            scale,
+            tmp: Vec::new(),
+        })
+    }
+
+    /// Resize `src` image data into `dst`.
+    #[inline]
+    pub fn resize(&mut self, src: &[Format::Pixel], dst: &mut [Format::Pixel]) -> Result<()> {
+        self.resize_internal(src, self.scale.w1, dst)
+    }
+
+    /// Resize `src` image data into `dst`, skipping `stride` pixels each row.
+    #[inline]
+    pub fn resize_stride(&mut self, src: &[Format::Pixel], src_stride: usize, dst: &mut [Format::Pixel]) -> Result<()> {
+        let src_stride = NonZeroUsize::new(src_stride).ok_or(Error::InvalidParameters)?;
+        self.resize_internal(src, src_stride, dst)
+    }
+
+    fn resize_internal(&mut self, src: &[Format::Pixel], stride: NonZeroUsize, mut dst: &mut [Format::Pixel]) -> Result<()> {
+        self.scale.check_buffers(src.len(), stride, dst.len())?;
+        let w2 = self.coeffs_w.len();
+        let tmp_len = w2 * self.scale.h1.get();
+        if let Some(missing) = tmp_len.checked_sub(self.tmp.len()).filter(|&n| n > 0) {
+            FallibleVec::try_reserve(&mut self.tmp, missing)?;
+            self.tmp.resize(tmp_len, Format::new());
+        }
+
+        let mut src_rows = src.chunks(stride.get());
+        // Rows of `tmp` resampled so far
+        let mut filled = 0;
+        for row in &self.coeffs_h {
+            while filled < row.start + row.coeffs.len() {
+                let src_row = src_rows.next().ok_or(Error::InvalidParameters)?;
+                for (tmp_px, col) in self.tmp[w2 * filled..w2 * (filled + 1)].iter_mut().zip(&self.coeffs_w) {
+                    let mut accum = Format::new();
+                    for (&coeff, &px) in col.coeffs.iter().zip(&src_row[col.start..col.start + col.coeffs.len()]) {
+                        Format::add(&mut accum, px, i32::from(coeff));
+                    }
+                    for c in accum.as_mut() {
+                        *c = (*c + (1 << (COEFF_BITS - INTERMEDIATE_BITS - 1))) >> (COEFF_BITS - INTERMEDIATE_BITS);
+                    }
+                    *tmp_px = accum;
+                }
+                filled += 1;
+            }
+
+            let tmp_rows = &self.tmp[w2 * row.start..];
+            for (col, dst_px) in dst[0..w2].iter_mut().enumerate() {
+                let mut accum = Format::new();
+                for (&coeff, other_row) in row.coeffs.iter().zip(tmp_rows.chunks_exact(w2)) {
+                    for (c, &inp) in accum.as_mut().iter_mut().zip(other_row[col].as_ref()) {
+                        *c += inp * i32::from(coeff);
+                    }
+                }
+                for c in accum.as_mut() {
+                    *c = ((*c + (1 << (COEFF_BITS + INTERMEDIATE_BITS - 1))) >> (COEFF_BITS + INTERMEDIATE_BITS)).clamp(0, 255);
+                }
+                *dst_px = Format::into_pixel(accum);
+            }
+            dst = &mut dst[w2..];
+        }
+        Ok(())
+    }
+}
+
+/// Quantizes every line, see [`quantize_weights`]
+fn quantize(lines: &[CoeffsLine]) -> Result<Vec<FixedLine>> {
+    // Lines share recycled weights, so they're quantized once per allocation
+    let mut quantized = TryHashMap::with_capacity(lines.len())?;
+    let mut res: Vec<FixedLine> = FallibleVec::try_with_capacity(lines.len())?;
+    for line in lines {
+        let key = line.coeffs.as_ptr() as usize;
+        let coeffs = if let Some(q) = quantized.get(&key) { Arc::clone(q) } else {
+            let coeffs: Arc<[i16]> = quantize_weights(&line.coeffs)?.into();
+            quantized.insert(key, coeffs.clone())?;
+            coeffs
+        };
+        res.push(FixedLine { start: line.start, coeffs });
+    }
+    Ok(res)
+}
+
+/// Rounds weights to 14-bit fixed-point, adjusting the largest one so that the sum stays as close to the original sum as it can be.
+/// That's exactly 1 for most lines, but less for lines that reach past [constant](crate::Edge::Constant) edges.
+pub(crate) fn quantize_weights(weights: &[f32]) -> Result<Vec<i16>> {
+    let one = 1 << COEFF_BITS;
+    let mut coeffs: Vec<i32> = FallibleVec::try_with_capacity(weights.len())?;
+    coeffs.extend(weights.iter().map(|&c| unsafe { round((c * one as f32) as f64) } as i32));
+    let sum = unsafe { round(weights.iter().map(|&c| c as f64).sum::<f64>() * one as f64) } as i32;
+    let error = sum - coeffs.iter().sum::<i32>();
+    if let Some(largest) = coeffs.iter_mut().max_by_key(|c| c.abs()) {
+        *largest += error;
+    }
+    let mut narrow: Vec<i16> = FallibleVec::try_with_capacity(coeffs.len())?;
+    for c in coeffs {
+        narrow.push(i16::try_from(c).map_err(|_| Error::InvalidParameters)?);
+    }
+    Ok(narrow)
+}
@@ -0,0 +1,275 @@
+use crate::px::Components;
+use crate::{libstd, Error, PixelFormat, Resizer, Result};
+use fallible_collections::FallibleVec;
+use core::convert::TryFrom;
+use std::borrow::Cow;
+use std::vec::Vec;
+use wgpu::util::DeviceExt;
+
+/// Both passes, one invocation per pixel they write. Pixels are up to 4 components, padded with zeros.
+const SHADER: &str = r"
+struct Dims { w1: u32, h1: u32, w2: u32, h2: u32 }
+
+@group(0) @binding(0) var<uniform> dims: Dims;
+// Lines of the columns and then of the rows: first source pixel, number of taps, and offset of their weights
+@group(0) @binding(1) var<storage, read> lines: array<vec4<u32>>;
+@group(0) @binding(2) var<storage, read> weights: array<f32>;
+@group(0) @binding(3) var<storage, read> source: array<vec4<f32>>;
+@group(0) @binding(4) var<storage, read_write> tmp: array<vec4<f32>>;
+@group(0) @binding(5) var<storage, read_write> dest: array<vec4<f32>>;
+
+@compute @workgroup_size(8, 8)
+fn horizontal(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x >= dims.w2 || id.y >= dims.h1 {
+        return;
+    }
+    let line = lines[id.x];
+    var acc = vec4<f32>(0.0);
+    for (var k = 0u; k < line.y; k += 1u) {
+        acc += weights[line.z + k] * source[id.y * dims.w1 + line.x + k];
+    }
+    tmp[id.y * dims.w2 + id.x] = acc;
+}
+
+@compute @workgroup_size(8, 8)
+fn vertical(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x >= dims.w2 || id.y >= dims.h2 {
+        return;
+    }
+    let line = lines[dims.w2 + id.y];
+    var acc = vec4<f32>(0.0);
+    for (var k = 0u; k < line.y; k += 1u) {
+        acc += weights[line.z + k] * tmp[(line.x + k) * dims.w2 + id.x];
+    }
+    dest[id.y * dims.w2 + id.x] = acc;
+}
+";
+
+/// Side of the square workgroups of [`SHADER`]
+const WORKGROUP_SIZE: usize = 8;
+/// Bytes of a pixel on the GPU, `vec4<f32>`
+const PIXEL_BYTES: usize = 16;
+
+/// Runs both passes of a [`Resizer`] as compute shaders with `wgpu`, on a device and queue owned by the caller.
+///
+/// Coefficients and buffers for the images are uploaded once, when it's created, so every [`GpuResizer::resize`]
+/// only uploads the source image and reads back the result. Pixels are converted to and from floats on the CPU
+/// by the pixel format, so the output is the same as [`Resizer::resize`] gives, except for rounding of the sums.
+///
+/// Resizers that the GPU can't run resize on the CPU instead, see [`GpuResizer::on_gpu`]. These are ones that use
+/// [`Options::multi_step`](crate::Options::multi_step), formats with more than 4 components or an accumulator
+/// that isn't only [`Components`], and images larger than the device's limits on buffers. Large frames like 8K
+/// need more than the default limits, so request the device with the adapter's limits.
+///
+/// ```rust,ignore
+/// let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor {
+///     required_limits: adapter.limits(),
+///     ..Default::default()
+/// }, None))?;
+/// let resizer = resize::new(7680, 4320, 3840, 2160, resize::Pixel::RGBAF32, resize::Type::Lanczos3)?;
+/// let mut gpu = resize::GpuResizer::new(resizer, &device, &queue)?;
+/// gpu.resize(src.as_rgba(), dst.as_rgba_mut())?;
+/// ```
+pub struct GpuResizer<'a, Format: PixelFormat> {
+    resizer: Resizer<Format>,
+    device: &'a wgpu::Device,
+    queue: &'a wgpu::Queue,
+    /// `None` if the resizer runs on the CPU
+    kernels: Option<Kernels>,
+    /// Source pixels converted to floats, reused between calls
+    upload: Vec<u8>,
+}
+
+/// Pipelines and buffers of one resizer
+struct Kernels {
+    horizontal: wgpu::ComputePipeline,
+    vertical: wgpu::ComputePipeline,
+    bind_group: wgpu::BindGroup,
+    source: wgpu::Buffer,
+    dest: wgpu::Buffer,
+    /// Copy of `dest` that can be mapped for reading
+    readback: wgpu::Buffer,
+}
+
+impl<Format: PixelFormat> core::fmt::Debug for GpuResizer<'_, Format> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("GpuResizer")
+            .field("on_gpu", &self.kernels.is_some())
+            .finish()
+    }
+}
+
+impl<'a, Format: PixelFormat> GpuResizer<'a, Format> where Format::Accumulator: Components {
+    /// Upload coefficients of `resizer`, and allocate buffers for its images on `device`.
+    pub fn new(resizer: Resizer<Format>, device: &'a wgpu::Device, queue: &'a wgpu::Queue) -> Result<Self> {
+        let kernels = Kernels::new(&resizer, device)?;
+        Ok(Self { resizer, device, queue, kernels, upload: Vec::new() })
+    }
+
+    /// Whether [`GpuResizer::resize`] runs on the GPU, rather than falling back to the CPU
+    #[inline]
+    #[must_use]
+    pub fn on_gpu(&self) -> bool {
+        self.kernels.is_some()
+    }
+
+    /// The resizer that this runs, e.g. to resize with it on the CPU
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> Resizer<Format> {
+        self.resizer
+    }
+
+    /// Resize `src` image data into `dst`, with the same buffers as [`Resizer::resize`] takes.
+    ///
+    /// If the GPU fails to return the result, this resizes on the CPU instead.
+    pub fn resize(&mut self, src: &[Format::InputPixel], dst: &mut [Format::OutputPixel]) -> Result<()> {
+        let kernels = match &self.kernels {
+            Some(kernels) => kernels,
+            None => return self.resizer.resize(src, dst),
+        };
+        let scale = &self.resizer.scale;
+        scale.check_buffers(src.len(), scale.w1, dst.len())?;
+        let (w1, h1, w2, h2) = (scale.w1.get(), scale.h1.get(), scale.w2(), scale.h2());
+
+        self.upload.clear();
+        FallibleVec::try_reserve(&mut self.upload, w1 * h1 * PIXEL_BYTES)?;
+        for &px in &src[..w1 * h1] {
+            let mut acc = Format::new();
+            self.resizer.pix_fmt.add(&mut acc, px, 1.);
+            let mut padded = [0f32; 4];
+            padded[..acc.components().len()].copy_from_slice(acc.components());
+            self.upload.extend(padded.iter().flat_map(|c| c.to_ne_bytes()));
+        }
+        self.queue.write_buffer(&kernels.source, 0, &self.upload);
+
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("resize") });
+        for (pipeline, height) in [(&kernels.horizontal, h1), (&kernels.vertical, h2)] {
+            // Separate passes, so the vertical one sees all of `tmp`
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor { label: None, timestamp_writes: None });
+            pass.set_pipeline(pipeline);
+            pass.set_bind_group(0, &kernels.bind_group, &[]);
+            pass.dispatch_workgroups(w2.div_ceil(WORKGROUP_SIZE) as u32, height.div_ceil(WORKGROUP_SIZE) as u32, 1);
+        }
+        encoder.copy_buffer_to_buffer(&kernels.dest, 0, &kernels.readback, 0, (w2 * h2 * PIXEL_BYTES) as u64);
+        self.queue.submit(Some(encoder.finish()));
+
+        let slice = kernels.readback.slice(..);
+        let (sender, receiver) = libstd::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |res| drop(sender.send(res)));
+        let _ = self.device.poll(wgpu::Maintain::Wait);
+        if !matches!(receiver.recv(), Ok(Ok(()))) {
+            return self.resizer.resize(src, dst);
+        }
+        {
+            let mapped = slice.get_mapped_range();
+            let dest_width = scale.dest_dimensions().0;
+            for (i, px) in mapped.chunks_exact(PIXEL_BYTES).enumerate() {
+                let mut acc = Format::new();
+                for (c, bytes) in acc.components_mut().iter_mut().zip(px.chunks_exact(4)) {
+                    *c = f32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+                }
+                // Rows of the result are columns of the output when it's rotated
+                let (x, y) = if scale.transpose { (i / w2, i % w2) } else { (i % w2, i / w2) };
+                self.resizer.pix_fmt.write_pixel(&mut dst[y * dest_width + x], acc, x, y);
+            }
+        }
+        kernels.readback.unmap();
+        Ok(())
+    }
+}
+
+impl Kernels {
+    /// `None` if the resizer can't run on this device
+    fn new<Format: PixelFormat>(resizer: &Resizer<Format>, device: &wgpu::Device) -> Result<Option<Self>> where Format::Accumulator: Components {
+        let scale = &resizer.scale;
+        if resizer.prescale.is_some() || Format::new().components().len() > 4 {
+            return Ok(None);
+        }
+        let (w1, h1, w2, h2) = (scale.w1.get(), scale.h1.get(), scale.w2(), scale.h2());
+
+        // Lines of both axes share one buffer, and so do their weights
+        let lines_len = w2 + h2;
+        let weights_len: usize = scale.coeffs_w.iter().chain(&scale.coeffs_h).map(|line| line.coeffs.len()).sum();
+        let mut lines: Vec<u8> = FallibleVec::try_with_capacity(lines_len * 16)?;
+        let mut weights: Vec<u8> = FallibleVec::try_with_capacity(weights_len * 4)?;
+        for line in scale.coeffs_w.iter().chain(&scale.coeffs_h) {
+            let offset = weights.len() / 4;
+            for v in [line.start, line.coeffs.len(), offset, 0] {
+                lines.extend(u32::try_from(v).map_err(|_| Error::InvalidParameters)?.to_ne_bytes());
+            }
+            weights.extend(line.coeffs.iter().flat_map(|w| w.to_ne_bytes()));
+        }
+
+        let limits = device.limits();
+        let max_binding = u64::from(limits.max_storage_buffer_binding_size).min(limits.max_buffer_size);
+        let max_groups = limits.max_compute_workgroups_per_dimension as usize;
+        let fits = [w1 * h1, w2 * h1, w2 * h2].iter().all(|&len| len.checked_mul(PIXEL_BYTES).is_some_and(|bytes| bytes as u64 <= max_binding)) &&
+            [lines.len(), weights.len()].iter().all(|&bytes| bytes as u64 <= max_binding) &&
+            [w2, h1, h2].iter().all(|&len| len.div_ceil(WORKGROUP_SIZE) <= max_groups);
+        if !fits {
+            return Ok(None);
+        }
+        let dims: Vec<u8> = [w1, h1, w2, h2].iter().flat_map(|&v| (v as u32).to_ne_bytes()).collect();
+
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("resize"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(SHADER)),
+        });
+        // Each entry point leaves out a buffer, so the layout is spelled out for the one bind group they share
+        let binding = |binding, ty| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer { ty, has_dynamic_offset: false, min_binding_size: None },
+            count: None,
+        };
+        let storage = |read_only| wgpu::BufferBindingType::Storage { read_only };
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("resize"),
+            entries: &[
+                binding(0, wgpu::BufferBindingType::Uniform),
+                binding(1, storage(true)),
+                binding(2, storage(true)),
+                binding(3, storage(true)),
+                binding(4, storage(false)),
+                binding(5, storage(false)),
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("resize"),
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = |entry_point| device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some(entry_point),
+            layout: Some(&pipeline_layout),
+            module: &module,
+            entry_point,
+            compilation_options: Default::default(),
+            cache: None,
+        });
+        let (horizontal, vertical) = (pipeline("horizontal"), pipeline("vertical"));
+
+        let init = |label, contents: &[u8], usage| device.create_buffer_init(&wgpu::util::BufferInitDescriptor { label: Some(label), contents, usage });
+        let empty = |label, pixels: usize, usage| device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some(label),
+            size: (pixels * PIXEL_BYTES) as u64,
+            usage,
+            mapped_at_creation: false,
+        });
+        let dims = init("dims", &dims, wgpu::BufferUsages::UNIFORM);
+        let lines = init("lines", &lines, wgpu::BufferUsages::STORAGE);
+        // Bindings can't be empty, e.g. when all weights are zero-length lines
+        let weights = init("weights", if weights.is_empty() { &[0; 4] } else { &weights }, wgpu::BufferUsages::STORAGE);
+        let source = empty("source", w1 * h1, wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST);
+        let tmp = empty("tmp", w2 * h1, wgpu::BufferUsages::STORAGE);
+        let dest = empty("dest", w2 * h2, wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC);
+        let readback = empty("readback", w2 * h2, wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST);
+
+        let entries = [&dims, &lines, &weights, &source, &tmp, &dest].iter().enumerate()
+            .map(|(binding, buffer)| wgpu::BindGroupEntry { binding: binding as u32, resource: buffer.as_entire_binding() })
+            .collect::<Vec<_>>();
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor { label: Some("resize"), layout: &layout, entries: &entries });
+        Ok(Some(Self { horizontal, vertical, bind_group, source, dest, readback }))
+    }
+}
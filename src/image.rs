@@ -0,0 +1,105 @@
+//! Resizing of [`image`](::image) crate's buffers, enabled with the `image` feature.
+use crate::{formats, Error, PixelFormat, Resizer, Result, Type};
+use ::image::{DynamicImage, ImageBuffer, Luma, LumaA, Primitive, Rgb, Rgba};
+use core::ops::{Deref, DerefMut};
+use fallible_collections::FallibleVec;
+use rgb::FromSlice;
+use std::vec::Vec;
+
+/// Pixel types of the `image` crate that can be resized.
+///
+/// Images with alpha are expected to be uncorrelated (not premultiplied), as usual in the `image` crate,
+/// so they're premultiplied during scaling like [`Pixel::RGBA8P`](crate::Pixel::RGBA8P).
+pub trait ImagePixel: ::image::Pixel {
+    /// Matching pixel format of this crate
+    type Format: PixelFormat;
+    /// The pixel format, e.g. [`Pixel::RGB8`](crate::Pixel::RGB8) for `Rgb<u8>`
+    fn pixel_format() -> Self::Format;
+    /// Groups raw subpixels into pixels
+    fn pixels(subpixels: &[Self::Subpixel]) -> &[<Self::Format as PixelFormat>::InputPixel];
+    /// Groups raw subpixels into pixels
+    fn pixels_mut(subpixels: &mut [Self::Subpixel]) -> &mut [<Self::Format as PixelFormat>::OutputPixel];
+}
+
+macro_rules! image_pixel {
+    ($pixel:ident, $format:ident, $as:ident, $as_mut:ident, $($t:ty),+) => {$(
+        impl ImagePixel for $pixel<$t> {
+            type Format = formats::$format<$t, $t>;
+            #[inline(always)]
+            fn pixel_format() -> Self::Format {
+                formats::$format(core::marker::PhantomData)
+            }
+            #[inline(always)]
+            fn pixels(subpixels: &[$t]) -> &[<Self::Format as PixelFormat>::InputPixel] {
+                subpixels.$as()
+            }
+            #[inline(always)]
+            fn pixels_mut(subpixels: &mut [$t]) -> &mut [<Self::Format as PixelFormat>::OutputPixel] {
+                subpixels.$as_mut()
+            }
+        }
+    )+};
+}
+
+image_pixel!(Luma, Gray, as_gray, as_gray_mut, u8, u16, f32);
+image_pixel!(LumaA, GrayAlphaPremultiply, as_gray_alpha, as_gray_alpha_mut, u8, u16, f32);
+image_pixel!(Rgb, Rgb, as_rgb, as_rgb_mut, u8, u16, f32);
+image_pixel!(Rgba, RgbaPremultiply, as_rgba, as_rgba_mut, u8, u16, f32);
+
+impl<Format: PixelFormat> Resizer<Format> {
+    /// Resize `src` image into `dst`, which must have the destination dimensions of this resizer.
+    #[inline]
+    pub fn resize_image<P, S, D>(&mut self, src: &ImageBuffer<P, S>, dst: &mut ImageBuffer<P, D>) -> Result<()>
+    where
+        P: ImagePixel<Format = Format>,
+        S: Deref<Target = [P::Subpixel]>,
+        D: Deref<Target = [P::Subpixel]> + DerefMut,
+    {
+        self.resize(P::pixels(src), P::pixels_mut(dst))
+    }
+}
+
+/// Resize `src` image to `dest_width`×`dest_height` pixels.
+///
+/// ```rust
+/// let img = image::RgbImage::new(64, 48);
+/// let thumbnail = resize::image::resize_image(&img, 16, 12, resize::Type::Lanczos3)?;
+/// assert_eq!(thumbnail.dimensions(), (16, 12));
+/// # Ok::<_, resize::Error>(())
+/// ```
+pub fn resize_image<P, S>(src: &ImageBuffer<P, S>, dest_width: u32, dest_height: u32, filter: Type) -> Result<ImageBuffer<P, Vec<P::Subpixel>>>
+where
+    P: ImagePixel,
+    S: Deref<Target = [P::Subpixel]>,
+{
+    let (w1, h1) = src.dimensions();
+    let len = (dest_width as usize).checked_mul(dest_height as usize)
+        .and_then(|n| n.checked_mul(P::CHANNEL_COUNT as usize))
+        .ok_or(Error::InvalidParameters)?;
+    let mut data = Vec::new();
+    FallibleVec::try_reserve(&mut data, len)?;
+    data.resize(len, P::Subpixel::DEFAULT_MIN_VALUE);
+    let mut dst = ImageBuffer::from_raw(dest_width, dest_height, data).ok_or(Error::InvalidParameters)?;
+    Resizer::new(w1 as usize, h1 as usize, dest_width as usize, dest_height as usize, P::pixel_format(), filter)?
+        .resize_image(src, &mut dst)?;
+    Ok(dst)
+}
+
+/// Resize `src` image to `dest_width`×`dest_height` pixels, keeping its color type.
+///
+/// Fails with [`Error::InvalidParameters`] for color types that aren't supported.
+pub fn resize_dynamic_image(src: &DynamicImage, dest_width: u32, dest_height: u32, filter: Type) -> Result<DynamicImage> {
+    Ok(match src {
+        DynamicImage::ImageLuma8(img) => resize_image(img, dest_width, dest_height, filter)?.into(),
+        DynamicImage::ImageLumaA8(img) => resize_image(img, dest_width, dest_height, filter)?.into(),
+        DynamicImage::ImageRgb8(img) => resize_image(img, dest_width, dest_height, filter)?.into(),
+        DynamicImage::ImageRgba8(img) => resize_image(img, dest_width, dest_height, filter)?.into(),
+        DynamicImage::ImageLuma16(img) => resize_image(img, dest_width, dest_height, filter)?.into(),
+        DynamicImage::ImageLumaA16(img) => resize_image(img, dest_width, dest_height, filter)?.into(),
+        DynamicImage::ImageRgb16(img) => resize_image(img, dest_width, dest_height, filter)?.into(),
+        DynamicImage::ImageRgba16(img) => resize_image(img, dest_width, dest_height, filter)?.into(),
+        DynamicImage::ImageRgb32F(img) => resize_image(img, dest_width, dest_height, filter)?.into(),
+        DynamicImage::ImageRgba32F(img) => resize_image(img, dest_width, dest_height, filter)?.into(),
+        _ => return Err(Error::InvalidParameters),
+    })
+}
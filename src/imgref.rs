@@ -0,0 +1,41 @@
+//! Resizing of [`imgref`](::imgref) images, enabled with the `imgref` feature.
+use crate::{Error, PixelFormat, Resizer, Result};
+use ::imgref::{ImgRef, ImgRefMut, ImgVec};
+use core::num::NonZeroUsize;
+use fallible_collections::FallibleVec;
+use std::vec::Vec;
+
+impl<Format: PixelFormat> Resizer<Format> {
+    /// Resize `src` image into `dst`. Both can have padding between rows.
+    ///
+    /// Fails with [`Error::InvalidParameters`] if dimensions of the images don't match dimensions of the resizer.
+    pub fn resize_img(&mut self, src: ImgRef<'_, Format::InputPixel>, mut dst: ImgRefMut<'_, Format::OutputPixel>) -> Result<()> {
+        if (src.width(), src.height()) != self.source_dimensions() || (dst.width(), dst.height()) != self.scale.dest_dimensions() {
+            return Err(Error::InvalidParameters);
+        }
+        let src_stride = NonZeroUsize::new(src.stride()).ok_or(Error::InvalidParameters)?;
+        let dst_stride = NonZeroUsize::new(dst.stride()).ok_or(Error::InvalidParameters)?;
+        self.resize_internal(src.buf(), src_stride, dst.buf_mut(), dst_stride)
+    }
+
+    /// Resize `src` image into a new image with the destination dimensions of this resizer.
+    ///
+    /// ```rust
+    /// use imgref::ImgVec;
+    /// let img = ImgVec::new(vec![rgb::RGB8::new(0, 0, 0); 64 * 48], 64, 48);
+    /// let mut resizer = resize::new(64, 48, 16, 12, resize::Pixel::RGB8, resize::Type::Lanczos3)?;
+    /// let thumbnail = resizer.resize_img_vec(img.as_ref())?;
+    /// assert_eq!(thumbnail.width(), 16);
+    /// # Ok::<_, resize::Error>(())
+    /// ```
+    pub fn resize_img_vec(&mut self, src: ImgRef<'_, Format::InputPixel>) -> Result<ImgVec<Format::OutputPixel>>
+    where Format::OutputPixel: Clone + Default {
+        let (w2, h2) = self.scale.dest_dimensions();
+        let mut buf = Vec::new();
+        FallibleVec::try_reserve(&mut buf, w2 * h2)?;
+        buf.resize(w2 * h2, Format::OutputPixel::default());
+        let mut dst = ImgVec::new(buf, w2, h2);
+        self.resize_img(src, dst.as_mut())?;
+        Ok(dst)
+    }
+}
@@ -0,0 +1,60 @@
+use crate::{image_len, Error, Options, PixelFormat, Result, RowOrder, Scale, Type};
+use core::num::NonZeroUsize;
+use core::ops::ControlFlow;
+use fallible_collections::FallibleVec;
+use std::vec::Vec;
+
+/// Resampler for interlaced video frames, which resizes the top and bottom fields separately and interleaves them again.
+///
+/// The top field is made of the even rows of the frame, and the bottom field of the odd rows.
+/// Fields are offset vertically by half of a field row, which is taken into account when computing coefficients,
+/// so the output fields keep their relative positions. Frame heights must be even.
+#[derive(Debug)]
+pub struct InterlacedResizer<Format: PixelFormat> {
+    top: Scale,
+    bottom: Scale,
+    pix_fmt: Format,
+    tmp: Vec<Format::Accumulator>,
+}
+
+impl<Format: PixelFormat> InterlacedResizer<Format> {
+    /// Create a new resizer for frames of the given dimensions.
+    pub fn new(source_width: usize, source_heigth: usize, dest_width: usize, dest_height: usize, pixel_format: Format, filter_type: Type) -> Result<Self> {
+        if !source_heigth.is_multiple_of(2) || !dest_height.is_multiple_of(2) {
+            return Err(Error::InvalidParameters);
+        }
+        let (h1, h2) = (source_heigth / 2, dest_height / 2);
+        // Top field rows are a quarter of a field row above the center of each pair of frame rows,
+        // and bottom ones a quarter below, which shrinks or grows with the scale
+        let offset_y = 0.25 * (1. - h1 as f64 / h2 as f64);
+        let field = |offset_y| Scale::with_options(source_width, h1, dest_width, h2, &filter_type, &filter_type, &Options::default().offset(0., offset_y));
+        Ok(Self {
+            top: field(offset_y)?,
+            bottom: field(-offset_y)?,
+            pix_fmt: pixel_format,
+            tmp: Vec::new(),
+        })
+    }
+
+    /// Resize `src` frame into `dst`.
+    pub fn resize(&mut self, src: &[Format::InputPixel], dst: &mut [Format::OutputPixel]) -> Result<()> {
+        let (w1, w2) = (self.top.w1, self.top.w2());
+        let (h1, h2) = (self.top.h1.get() * 2, self.top.h2() * 2);
+        if !matches!(image_len(w1.get(), h1, w1.get()), Some(len) if src.len() >= len) || image_len(w2, h2, w2) != Some(dst.len()) {
+            return Err(Error::InvalidParameters);
+        }
+        let tmp_len = self.top.tmp_len().max(self.bottom.tmp_len());
+        if let Some(missing) = tmp_len.checked_sub(self.tmp.len()).filter(|&n| n > 0) {
+            FallibleVec::try_reserve(&mut self.tmp, missing)?;
+            self.tmp.resize(tmp_len, Format::new());
+        }
+        // Each field is every other row of the frame
+        let src_stride = NonZeroUsize::new(w1.get() * 2).ok_or(Error::InvalidParameters)?;
+        let dst_stride = NonZeroUsize::new(w2 * 2).ok_or(Error::InvalidParameters)?;
+        let (src_bottom, dst_bottom) = (&src[w1.get()..], &mut dst[w2..]);
+        self.bottom.check_strided_buffers(src_bottom.len(), src_stride, dst_bottom.len(), dst_stride)?;
+        self.bottom.resample_into(&self.pix_fmt, &mut self.tmp, src_bottom, src_stride, dst_bottom, dst_stride.get(), RowOrder::default(), &mut |_| ControlFlow::Continue(())).map(drop)?;
+        self.top.check_strided_buffers(src.len(), src_stride, dst.len(), dst_stride)?;
+        self.top.resample_into(&self.pix_fmt, &mut self.tmp, src, src_stride, dst, dst_stride.get(), RowOrder::default(), &mut |_| ControlFlow::Continue(())).map(drop)
+    }
+}
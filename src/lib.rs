@@ -22,13 +22,27 @@
 //! resizer.resize(src.as_rgb(), dst.as_rgb_mut());
 //! # Ok::<_, resize::Error>(())
 //! ```
+//!
+//! # Determinism
+//!
+//! Floating-point arithmetic is evaluated in a fixed order, and never fused into multiply-add instructions,
+//! but trigonometric and exponential functions used by filters come from the platform's C library by default,
+//! and their last bits vary between implementations.
+//!
+//! With the `deterministic` feature these functions are computed by the pure-Rust `libm` crate instead,
+//! so the same input gives bit-identical output on every target with IEEE 754 floats, e.g. x86-64, ARM and WebAssembly.
+//! The exception is 32-bit x86 without SSE2, where the x87 FPU rounds intermediate results differently.
 // Current implementation is based on:
 // * https://github.com/sekrit-twc/zimg/tree/master/src/zimg/resize
 // * https://github.com/PistonDevelopers/image/blob/master/src/imageops/sample.rs
 #![no_std]
 #![deny(missing_docs)]
+// libm's functions are safe, unlike the C declarations
+#![cfg_attr(feature = "libm", allow(unused_unsafe))]
 
 extern crate alloc as std;
+#[cfg(any(feature = "threads", feature = "wgpu"))]
+extern crate std as libstd;
 
 use fallible_collections::FallibleVec;
 use std::sync::Arc;
@@ -36,16 +50,39 @@ use fallible_collections::TryHashMap;
 use core::f32;
 use std::fmt;
 use core::num::NonZeroUsize;
+use core::ops::ControlFlow;
 use std::boxed::Box;
 use std::vec::{Vec};
 
+// Math comes from the platform's C library, or from the `libm` crate
+// on targets that don't have one.
+#[cfg(feature = "libm")]
+use libm::{ceil, cos, exp, fabs, floor, log, pow, round, sin, sqrt};
+#[cfg(all(feature = "libm", feature = "metrics"))]
+use libm::log10;
+
+#[cfg(not(feature = "libm"))]
 extern "C" {
     fn sin(angle: f64) -> f64;
+    fn cos(angle: f64) -> f64;
     fn fabs(value: f64) -> f64;
     fn ceil(value: f64) -> f64;
     fn floor(value: f64) -> f64;
     fn pow(x: f64, y: f64) -> f64;
     fn round(value: f64) -> f64;
+    fn exp(x: f64) -> f64;
+    fn log(x: f64) -> f64;
+    fn sqrt(x: f64) -> f64;
+    #[cfg(feature = "metrics")]
+    fn log10(x: f64) -> f64;
+}
+
+/// Enters a span of the `tracing` crate until the end of the enclosing block, if the feature is enabled
+macro_rules! span {
+    ($level:ident, $($args:tt)*) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::span!(tracing::Level::$level, $($args)*).entered();
+    };
 }
 
 /// See [Error]
@@ -55,9 +92,100 @@ pub type Result<T, E = Error> = core::result::Result<T, E>;
 pub mod px;
 pub use px::PixelFormat;
 
+mod planar;
+pub use planar::{PlanarResizer, Plane, PlaneDimensions};
+
+mod yuv;
+pub use yuv::{ChromaResizer, ChromaSiting, Subsampling, Yuv420Resizer};
+
+mod alpha;
+pub use alpha::AlphaResizer;
+
+mod interlaced;
+pub use interlaced::InterlacedResizer;
+
+mod video;
+pub use video::{PictureStructure, PlaneMut, VideoFrame, VideoFrameMut, VideoScaler};
+
+mod volume;
+pub use volume::{VolumeResizer, VolumeStrides};
+
+mod fixed;
+pub use fixed::FixedPointResizer;
+
+mod compat;
+pub use compat::{CompatResizer, OpenCvInterpolation, PillowFilter};
+
+mod nearest;
+pub use nearest::NearestResizer;
+
+mod boxed;
+pub use boxed::BoxDownscaler;
+
+mod mips;
+pub use mips::{generate_mips, MipLevel};
+
+mod ewa;
+pub use ewa::EwaResizer;
+
+mod supersample;
+pub use supersample::SupersampleResizer;
+
+mod edge;
+pub use edge::EdgeDirectedResizer;
+
+mod parallel;
+pub use parallel::{Parallelism, SingleThreaded};
+#[cfg(feature = "threads")]
+pub use parallel::ScopedThreads;
+
+#[cfg(feature = "wgpu")]
+mod gpu;
+#[cfg(feature = "wgpu")]
+pub use gpu::GpuResizer;
+
+mod steps;
+pub use steps::ResizeSteps;
+
+mod params;
+pub use params::ResizeParams;
+
+mod fit;
+pub use fit::{CoverResizer, Dimensions, LetterboxResizer};
+
+mod cache;
+pub use cache::CoefficientCache;
+use cache::{AxisKey, FilterKey};
+
+mod builder;
+pub use builder::ResizerBuilder;
+
+mod snapshot;
+
+#[cfg(feature = "capi")]
+pub mod capi;
+
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+#[cfg(feature = "image")]
+pub mod image;
+
+#[cfg(feature = "imgref")]
+mod imgref;
+
+#[cfg(feature = "ndarray")]
+pub mod ndarray;
+
+#[cfg(feature = "bytemuck")]
+pub mod bytes;
+
+#[cfg(feature = "metrics")]
+pub mod metrics;
+
 /// Resizing type to use.
 pub enum Type {
-    /// Point resizing.
+    /// Point resizing. Source pixels are copied without resampling them.
     Point,
     /// Triangle (bilinear) resizing.
     Triangle,
@@ -65,12 +193,158 @@ pub enum Type {
     Catrom,
     /// Resize using Mitchell-Netravali filter.
     Mitchell,
+    /// Cubic B-spline (B = 1, C = 0). Very smooth, blurry.
+    BSpline,
+    /// Hermite cubic (B = 0, C = 0).
+    Hermite,
+    /// Mitchell-Netravali family cubic filter with custom B and C parameters.
+    Cubic {
+        /// Blurring parameter
+        b: f32,
+        /// Ringing parameter
+        c: f32,
+    },
     /// Resize using Sinc-windowed Sinc with radius of 3.
     Lanczos3,
+    /// Gaussian filter with the given standard deviation (in source pixels when upscaling,
+    /// destination pixels when downscaling). Truncated at 3 sigma. Blurs, but never rings.
+    Gaussian(f32),
+    /// Sinc windowed with the given window function, with the given radius.
+    ///
+    /// `WindowedSinc(Window::Hamming, 1.0)` matches Pillow's `HAMMING` filter.
+    WindowedSinc(Window, f32),
+    /// Spline interpolation with 4 taps (AviSynth `Spline16Resize`).
+    Spline16,
+    /// Spline interpolation with 6 taps (AviSynth `Spline36Resize`).
+    Spline36,
+    /// Spline interpolation with 8 taps (AviSynth `Spline64Resize`).
+    Spline64,
+    /// Magic Kernel Sharp, 2013 version. Magic Kernel with a sharpening step folded in; support of 2.5.
+    MagicKernelSharp2013,
+    /// Magic Kernel Sharp, 2021 version. Better sharpening, with support of 4.5.
+    MagicKernelSharp2021,
+    /// Area averaging. Each output pixel is an average of the source pixels it covers,
+    /// weighted by the exact covered area. Gives moire-free results when downscaling.
+    #[doc(alias = "Box")]
+    #[doc(alias = "INTER_AREA")]
+    Area,
     /// Resize with custom filter.
     Custom(Filter),
 }
 
+impl Type {
+    /// Name of the filter without its parameters
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Point => "point",
+            Self::Triangle => "triangle",
+            Self::Catrom => "catrom",
+            Self::Mitchell => "mitchell",
+            Self::BSpline => "bspline",
+            Self::Hermite => "hermite",
+            Self::Cubic { .. } => "cubic",
+            Self::Lanczos3 => "lanczos3",
+            Self::Gaussian(_) => "gaussian",
+            Self::WindowedSinc(..) => "windowed_sinc",
+            Self::Spline16 => "spline16",
+            Self::Spline36 => "spline36",
+            Self::Spline64 => "spline64",
+            Self::MagicKernelSharp2013 => "magic_kernel_sharp_2013",
+            Self::MagicKernelSharp2021 => "magic_kernel_sharp_2021",
+            Self::Area => "area",
+            Self::Custom(_) => "custom",
+        }
+    }
+}
+
+/// Filters are written as their name, followed by a colon and comma-separated parameters for the ones that have them:
+///
+/// | Filter | Text |
+/// |---|---|
+/// | [`Type::Point`] … [`Type::Area`] without parameters | `point`, `triangle`, `catrom`, `mitchell`, `bspline`, `hermite`, `lanczos3`, `spline16`, `spline36`, `spline64`, `magic_kernel_sharp_2013`, `magic_kernel_sharp_2021`, `area` |
+/// | [`Type::Cubic`] | `cubic:B,C`, e.g. `cubic:0.33,0.33` |
+/// | [`Type::Gaussian`] | `gaussian:SIGMA`, e.g. `gaussian:0.5` |
+/// | [`Type::WindowedSinc`] | `windowed_sinc:WINDOW,RADIUS` with `hann`, `hamming` or `blackman` window, e.g. `windowed_sinc:hamming,1` |
+///
+/// Names are case-sensitive, and spaces around parameters are allowed.
+/// Parameters must be finite numbers. [`Type::Custom`] can't be parsed, and is displayed as `custom`.
+///
+/// ```rust
+/// let filter: resize::Type = "cubic:0.33,0.33".parse()?;
+/// assert_eq!(filter.to_string(), "cubic:0.33,0.33");
+/// # Ok::<_, resize::Error>(())
+/// ```
+impl core::str::FromStr for Type {
+    type Err = Error;
+
+    /// Fails with [`Error::InvalidParameters`] if the text doesn't follow the grammar above
+    fn from_str(s: &str) -> Result<Self> {
+        let (name, params) = match s.split_once(':') {
+            Some((name, params)) => (name, Some(params)),
+            None => (s, None),
+        };
+        // One more than the most parameters a filter has, to detect extra ones
+        let mut params = params.into_iter().flat_map(|p| p.split(',')).map(str::trim);
+        let params = [params.next(), params.next(), params.next()];
+        let number = |p: &str| p.parse::<f32>().ok().filter(|n| n.is_finite()).ok_or(Error::InvalidParameters);
+        Ok(match (name, params) {
+            ("point", [None, ..]) => Self::Point,
+            ("triangle", [None, ..]) => Self::Triangle,
+            ("catrom", [None, ..]) => Self::Catrom,
+            ("mitchell", [None, ..]) => Self::Mitchell,
+            ("bspline", [None, ..]) => Self::BSpline,
+            ("hermite", [None, ..]) => Self::Hermite,
+            ("cubic", [Some(b), Some(c), None]) => Self::Cubic { b: number(b)?, c: number(c)? },
+            ("lanczos3", [None, ..]) => Self::Lanczos3,
+            ("gaussian", [Some(sigma), None, _]) => Self::Gaussian(number(sigma)?),
+            ("windowed_sinc", [Some(window), Some(radius), None]) => Self::WindowedSinc(match window {
+                "hann" => Window::Hann,
+                "hamming" => Window::Hamming,
+                "blackman" => Window::Blackman,
+                _ => return Err(Error::InvalidParameters),
+            }, number(radius)?),
+            ("spline16", [None, ..]) => Self::Spline16,
+            ("spline36", [None, ..]) => Self::Spline36,
+            ("spline64", [None, ..]) => Self::Spline64,
+            ("magic_kernel_sharp_2013", [None, ..]) => Self::MagicKernelSharp2013,
+            ("magic_kernel_sharp_2021", [None, ..]) => Self::MagicKernelSharp2021,
+            ("area", [None, ..]) => Self::Area,
+            _ => return Err(Error::InvalidParameters),
+        })
+    }
+}
+
+/// Writes the filter in the text format that `FromStr` parses
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())?;
+        match *self {
+            Self::Cubic { b, c } => write!(f, ":{},{}", b, c),
+            Self::Gaussian(sigma) => write!(f, ":{}", sigma),
+            Self::WindowedSinc(window, radius) => write!(f, ":{},{}", match window {
+                Window::Hann => "hann",
+                Window::Hamming => "hamming",
+                Window::Blackman => "blackman",
+            }, radius),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Window function for [`Type::WindowedSinc`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Window {
+    /// Raised cosine, `0.5 + 0.5 cos(πx)`
+    #[doc(alias = "Hanning")]
+    Hann,
+    /// `0.54 + 0.46 cos(πx)`
+    Hamming,
+    /// `0.42 + 0.5 cos(πx) + 0.08 cos(2πx)`
+    Blackman,
+}
+
 /// Resampling filter.
 pub struct Filter {
     kernel: Box<dyn Fn(f32) -> f32>,
@@ -100,6 +374,22 @@ impl Filter {
         Self::new(Box::new(move |x| cubic_bc(b, c, x)), 2.0)
     }
 
+    /// Helper to create Gaussian filter with the given standard deviation, truncated at 3 sigma.
+    ///
+    /// Fails with [`Error::InvalidParameters`] unless `sigma` is positive and finite.
+    pub fn new_gaussian(sigma: f32) -> Result<Self> {
+        if !sigma.is_finite() || sigma <= 0. {
+            return Err(Error::InvalidParameters);
+        }
+        Ok(Self::new(Box::new(move |x| gaussian(sigma, x)), 3.0 * sigma))
+    }
+
+    /// Helper to create Sinc filter windowed with the given window function.
+    #[must_use]
+    pub fn new_windowed_sinc(window: Window, radius: f32) -> Self {
+        Self::new(Box::new(move |x| windowed_sinc(window, radius, x)), radius)
+    }
+
     /// Helper to create Lanczos filter with custom radius.
     #[must_use]
     #[deprecated(note = "use Type enum")]
@@ -159,12 +449,121 @@ fn lanczos(taps: f32, x: f32) -> f32 {
     }
 }
 
+// Piecewise cubics from
+// https://github.com/AviSynth/AviSynthPlus/blob/master/avs_core/filters/resample_functions.cpp
+#[inline]
+fn spline16(x: f32) -> f32 {
+    let x = unsafe { fabs(x as f64) } as f32;
+    if x < 1.0 {
+        ((x - 9.0 / 5.0) * x - 1.0 / 5.0) * x + 1.0
+    } else if x < 2.0 {
+        let x = x - 1.0;
+        ((-1.0 / 3.0 * x + 4.0 / 5.0) * x - 7.0 / 15.0) * x
+    } else {
+        0.0
+    }
+}
+
+#[inline]
+fn spline36(x: f32) -> f32 {
+    let x = unsafe { fabs(x as f64) } as f32;
+    if x < 1.0 {
+        ((13.0 / 11.0 * x - 453.0 / 209.0) * x - 3.0 / 209.0) * x + 1.0
+    } else if x < 2.0 {
+        let x = x - 1.0;
+        ((-6.0 / 11.0 * x + 270.0 / 209.0) * x - 156.0 / 209.0) * x
+    } else if x < 3.0 {
+        let x = x - 2.0;
+        ((1.0 / 11.0 * x - 45.0 / 209.0) * x + 26.0 / 209.0) * x
+    } else {
+        0.0
+    }
+}
+
+#[inline]
+fn spline64(x: f32) -> f32 {
+    let x = unsafe { fabs(x as f64) } as f32;
+    if x < 1.0 {
+        ((49.0 / 41.0 * x - 6387.0 / 2911.0) * x - 3.0 / 2911.0) * x + 1.0
+    } else if x < 2.0 {
+        let x = x - 1.0;
+        ((-24.0 / 41.0 * x + 4032.0 / 2911.0) * x - 2328.0 / 2911.0) * x
+    } else if x < 3.0 {
+        let x = x - 2.0;
+        ((6.0 / 41.0 * x - 1008.0 / 2911.0) * x + 582.0 / 2911.0) * x
+    } else if x < 4.0 {
+        let x = x - 3.0;
+        ((-1.0 / 41.0 * x + 168.0 / 2911.0) * x - 97.0 / 2911.0) * x
+    } else {
+        0.0
+    }
+}
+
+// Magic Kernel convolved with its sharpening kernels, see
+// https://johncostella.com/magic/
+#[inline]
+fn magic_kernel_sharp_2013(x: f32) -> f32 {
+    let x = unsafe { fabs(x as f64) } as f32;
+    if x <= 0.5 {
+        17.0 / 16.0 - 7.0 / 4.0 * x * x
+    } else if x <= 1.5 {
+        (4.0 * x * x - 11.0 * x + 7.0) / 4.0
+    } else if x <= 2.5 {
+        -(4.0 * x * x - 20.0 * x + 25.0) / 32.0
+    } else {
+        0.0
+    }
+}
+
+#[inline]
+fn magic_kernel_sharp_2021(x: f32) -> f32 {
+    let x = unsafe { fabs(x as f64) } as f32;
+    if x <= 0.5 {
+        577.0 / 576.0 - 239.0 / 144.0 * x * x
+    } else if x <= 1.5 {
+        (140.0 * x * x - 379.0 * x + 239.0) / 144.0
+    } else if x <= 2.5 {
+        -(24.0 * x * x - 113.0 * x + 130.0) / 144.0
+    } else if x <= 3.5 {
+        (4.0 * x * x - 27.0 * x + 45.0) / 144.0
+    } else if x <= 4.5 {
+        -(4.0 * x * x - 36.0 * x + 81.0) / 1152.0
+    } else {
+        0.0
+    }
+}
+
+#[inline]
+fn windowed_sinc(window: Window, radius: f32, x: f32) -> f32 {
+    let t = unsafe { fabs(x as f64) } / radius as f64;
+    if t >= 1.0 {
+        return 0.0;
+    }
+    let pt = t * core::f64::consts::PI;
+    let w = match window {
+        Window::Hann => 0.5 + 0.5 * unsafe { cos(pt) },
+        Window::Hamming => 0.54 + 0.46 * unsafe { cos(pt) },
+        Window::Blackman => 0.42 + 0.5 * unsafe { cos(pt) } + 0.08 * unsafe { cos(2.0 * pt) },
+    };
+    sinc(x) * w as f32
+}
+
+#[inline]
+fn gaussian(sigma: f32, x: f32) -> f32 {
+    if (unsafe { fabs(x as f64) } as f32) < 3.0 * sigma {
+        unsafe { exp(-(x * x) as f64 / (2.0 * (sigma * sigma) as f64)) as f32 }
+    } else {
+        0.0
+    }
+}
+
 /// Predefined constants for supported pixel formats.
 #[allow(non_snake_case)]
 #[allow(non_upper_case_globals)]
 pub mod Pixel {
     use core::marker::PhantomData;
     use crate::formats;
+    use crate::px;
 
     /// Grayscale, 8-bit.
     #[doc(alias = "Grey")]
@@ -172,10 +571,65 @@ pub mod Pixel {
     /// Grayscale, 16-bit, native endian.
     pub const Gray16: formats::Gray<u16, u16> = formats::Gray(PhantomData);
 
-    /// Grayscale, 32-bit float
+    /// Grayscale, signed 16-bit, native endian, e.g. CT scans in Hounsfield units or disparity maps.
+    ///
+    /// Output is clamped to the signed range. Converting to other depths maps 32767 to their full intensity.
+    pub const GrayI16: formats::Gray<i16, i16> = formats::Gray(PhantomData);
+    /// Grayscale, signed 16-bit, native endian, accumulated in double precision
+    pub const GrayI16Precise: formats::PreciseGray<i16, i16> = formats::PreciseGray(PhantomData);
+
+    /// Grayscale, 16-bit, big endian.
+    pub const Gray16BE: px::BigEndian<formats::Gray<u16, u16>> = px::BigEndian(formats::Gray(PhantomData));
+
+    /// Grayscale, 32-bit float. This is pretty efficient, since resizing uses f32 internally.
+    ///
+    /// Output is neither rounded nor clamped, so HDR (> 1.0) and negative values are preserved.
     pub const GrayF32: formats::Gray<f32, f32> = formats::Gray(PhantomData);
     /// Grayscale, 64-bit float
     pub const GrayF64: formats::Gray<f64, f64> = formats::Gray(PhantomData);
+    /// Grayscale, 32-bit unsigned integer, native endian, accumulated in double precision to keep all the bits
+    pub const Gray32: formats::PreciseGray<u32, u32> = formats::PreciseGray(PhantomData);
+    /// Grayscale, 16-bit, native endian, accumulated in double precision. Slower, but doesn't lose the lowest bits with large kernels.
+    pub const Gray16Precise: formats::PreciseGray<u16, u16> = formats::PreciseGray(PhantomData);
+    /// Grayscale, 32-bit float, accumulated in double precision
+    pub const GrayF32Precise: formats::PreciseGray<f32, f32> = formats::PreciseGray(PhantomData);
+    /// Grayscale, 64-bit float, accumulated in double precision
+    pub const GrayF64Precise: formats::PreciseGray<f64, f64> = formats::PreciseGray(PhantomData);
+
+    /// Coverage mask, 8-bit. Like [Gray8], but never overshoots, see [`formats::Coverage`].
+    pub const Coverage8: formats::Coverage<u8, u8> = formats::Coverage(PhantomData);
+    /// Coverage mask, 32-bit float. Output is clamped to 0-1.
+    pub const CoverageF32: formats::Coverage<f32, f32> = formats::Coverage(PhantomData);
+
+    /// Grayscale with alpha, 8-bit per component. Components are scaled independently. Use this if the input is already alpha-premultiplied.
+    ///
+    /// See [GrayAlpha8P].
+    #[doc(alias = "LA8")]
+    pub const GrayAlpha8: formats::GrayAlpha<u8, u8> = formats::GrayAlpha(PhantomData);
+    /// Grayscale with alpha, 16-bit per component, native endian. Components are scaled independently. Use this if the input is already alpha-premultiplied.
+    ///
+    /// See [GrayAlpha16P].
+    #[doc(alias = "LA16")]
+    pub const GrayAlpha16: formats::GrayAlpha<u16, u16> = formats::GrayAlpha(PhantomData);
+    /// Grayscale with alpha, 16-bit per component, big endian. Components are scaled independently.
+    pub const GrayAlpha16BE: px::BigEndian<formats::GrayAlpha<u16, u16>> = px::BigEndian(formats::GrayAlpha(PhantomData));
+    /// Grayscale with alpha, 8-bit per component. Gray component will be converted to premultiplied during scaling, and then converted back to uncorrelated.
+    ///
+    /// Clears "dirty alpha". Use this for regular uncorrelated (not premultiplied) gray+alpha bitmaps, e.g. PNG LA images.
+    pub const GrayAlpha8P: formats::GrayAlphaPremultiply<u8, u8> = formats::GrayAlphaPremultiply(PhantomData);
+    /// Grayscale with alpha, 16-bit per component, native endian. Gray component will be converted to premultiplied during scaling, and then converted back to uncorrelated.
+    ///
+    /// Clears "dirty alpha". Use this for regular uncorrelated (not premultiplied) gray+alpha bitmaps, e.g. PNG LA images.
+    pub const GrayAlpha16P: formats::GrayAlphaPremultiply<u16, u16> = formats::GrayAlphaPremultiply(PhantomData);
+    /// Grayscale with alpha, 16-bit per component, big endian. Gray component will be converted to premultiplied during scaling, and then converted back to uncorrelated.
+    pub const GrayAlpha16PBE: px::BigEndian<formats::GrayAlphaPremultiply<u16, u16>> = px::BigEndian(formats::GrayAlphaPremultiply(PhantomData));
+
+    /// Two independent channels, 8-bit per component. Use this for interleaved chroma (UV or CbCr) planes of semi-planar video.
+    #[doc(alias = "UV")]
+    #[doc(alias = "CbCr")]
+    pub const Duo8: formats::Duo<u8, u8> = formats::Duo(PhantomData);
+    /// Two independent channels, 16-bit per component, native endian.
+    pub const Duo16: formats::Duo<u16, u16> = formats::Duo(PhantomData);
 
     /// RGB, 8-bit per component.
     #[doc(alias = "RGB24")]
@@ -183,6 +637,8 @@ pub mod Pixel {
     /// RGB, 16-bit per component, native endian.
     #[doc(alias = "RGB48")]
     pub const RGB16: formats::Rgb<u16, u16> = formats::Rgb(PhantomData);
+    /// RGB, 16-bit per component, big endian.
+    pub const RGB16BE: px::BigEndian<formats::Rgb<u16, u16>> = px::BigEndian(formats::Rgb(PhantomData));
     /// RGBA, 8-bit per component. Components are scaled independently. Use this if the input is already alpha-premultiplied.
     ///
     /// Preserves RGB values of fully-transparent pixels. Expect halos around edges of transparency if using regular, uncorrelated RGBA. See [RGBA8P].
@@ -203,26 +659,146 @@ pub mod Pixel {
     ///
     /// Clears "dirty alpha". Use this for high-quality scaling of regular uncorrelated (not premultiplied) RGBA bitmaps.
     pub const RGBA16P: formats::RgbaPremultiply<u16, u16> = formats::RgbaPremultiply(PhantomData);
+    /// RGBA, 16-bit per component, big endian. Components are scaled independently.
+    pub const RGBA16BE: px::BigEndian<formats::Rgba<u16, u16>> = px::BigEndian(formats::Rgba(PhantomData));
+    /// RGBA, 16-bit per component, big endian. RGB components will be converted to premultiplied during scaling, and then converted back to uncorrelated.
+    pub const RGBA16PBE: px::BigEndian<formats::RgbaPremultiply<u16, u16>> = px::BigEndian(formats::RgbaPremultiply(PhantomData));
+
+    /// BGR, 8-bit per component.
+    #[doc(alias = "BGR24")]
+    pub const BGR8: formats::Bgr<u8, u8> = formats::Bgr(PhantomData);
+    /// BGR, 16-bit per component, native endian.
+    #[doc(alias = "BGR48")]
+    pub const BGR16: formats::Bgr<u16, u16> = formats::Bgr(PhantomData);
+    /// BGRA, 8-bit per component. Components are scaled independently. Use this if the input is already alpha-premultiplied.
+    ///
+    /// See [BGRA8P].
+    #[doc(alias = "BGRA32")]
+    pub const BGRA8: formats::Bgra<u8, u8> = formats::Bgra(PhantomData);
+    /// BGRA, 16-bit per component, native endian. Components are scaled independently. Use this if the input is already alpha-premultiplied.
+    ///
+    /// See [BGRA16P].
+    #[doc(alias = "BGRA64")]
+    pub const BGRA16: formats::Bgra<u16, u16> = formats::Bgra(PhantomData);
+    /// BGRA, 8-bit per component. BGR components will be converted to premultiplied during scaling, and then converted back to uncorrelated.
+    ///
+    /// Clears "dirty alpha". Use this for regular uncorrelated (not premultiplied) BGRA bitmaps.
+    pub const BGRA8P: formats::BgraPremultiply<u8, u8> = formats::BgraPremultiply(PhantomData);
+    /// BGRA, 16-bit per component, native endian. BGR components will be converted to premultiplied during scaling, and then converted back to uncorrelated.
+    ///
+    /// Clears "dirty alpha". Use this for regular uncorrelated (not premultiplied) BGRA bitmaps.
+    pub const BGRA16P: formats::BgraPremultiply<u16, u16> = formats::BgraPremultiply(PhantomData);
+
+    /// CMYK, 8-bit per component.
+    #[doc(alias = "CMYK32")]
+    pub const CMYK8: formats::Cmyk<u8, u8> = formats::Cmyk(PhantomData);
+    /// CMYK, 16-bit per component, native endian.
+    #[doc(alias = "CMYK64")]
+    pub const CMYK16: formats::Cmyk<u16, u16> = formats::Cmyk(PhantomData);
 
     /// RGB, 32-bit float per component. This is pretty efficient, since resizing uses f32 internally.
+    ///
+    /// Output is neither rounded nor clamped, so HDR (> 1.0) and negative values are preserved.
     pub const RGBF32: formats::Rgb<f32, f32> = formats::Rgb(PhantomData);
     /// RGB, 64-bit double per component.
     pub const RGBF64: formats::Rgb<f64, f64> = formats::Rgb(PhantomData);
 
     /// RGBA, 32-bit float per component. This is pretty efficient, since resizing uses f32 internally.
     ///
-    /// Components are scaled independently (no premultiplication applied)
+    /// Output is neither rounded nor clamped. Components are scaled independently (no premultiplication applied). See [RGBAF32P].
     pub const RGBAF32: formats::Rgba<f32, f32> = formats::Rgba(PhantomData);
     /// RGBA, 64-bit double per component.
     ///
-    /// Components are scaled independently (no premultiplication applied)
+    /// Components are scaled independently (no premultiplication applied). See [RGBAF64P].
     pub const RGBAF64: formats::Rgba<f64, f64> = formats::Rgba(PhantomData);
+    /// Grayscale, 16-bit half-precision float. Widened to f32 during scaling.
+    #[cfg(feature = "half")]
+    pub const GrayF16: formats::Gray<half::f16, half::f16> = formats::Gray(PhantomData);
+    /// RGB, 16-bit half-precision float per component. Widened to f32 during scaling.
+    #[cfg(feature = "half")]
+    pub const RGBF16: formats::Rgb<half::f16, half::f16> = formats::Rgb(PhantomData);
+    /// RGBA, 16-bit half-precision float per component. Widened to f32 during scaling.
+    ///
+    /// Components are scaled independently (no premultiplication applied). See [RGBAF16P].
+    #[cfg(feature = "half")]
+    pub const RGBAF16: formats::Rgba<half::f16, half::f16> = formats::Rgba(PhantomData);
+    /// RGBA, 16-bit half-precision float per component. RGB components will be weighted by alpha during scaling, and then converted back to uncorrelated.
+    #[cfg(feature = "half")]
+    pub const RGBAF16P: formats::RgbaPremultiply<half::f16, half::f16> = formats::RgbaPremultiply(PhantomData);
+
+    /// RGBA, 32-bit float per component. RGB components will be weighted by alpha during scaling, and then converted back to uncorrelated.
+    ///
+    /// Clears "dirty alpha". Use this for straight (not premultiplied) RGBA float bitmaps.
+    pub const RGBAF32P: formats::RgbaPremultiply<f32, f32> = formats::RgbaPremultiply(PhantomData);
+
+    /// RGB, 32-bit unsigned integer per component, native endian, accumulated in double precision
+    pub const RGB32: formats::PreciseRgb<u32, u32> = formats::PreciseRgb(PhantomData);
+    /// RGBA, 32-bit unsigned integer per component, native endian, accumulated in double precision. Components are scaled independently.
+    pub const RGBA32: formats::PreciseRgba<u32, u32> = formats::PreciseRgba(PhantomData);
+    /// RGB, 16-bit per component, native endian, accumulated in double precision
+    pub const RGB16Precise: formats::PreciseRgb<u16, u16> = formats::PreciseRgb(PhantomData);
+    /// RGBA, 16-bit per component, native endian, accumulated in double precision. Components are scaled independently.
+    pub const RGBA16Precise: formats::PreciseRgba<u16, u16> = formats::PreciseRgba(PhantomData);
+    /// RGB, 32-bit float per component, accumulated in double precision
+    pub const RGBF32Precise: formats::PreciseRgb<f32, f32> = formats::PreciseRgb(PhantomData);
+    /// RGBA, 32-bit float per component, accumulated in double precision. Components are scaled independently.
+    pub const RGBAF32Precise: formats::PreciseRgba<f32, f32> = formats::PreciseRgba(PhantomData);
+    /// RGBA, 64-bit double per component. RGB components will be weighted by alpha during scaling, and then converted back to uncorrelated.
+    ///
+    /// Clears "dirty alpha". Use this for straight (not premultiplied) RGBA float bitmaps.
+    pub const RGBAF64P: formats::RgbaPremultiply<f64, f64> = formats::RgbaPremultiply(PhantomData);
+
+    /// Any fixed number of independent channels, e.g. multispectral imagery. Pixels are `[T; N]` arrays.
+    ///
+    /// ```
+    /// let format = resize::Pixel::Channels::<u16, 7>();
+    /// let mut r = resize::new(2, 1, 1, 1, format, resize::Type::Triangle)?;
+    /// let mut dst = [[0; 7]];
+    /// r.resize(&[[10; 7], [20; 7]], &mut dst)?;
+    /// assert_eq!([[15; 7]], dst);
+    /// # Ok::<_, resize::Error>(())
+    /// ```
+    #[inline(always)]
+    #[must_use]
+    pub const fn Channels<T, const N: usize>() -> formats::Channels<T, T, N> {
+        formats::Channels(PhantomData)
+    }
+
+    /// Grayscale, 8-bit input, 16-bit native endian output. Values are scaled to the full output range.
+    pub const Gray8to16: formats::Gray<u8, u16> = formats::Gray(PhantomData);
+    /// Grayscale, 16-bit native endian input, 8-bit output. Values are scaled to the full output range.
+    pub const Gray16to8: formats::Gray<u16, u8> = formats::Gray(PhantomData);
+    /// RGB, 8-bit input, 16-bit native endian output. Values are scaled to the full output range.
+    #[doc(alias = "RGB24_TO_48")]
+    pub const RGB8to16: formats::Rgb<u8, u16> = formats::Rgb(PhantomData);
+    /// RGB, 16-bit native endian input, 8-bit output. Values are scaled to the full output range.
+    #[doc(alias = "RGB48_TO_24")]
+    pub const RGB16to8: formats::Rgb<u16, u8> = formats::Rgb(PhantomData);
+    /// RGBA, 8-bit input, 16-bit native endian output. Components are scaled independently, see [RGBA8].
+    #[doc(alias = "RGBA32_TO_64")]
+    pub const RGBA8to16: formats::Rgba<u8, u16> = formats::Rgba(PhantomData);
+    /// RGBA, 16-bit native endian input, 8-bit output. Components are scaled independently, see [RGBA16].
+    #[doc(alias = "RGBA64_TO_32")]
+    pub const RGBA16to8: formats::Rgba<u16, u8> = formats::Rgba(PhantomData);
+
+    /// RGB, 8-bit input converted to 8-bit grayscale output using BT.709 luma weights.
+    ///
+    /// Use `.with_weights()` to choose different weights, e.g. `px::LumaWeights::BT601`.
+    #[doc(alias = "luma")]
+    pub const RGB8toGray8: formats::RgbToGray<u8, u8> = formats::RgbToGray(px::LumaWeights::BT709, PhantomData);
+    /// RGB, 16-bit native endian input converted to 16-bit grayscale output using BT.709 luma weights.
+    pub const RGB16toGray16: formats::RgbToGray<u16, u16> = formats::RgbToGray(px::LumaWeights::BT709, PhantomData);
+    /// RGBA, 8-bit input converted to 8-bit grayscale output using BT.709 luma weights. Alpha is ignored.
+    pub const RGBA8toGray8: formats::RgbaToGray<u8, u8> = formats::RgbaToGray(px::LumaWeights::BT709, PhantomData);
+    /// RGBA, 16-bit native endian input converted to 16-bit grayscale output using BT.709 luma weights. Alpha is ignored.
+    pub const RGBA16toGray16: formats::RgbaToGray<u16, u16> = formats::RgbaToGray(px::LumaWeights::BT709, PhantomData);
 }
 
-/// Implementation detail
+/// Pixel formats with any combination of input and output subpixel types.
 ///
 /// These structs implement `PixelFormat` trait that allows conversion to and from internal pixel representation.
-#[doc(hidden)]
+/// Constants in [`Pixel`] cover the common cases, and `instance()` creates the others,
+/// e.g. `formats::Rgb::<u8, u16>::instance()` reads `RGB<u8>` and writes `RGB<u16>`. See [`Resizer::resize_typed`].
 pub mod formats {
     use core::marker::PhantomData;
     /// RGB pixels
@@ -234,9 +810,306 @@ pub mod formats {
     /// Apply premultiplication to RGBA pixels during scaling. Assumes **non**-premultiplied input/output.
     #[derive(Debug, Copy, Clone)]
     pub struct RgbaPremultiply<InputSubpixel, OutputSubpixel>(pub(crate) PhantomData<(InputSubpixel, OutputSubpixel)>);
+    /// BGR pixels
+    #[derive(Debug, Copy, Clone)]
+    pub struct Bgr<InputSubpixel, OutputSubpixel>(pub(crate) PhantomData<(InputSubpixel, OutputSubpixel)>);
+    /// BGRA pixels, each channel is independent. Compatible with premultiplied input/output.
+    #[derive(Debug, Copy, Clone)]
+    pub struct Bgra<InputSubpixel, OutputSubpixel>(pub(crate) PhantomData<(InputSubpixel, OutputSubpixel)>);
+    /// Apply premultiplication to BGRA pixels during scaling. Assumes **non**-premultiplied input/output.
+    #[derive(Debug, Copy, Clone)]
+    pub struct BgraPremultiply<InputSubpixel, OutputSubpixel>(pub(crate) PhantomData<(InputSubpixel, OutputSubpixel)>);
     /// Grayscale pixels
     #[derive(Debug, Copy, Clone)]
     pub struct Gray<InputSubpixel, OutputSubpixel>(pub(crate) PhantomData<(InputSubpixel, OutputSubpixel)>);
+    /// Grayscale coverage masks, e.g. of glyphs and shapes. Output is clamped to the range of the input, even for floats,
+    /// so filters with negative lobes can't overshoot. It doesn't change the filter, so for area weighting, which keeps the total coverage
+    /// up to rounding, use it with [`Type::Area`](crate::Type::Area).
+    #[derive(Debug, Copy, Clone)]
+    pub struct Coverage<InputSubpixel, OutputSubpixel>(pub(crate) PhantomData<(InputSubpixel, OutputSubpixel)>);
+    /// Grayscale pixels accumulated in double precision, which is slower, but exact for 16-bit and float data with large kernels
+    #[derive(Debug, Copy, Clone)]
+    pub struct PreciseGray<InputSubpixel, OutputSubpixel>(pub(crate) PhantomData<(InputSubpixel, OutputSubpixel)>);
+    /// RGB pixels accumulated in double precision
+    #[derive(Debug, Copy, Clone)]
+    pub struct PreciseRgb<InputSubpixel, OutputSubpixel>(pub(crate) PhantomData<(InputSubpixel, OutputSubpixel)>);
+    /// RGBA pixels accumulated in double precision, each channel is independent
+    #[derive(Debug, Copy, Clone)]
+    pub struct PreciseRgba<InputSubpixel, OutputSubpixel>(pub(crate) PhantomData<(InputSubpixel, OutputSubpixel)>);
+    /// RGB pixels converted to grayscale
+    #[derive(Debug, Copy, Clone)]
+    pub struct RgbToGray<InputSubpixel, OutputSubpixel>(pub(crate) crate::px::LumaWeights, pub(crate) PhantomData<(InputSubpixel, OutputSubpixel)>);
+    /// RGBA pixels converted to grayscale, ignoring alpha
+    #[derive(Debug, Copy, Clone)]
+    pub struct RgbaToGray<InputSubpixel, OutputSubpixel>(pub(crate) crate::px::LumaWeights, pub(crate) PhantomData<(InputSubpixel, OutputSubpixel)>);
+
+    impl<I, O> RgbToGray<I, O> {
+        /// Use given weights of R, G and B components instead of the default BT.709 ones.
+        #[must_use]
+        pub const fn with_weights(self, weights: crate::px::LumaWeights) -> Self {
+            Self(weights, PhantomData)
+        }
+    }
+
+    impl<I, O> RgbaToGray<I, O> {
+        /// Use given weights of R, G and B components instead of the default BT.709 ones.
+        #[must_use]
+        pub const fn with_weights(self, weights: crate::px::LumaWeights) -> Self {
+            Self(weights, PhantomData)
+        }
+    }
+    /// CMYK pixels
+    #[derive(Debug, Copy, Clone)]
+    pub struct Cmyk<InputSubpixel, OutputSubpixel>(pub(crate) PhantomData<(InputSubpixel, OutputSubpixel)>);
+    /// `N` independent channels
+    #[derive(Debug, Copy, Clone)]
+    pub struct Channels<InputSubpixel, OutputSubpixel, const N: usize>(pub(crate) PhantomData<(InputSubpixel, OutputSubpixel)>);
+    /// Two independent channels, e.g. interleaved chroma
+    #[derive(Debug, Copy, Clone)]
+    pub struct Duo<InputSubpixel, OutputSubpixel>(pub(crate) PhantomData<(InputSubpixel, OutputSubpixel)>);
+    /// Grayscale pixels with alpha, each channel is independent. Compatible with premultiplied input/output.
+    #[derive(Debug, Copy, Clone)]
+    pub struct GrayAlpha<InputSubpixel, OutputSubpixel>(pub(crate) PhantomData<(InputSubpixel, OutputSubpixel)>);
+    /// Apply premultiplication to grayscale pixels with alpha during scaling. Assumes **non**-premultiplied input/output.
+    #[derive(Debug, Copy, Clone)]
+    pub struct GrayAlphaPremultiply<InputSubpixel, OutputSubpixel>(pub(crate) PhantomData<(InputSubpixel, OutputSubpixel)>);
+
+    macro_rules! new_format {
+        ($($name:ident),+) => {$(
+            impl<I, O> $name<I, O> {
+                /// Format reading `I` subpixels and writing `O` subpixels
+                #[must_use]
+                pub const fn instance() -> Self {
+                    Self(PhantomData)
+                }
+            }
+
+            impl<I, O> Default for $name<I, O> {
+                #[inline]
+                fn default() -> Self {
+                    Self::instance()
+                }
+            }
+        )+};
+    }
+
+    new_format!(Rgb, Rgba, RgbaPremultiply, Bgr, Bgra, BgraPremultiply, Gray, Coverage, PreciseGray, PreciseRgb, PreciseRgba, Cmyk, Duo, GrayAlpha, GrayAlphaPremultiply);
+
+    impl<I, O, const N: usize> Channels<I, O, N> {
+        /// Format reading `I` subpixels and writing `O` subpixels
+        #[must_use]
+        pub const fn instance() -> Self {
+            Self(PhantomData)
+        }
+    }
+
+    impl<I, O, const N: usize> Default for Channels<I, O, N> {
+        #[inline]
+        fn default() -> Self {
+            Self::instance()
+        }
+    }
+}
+
+/// How samples beyond the edges of the image are treated
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Edge {
+    /// Ignore them, and give more weight to pixels inside the image
+    #[default]
+    Clamp,
+    /// Image is reflected around its edges, e.g. `2 1 0 | 0 1 2`
+    Mirror,
+    /// Image repeats, for tileable textures
+    Wrap,
+    /// Pixels outside are transparent black.
+    /// Wrap the pixel format in [`px::Fill`] to use another color.
+    Constant,
+}
+
+/// How positions of destination pixels map onto the source image
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Alignment {
+    /// Centers of pixels are at half-pixel offsets, and edges of both images line up.
+    /// Same as `align_corners=False` in PyTorch and `half_pixel_centers=True` in TensorFlow.
+    #[default]
+    Centers,
+    /// Centers of the first and last pixels of both images line up. Same as `align_corners=True`.
+    Corners,
+    /// Pixel positions are scaled from the top-left corner, without the half-pixel offset.
+    /// Same as legacy TensorFlow resizing with `half_pixel_centers=False`.
+    Asymmetric,
+}
+
+impl Alignment {
+    /// Position in the source of the center of `x2`-th destination pixel
+    #[inline]
+    fn source_position(self, x2: usize, s1: usize, s2: usize) -> f64 {
+        match self {
+            Self::Centers => (x2 as f64 + 0.5) * (s1 as f64 / s2 as f64) - 0.5,
+            Self::Corners if s2 > 1 => x2 as f64 * ((s1 - 1) as f64 / (s2 - 1) as f64),
+            Self::Corners => 0.,
+            Self::Asymmetric => x2 as f64 * (s1 as f64 / s2 as f64),
+        }
+    }
+}
+
+/// Optional tweaks to resampling, see [`Resizer::with_options`]
+///
+/// ```rust
+/// let options = resize::Options::default().sharpen(0.3);
+/// ```
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[non_exhaustive]
+pub struct Options {
+    /// Strength of an unsharp mask folded into the filter weights, 0 disables it.
+    ///
+    /// Useful after big downscales, which look soft. Values around 0.1-0.5 are reasonable.
+    pub sharpen: f32,
+    /// Treatment of pixels beyond the edges. Doesn't affect [`Type::Area`].
+    pub edge: Edge,
+    /// Mapping between source and destination pixel positions. Doesn't affect [`Type::Area`].
+    pub alignment: Alignment,
+    /// Shifts sampling positions by a fraction of a source pixel on each axis, e.g. for chroma siting.
+    /// Positive values move the image left/up.
+    pub offset: (f64, f64),
+    /// Sample the filter kernel into a lookup table once, and interpolate it when computing weights.
+    ///
+    /// Speeds up creation of resizers for large images, especially with slow [`Type::Custom`] kernels,
+    /// at the cost of a tiny loss of precision.
+    pub kernel_lut: bool,
+    /// When downscaling more than 3x, first reduce the image with a fast box filter to twice the destination size,
+    /// then apply the filter. This is much faster for huge reductions, and looks nearly the same.
+    ///
+    /// [Coefficients](Resizer::horizontal_coefficients) then describe only the second step.
+    /// The box filter is [`Type::Area`], which [`Options::edge`] and [`Options::alignment`] don't affect, so these only apply to the second step,
+    /// and so does [`Options::offset`], converted to pixels of the reduced image.
+    pub multi_step: bool,
+    /// Mirror the output left to right, at no extra cost
+    pub flip_horizontal: bool,
+    /// Mirror the output top to bottom, at no extra cost
+    pub flip_vertical: bool,
+    /// Rotate the output clockwise. Flips are applied after the rotation.
+    ///
+    /// Destination dimensions are of the rotated image, so a 90° rotation of a 640×480 image to the same size is 480×640.
+    pub rotation: Rotation,
+    /// Number of threads used by `Resizer::resize_threaded` with the `threads` feature.
+    /// 0 uses as many as the machine has cores.
+    pub threads: usize,
+}
+
+/// Clockwise rotation of the output, see [`Options::rotation`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Rotation {
+    /// Upright
+    #[default]
+    None,
+    /// 90° clockwise
+    Cw90,
+    /// Upside down
+    Cw180,
+    /// 270° clockwise (90° counter-clockwise)
+    Cw270,
+}
+
+impl Options {
+    /// Sets [`Options::sharpen`]
+    #[inline]
+    #[must_use]
+    pub fn sharpen(mut self, amount: f32) -> Self {
+        self.sharpen = amount;
+        self
+    }
+
+    /// Sets [`Options::edge`]
+    #[inline]
+    #[must_use]
+    pub fn edge(mut self, edge: Edge) -> Self {
+        self.edge = edge;
+        self
+    }
+
+    /// Sets [`Options::alignment`]
+    #[inline]
+    #[must_use]
+    pub fn alignment(mut self, alignment: Alignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    /// Sets [`Options::offset`]
+    #[inline]
+    #[must_use]
+    pub fn offset(mut self, x: f64, y: f64) -> Self {
+        self.offset = (x, y);
+        self
+    }
+
+    /// Sets [`Options::kernel_lut`]
+    #[inline]
+    #[must_use]
+    pub fn kernel_lut(mut self, kernel_lut: bool) -> Self {
+        self.kernel_lut = kernel_lut;
+        self
+    }
+
+    /// Sets [`Options::multi_step`]
+    #[inline]
+    #[must_use]
+    pub fn multi_step(mut self, multi_step: bool) -> Self {
+        self.multi_step = multi_step;
+        self
+    }
+
+    /// Sets [`Options::flip_horizontal`] and [`Options::flip_vertical`]
+    #[inline]
+    #[must_use]
+    pub fn flip(mut self, horizontal: bool, vertical: bool) -> Self {
+        self.flip_horizontal = horizontal;
+        self.flip_vertical = vertical;
+        self
+    }
+
+    /// Sets [`Options::rotation`]
+    #[inline]
+    #[must_use]
+    pub fn rotation(mut self, rotation: Rotation) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// Sets [`Options::threads`]
+    #[inline]
+    #[must_use]
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    /// Whether rows of the scaled image become columns of the output
+    #[inline]
+    fn transposes(&self) -> bool {
+        matches!(self.rotation, Rotation::Cw90 | Rotation::Cw270)
+    }
+
+    /// Whether lines of horizontal and vertical coefficients are in reverse order, to rotate and flip the output
+    fn reversed_lines(&self) -> (bool, bool) {
+        let (x, y) = (self.flip_horizontal, self.flip_vertical);
+        // When transposed, vertical lines make columns of the output
+        match self.rotation {
+            Rotation::None => (x, y),
+            Rotation::Cw90 => (y, !x),
+            Rotation::Cw180 => (!x, !y),
+            Rotation::Cw270 => (!y, x),
+        }
+    }
 }
 
 /// Resampler with preallocated buffers and coeffecients for the given
@@ -244,19 +1117,49 @@ pub mod formats {
 #[derive(Debug)]
 pub struct Resizer<Format: PixelFormat> {
     scale: Scale,
+    /// Box reduction done before `scale` with [`Options::multi_step`]
+    prescale: Option<Scale>,
     pix_fmt: Format,
-    // Temporary/preallocated stuff.
+    /// Settings needed to recompute the coefficients. `None` filters for custom closures or tables.
+    options: Options,
+    filters: Option<[FilterKey; 2]>,
+    // Temporary/preallocated stuff, see `scratch_len`.
     tmp: Vec<Format::Accumulator>,
 }
 
-#[derive(Debug)]
+/// Accumulator of a single source pixel with weight 1
+#[inline(always)]
+fn copied<Format: PixelFormat>(pix_fmt: &Format, inp: Format::InputPixel) -> Format::Accumulator {
+    let mut accum = Format::new();
+    pix_fmt.add(&mut accum, inp, 1.);
+    let mut total = Format::new();
+    Format::add_acc(&mut total, accum, 1.);
+    total
+}
+
+/// Bytes of `tmp` rows that the vertical pass may read for one output row, sized for a typical L2 cache
+const STRIP_CACHE_BYTES: usize = 256 * 1024;
+/// Rows of one strip resampled before moving on to the next strip, and then reporting progress
+const STRIP_BAND_ROWS: usize = 4;
+
+#[derive(Debug, Clone)]
 struct Scale {
     /// Source dimensions.
     w1: NonZeroUsize,
     h1: NonZeroUsize,
+    /// Rows of the scaled image are written as columns of the output, for rotation
+    transpose: bool,
     /// Vec's len == target dimensions
     coeffs_w: Vec<CoeffsLine>,
     coeffs_h: Vec<CoeffsLine>,
+    /// Output position of the first pixel in the whole image, when this is only a tile of it
+    origin: [usize; 2],
+    /// Source column and row of every output column and row, when each copies a single pixel, like with [`Type::Point`]
+    index_maps: Option<[Vec<usize>; 2]>,
+    /// Every output pixel is a copy of the source pixel at the same position
+    identity: bool,
+    /// Width and height of blocks of source pixels that every output pixel is an average of, like with [`Type::Area`] and integer ratios
+    blocks: Option<[usize; 2]>,
 }
 
 impl Scale {
@@ -277,58 +1180,255 @@ struct CoeffsLine {
     coeffs: Arc<[f32]>,
 }
 
-type DynCallback<'a> = &'a dyn Fn(f32) -> f32;
-
-impl Scale {
-    pub fn new(source_width: usize, source_heigth: usize, dest_width: usize, dest_height: usize, filter_type: Type) -> Result<Self> {
-        let source_width = NonZeroUsize::new(source_width).ok_or(Error::InvalidParameters)?;
-        let source_heigth = NonZeroUsize::new(source_heigth).ok_or(Error::InvalidParameters)?;
-        if dest_width == 0 || dest_height == 0 {
-            return Err(Error::InvalidParameters);
+impl CoeffsLine {
+    /// The source pixel that the line copies, if it has a single weight of 1.
+    /// Kernels like sinc aren't exactly 0 at integer positions, so tiny weights are ignored.
+    fn copied_pixel(&self) -> Option<usize> {
+        let mut weights = self.coeffs.iter().enumerate().filter(|(_, w)| w.abs() >= 1e-6);
+        match (weights.next(), weights.next()) {
+            (Some((i, w)), None) if (w - 1.).abs() < 1e-6 => Some(self.start + i),
+            _ => None,
         }
-        let filter = match filter_type {
-            Type::Point => (&point_kernel as DynCallback, 0.0_f32),
-            Type::Triangle => (&triangle_kernel as DynCallback, 1.0),
-            Type::Catrom => ((&|x| cubic_bc(0.0, 0.5, x)) as DynCallback, 2.0),
-            Type::Mitchell => ((&|x| cubic_bc(1.0/3.0, 1.0/3.0, x)) as DynCallback, 2.0),
-            Type::Lanczos3 => ((&|x| lanczos(3.0, x)) as DynCallback, 3.0),
-            Type::Custom(ref f) => (&f.kernel as DynCallback, f.support),
-        };
+    }
+}
 
-        // filters very often create repeating patterns,
-        // so overall memory used by them can be reduced
-        // which should save some cache space
-        let mut recycled_coeffs = TryHashMap::with_capacity(dest_width.max(dest_height))?;
+type DynCallback<'a> = &'a dyn Fn(f32) -> f32;
 
-        let coeffs_w = Self::calc_coeffs(source_width, dest_width, filter, &mut recycled_coeffs)?;
-        let coeffs_h = if source_heigth == source_width && dest_height == dest_width {
-            coeffs_w.clone()
-        } else {
-            Self::calc_coeffs(source_heigth, dest_height, filter, &mut recycled_coeffs)?
+/// How coefficients are computed
+#[derive(Copy, Clone)]
+enum Filtering<'a> {
+    /// Kernel sampled at source pixel centers, with its support
+    Kernel(DynCallback<'a>, f32),
+    /// Exact area coverage
+    Area,
+}
+/// Kernel sampled at regular intervals over its support
+struct KernelLut {
+    samples: Vec<f32>,
+    support: f32,
+    per_unit: f32,
+}
+
+impl KernelLut {
+    /// Samples per unit of the kernel's support
+    const RESOLUTION: f32 = 1024.;
+    /// Limit for very wide kernels, like Gaussian with a large sigma
+    const MAX_SAMPLES: usize = 1 << 16;
+
+    fn new(kernel: DynCallback<'_>, support: f32) -> Result<Self> {
+        let len = ((2. * support * Self::RESOLUTION) as usize).min(Self::MAX_SAMPLES) + 1;
+        let per_unit = (len - 1) as f32 / (2. * support);
+        let mut samples: Vec<f32> = FallibleVec::try_with_capacity(len)?;
+        samples.extend((0..len).map(|i| (kernel)(i as f32 / per_unit - support)));
+        Ok(Self { samples, support, per_unit })
+    }
+
+    /// Linearly interpolated value of the kernel at `x`
+    #[inline]
+    fn sample(&self, x: f32) -> f32 {
+        let pos = (x.max(-self.support).min(self.support) + self.support) * self.per_unit;
+        let i = (pos as usize).min(self.samples.len() - 2);
+        let frac = pos - i as f32;
+        self.samples[i] * (1. - frac) + self.samples[i + 1] * frac
+    }
+}
+
+/// Called with the number of destination rows written so far. Resizing stops on `Break`.
+type Progress<'a> = &'a mut dyn FnMut(usize) -> ControlFlow<()>;
+
+/// Whether rows of the source and destination buffers are stored bottom-up, like in BMP files
+#[derive(Debug, Copy, Clone, Default)]
+struct RowOrder {
+    src_bottom_up: bool,
+    dst_bottom_up: bool,
+}
+
+impl RowOrder {
+    /// Index in memory of the source row `y` of an image `height` rows tall
+    #[inline(always)]
+    fn src_row(self, y: usize, height: usize) -> usize {
+        if self.src_bottom_up { height - 1 - y } else { y }
+    }
+
+    #[inline(always)]
+    fn dst_row(self, y: usize, height: usize) -> usize {
+        if self.dst_bottom_up { height - 1 - y } else { y }
+    }
+}
+
+type RecycledCoeffs = TryHashMap<(usize, [u8; 4], [u8; 4]), Arc<[f32]>>;
+
+impl Scale {
+    pub fn new(source_width: usize, source_heigth: usize, dest_width: usize, dest_height: usize, filter_type: &Type) -> Result<Self> {
+        Self::with_options(source_width, source_heigth, dest_width, dest_height, filter_type, filter_type, &Options::default())
+    }
+
+    pub fn with_options(source_width: usize, source_heigth: usize, dest_width: usize, dest_height: usize, filter_x: &Type, filter_y: &Type, options: &Options) -> Result<Self> {
+        Self::with_cache(source_width, source_heigth, dest_width, dest_height, filter_x, filter_y, options, None)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn with_cache(source_width: usize, source_heigth: usize, dest_width: usize, dest_height: usize, filter_x: &Type, filter_y: &Type, options: &Options, mut cache: Option<&mut CoefficientCache>) -> Result<Self> {
+        let source_width = NonZeroUsize::new(source_width).ok_or(Error::InvalidParameters)?;
+        let source_heigth = NonZeroUsize::new(source_heigth).ok_or(Error::InvalidParameters)?;
+        if dest_width == 0 || dest_height == 0 {
+            return Err(Error::InvalidParameters);
+        }
+        if !options.sharpen.is_finite() || options.sharpen < 0. {
+            return Err(Error::InvalidParameters);
+        }
+        let (offset_x, offset_y) = options.offset;
+        if !offset_x.is_finite() || !offset_y.is_finite() {
+            return Err(Error::InvalidParameters);
+        }
+
+        let (dest_width, dest_height) = if options.transposes() { (dest_height, dest_width) } else { (dest_width, dest_height) };
+        span!(DEBUG, "coefficients", source_width, source_heigth, dest_width, dest_height, filter_x = filter_x.name(), filter_y = filter_y.name());
+        // Source, destination, and the image between the passes
+        check_area(source_width.get(), source_heigth.get())?;
+        check_area(dest_width, dest_height)?;
+        check_area(dest_width, source_heigth.get())?;
+
+        // filters very often create repeating patterns,
+        // so overall memory used by them can be reduced
+        // which should save some cache space
+        let mut recycled_coeffs = TryHashMap::with_capacity(dest_width.max(dest_height))?;
+
+        let mut coeffs_w = Self::axis_coeffs(source_width, dest_width, filter_x, options, offset_x, &mut recycled_coeffs, cache.as_deref_mut())?;
+        let mut coeffs_h = if source_heigth == source_width && dest_height == dest_width && offset_x == offset_y && core::ptr::eq(filter_x, filter_y) {
+            coeffs_w.clone()
+        } else {
+            Self::axis_coeffs(source_heigth, dest_height, filter_y, options, offset_y, &mut recycled_coeffs, cache)?
         };
+        // Flipped output just takes the lines in reverse order
+        let (reverse_w, reverse_h) = options.reversed_lines();
+        if reverse_w {
+            coeffs_w.reverse();
+        }
+        if reverse_h {
+            coeffs_h.reverse();
+        }
 
-        Ok(Self {
-            w1: source_width,
-            h1: source_heigth,
-            coeffs_w,
-            coeffs_h,
-        })
+        Self::from_lines(source_width, source_heigth, options.transposes(), coeffs_w, coeffs_h)
+    }
+
+    /// Scale with the given coefficients, starting at the top-left corner of the output
+    fn from_lines(w1: NonZeroUsize, h1: NonZeroUsize, transpose: bool, coeffs_w: Vec<CoeffsLine>, coeffs_h: Vec<CoeffsLine>) -> Result<Self> {
+        let index_map = |lines: &[CoeffsLine]| -> Result<Option<Vec<usize>>> {
+            if !lines.iter().all(|l| l.copied_pixel().is_some()) {
+                return Ok(None);
+            }
+            let mut map: Vec<usize> = FallibleVec::try_with_capacity(lines.len())?;
+            map.extend(lines.iter().filter_map(CoeffsLine::copied_pixel));
+            Ok(Some(map))
+        };
+        let index_maps = match (index_map(&coeffs_w)?, index_map(&coeffs_h)?) {
+            (Some(x_map), Some(y_map)) => Some([x_map, y_map]),
+            _ => None,
+        };
+        let same = |map: &[usize], len: NonZeroUsize| map.len() == len.get() && map.iter().enumerate().all(|(i, &j)| i == j);
+        let identity = !transpose && matches!(&index_maps, Some([x_map, y_map]) if same(x_map, w1) && same(y_map, h1));
+        let factor = |lines: &[CoeffsLine], len: NonZeroUsize| {
+            let factor = len.get() / lines.len().max(1);
+            let weight = 1. / factor as f32;
+            let averages = factor * lines.len() == len.get() && lines.iter().enumerate().all(|(i, l)| {
+                l.start == i * factor && l.coeffs.len() == factor && l.coeffs.iter().all(|&w| (w - weight).abs() < 1e-6)
+            });
+            averages.then_some(factor)
+        };
+        let blocks = match (factor(&coeffs_w, w1), factor(&coeffs_h, h1)) {
+            (Some(factor_x), Some(factor_y)) if !transpose && factor_x * factor_y > 1 => Some([factor_x, factor_y]),
+            _ => None,
+        };
+        Ok(Self { w1, h1, transpose, coeffs_w, coeffs_h, origin: [0, 0], index_maps, identity, blocks })
+    }
+
+    /// Final (sharpened) coefficients of one axis, taken from the `cache` if it has them
+    fn axis_coeffs(s1: NonZeroUsize, s2: usize, filter_type: &Type, options: &Options, offset: f64, recycled_coeffs: &mut RecycledCoeffs, cache: Option<&mut CoefficientCache>) -> Result<Vec<CoeffsLine>> {
+        let cached = cache.and_then(|cache| Some((AxisKey::new(s1.get(), s2, filter_type, options, offset)?, cache)));
+        if let Some((key, cache)) = &cached {
+            if let Some(lines) = cache.get(key) {
+                return Ok(lines);
+            }
+        }
+        let mut lines = Self::calc_filter_coeffs(s1, s2, filter_type, options, offset, recycled_coeffs)?;
+        if options.sharpen > 0. {
+            lines = Self::sharpen_coeffs(&lines, options.sharpen)?;
+        }
+        if let Some((key, cache)) = cached {
+            cache.insert(key, lines.clone())?;
+        }
+        Ok(lines)
+    }
+
+    fn calc_filter_coeffs(s1: NonZeroUsize, s2: usize, filter_type: &Type, options: &Options, offset: f64, recycled_coeffs: &mut RecycledCoeffs) -> Result<Vec<CoeffsLine>> {
+        let filter = match *filter_type {
+            Type::Point => Filtering::Kernel(&point_kernel, 0.0_f32),
+            Type::Triangle => Filtering::Kernel(&triangle_kernel, 1.0),
+            Type::Catrom => Filtering::Kernel(&|x| cubic_bc(0.0, 0.5, x), 2.0),
+            Type::Mitchell => Filtering::Kernel(&|x| cubic_bc(1.0/3.0, 1.0/3.0, x), 2.0),
+            Type::BSpline => Filtering::Kernel(&|x| cubic_bc(1.0, 0.0, x), 2.0),
+            Type::Hermite => Filtering::Kernel(&|x| cubic_bc(0.0, 0.0, x), 1.0),
+            Type::Cubic { b, c } => Filtering::Kernel(&move |x| cubic_bc(b, c, x), 2.0),
+            Type::Lanczos3 => Filtering::Kernel(&|x| lanczos(3.0, x), 3.0),
+            Type::Gaussian(sigma) if !sigma.is_finite() || sigma <= 0. => return Err(Error::InvalidParameters),
+            Type::Gaussian(sigma) => Filtering::Kernel(&move |x| gaussian(sigma, x), 3.0 * sigma),
+            Type::WindowedSinc(_, radius) if !radius.is_finite() || radius <= 0. => return Err(Error::InvalidParameters),
+            Type::WindowedSinc(window, radius) => Filtering::Kernel(&move |x| windowed_sinc(window, radius, x), radius),
+            Type::Spline16 => Filtering::Kernel(&spline16, 2.0),
+            Type::Spline36 => Filtering::Kernel(&spline36, 3.0),
+            Type::Spline64 => Filtering::Kernel(&spline64, 4.0),
+            Type::MagicKernelSharp2013 => Filtering::Kernel(&magic_kernel_sharp_2013, 2.5),
+            Type::MagicKernelSharp2021 => Filtering::Kernel(&magic_kernel_sharp_2021, 4.5),
+            Type::Area => Filtering::Area,
+            Type::Custom(ref f) => Filtering::Kernel(&f.kernel, f.support),
+        };
+        match filter {
+            Filtering::Kernel(kernel, support) if options.kernel_lut && support > 0. => {
+                let lut = KernelLut::new(kernel, support)?;
+                Self::calc_coeffs(s1, s2, Filtering::Kernel(&|x| lut.sample(x), support), options, offset, recycled_coeffs)
+            },
+            _ => Self::calc_coeffs(s1, s2, filter, options, offset, recycled_coeffs),
+        }
     }
 
-    fn calc_coeffs(s1: NonZeroUsize, s2: usize, (kernel, support): (&dyn Fn(f32) -> f32, f32), recycled_coeffs: &mut TryHashMap<(usize, [u8; 4], [u8; 4]), Arc<[f32]>>) -> Result<Vec<CoeffsLine>> {
+    /// Limit of footprints that cross the edges, in multiples of the source size.
+    /// Built-in kernels with a fixed support are narrower than this even when downscaling to a single pixel.
+    const MAX_EDGE_RADIUS: f64 = 8.;
+
+    fn calc_coeffs(s1: NonZeroUsize, s2: usize, filter: Filtering<'_>, options: &Options, offset: f64, recycled_coeffs: &mut RecycledCoeffs) -> Result<Vec<CoeffsLine>> {
+        let (kernel, support) = match filter {
+            Filtering::Kernel(kernel, support) => (kernel, support),
+            Filtering::Area => return Self::calc_area_coeffs(s1, s2, offset, recycled_coeffs),
+        };
         let ratio = s1.get() as f64 / s2 as f64;
         // Scale the filter when downsampling.
         let filter_scale = ratio.max(1.);
         let filter_radius = unsafe { ceil(support as f64 * filter_scale) };
-        let mut res = Vec::try_with_capacity(s2)?;
+        let mut res: Vec<_> = FallibleVec::try_with_capacity(s2)?;
         for x2 in 0..s2 {
-            let x1 = (x2 as f64 + 0.5) * ratio - 0.5;
+            let x1 = options.alignment.source_position(x2, s1.get(), s2) + offset;
+            if options.edge != Edge::Clamp {
+                // Footprints that cross the edges are walked pixel by pixel, so kernels that are much wider
+                // than the image, or even infinite, are cut off at a few times its size
+                let radius = filter_radius.min(Self::MAX_EDGE_RADIUS * s1.get() as f64);
+                let first = unsafe { ceil(x1 - radius) } as isize;
+                let last = unsafe { floor(x1 + radius) } as isize;
+                if first < 0 || last >= s1.get() as isize {
+                    res.push(Self::calc_edge_coeffs(s1, options.edge, first..=last, |i| {
+                        let n = ((i as f64 - x1) / filter_scale) as f32;
+                        (kernel)(n.min(support).max(-support))
+                    })?);
+                    continue;
+                }
+            }
             let start = unsafe { ceil(x1 - filter_radius) } as isize;
             let start = start.min(s1.get() as isize - 1).max(0) as usize;
             let end = unsafe { floor(x1 + filter_radius) } as isize;
             let end = (end.min(s1.get() as isize - 1).max(0) as usize).max(start);
             let sum: f64 = (start..=end).map(|i| (kernel)(((i as f64 - x1) / filter_scale) as f32) as f64).sum();
-            let key = (end - start, (filter_scale as f32).to_ne_bytes(), (start as f32 - x1 as f32).to_ne_bytes());
+            // Phase is computed in f64, since f32 positions lose precision in very wide images
+            let key = (end - start, (filter_scale as f32).to_ne_bytes(), ((start as f64 - x1) as f32).to_ne_bytes());
             let coeffs = if let Some(k) = recycled_coeffs.get(&key) { k.clone() } else {
                 let tmp = (start..=end).map(|i| {
                     let n = ((i as f64 - x1) / filter_scale) as f32;
@@ -341,160 +1441,962 @@ impl Scale {
         }
         Ok(res)
     }
+
+    /// Table of one axis from `(start, weights)` pairs, checked against the source size
+    fn lines_from<W: AsRef<[f32]>>(source: NonZeroUsize, table: impl IntoIterator<Item = (usize, W)>) -> Result<Vec<CoeffsLine>> {
+        let table = table.into_iter();
+        let mut res: Vec<_> = FallibleVec::try_with_capacity(table.size_hint().0)?;
+        for (start, weights) in table {
+            let weights = weights.as_ref();
+            let end = start.checked_add(weights.len()).ok_or(Error::InvalidParameters)?;
+            if weights.is_empty() || end > source.get() {
+                return Err(Error::InvalidParameters);
+            }
+            FallibleVec::try_push(&mut res, CoeffsLine { start, coeffs: weights.into() })?;
+        }
+        if res.is_empty() {
+            return Err(Error::InvalidParameters);
+        }
+        Ok(res)
+    }
+
+    /// Part of the scale that computes only `cols` and `rows` of the output.
+    /// Returns it with the first source column and row that it reads, since its coefficients are relative to them.
+    fn tile(&self, cols: core::ops::Range<usize>, rows: core::ops::Range<usize>) -> Result<(Self, usize, usize)> {
+        if self.transpose {
+            return Err(Error::InvalidParameters);
+        }
+        let origin = [self.origin[0] + cols.start, self.origin[1] + rows.start];
+        let (x, w1, coeffs_w) = Self::sub_lines(&self.coeffs_w, cols)?;
+        let (y, h1, coeffs_h) = Self::sub_lines(&self.coeffs_h, rows)?;
+        Ok((Self { origin, ..Self::from_lines(w1, h1, false, coeffs_w, coeffs_h)? }, x, y))
+    }
+
+    /// Coefficient lines in `range`, shifted to start at the first source pixel any of them reads
+    fn sub_lines(lines: &[CoeffsLine], range: core::ops::Range<usize>) -> Result<(usize, NonZeroUsize, Vec<CoeffsLine>)> {
+        let lines = lines.get(range).filter(|l| !l.is_empty()).ok_or(Error::InvalidParameters)?;
+        let first = lines.iter().map(|l| l.start).min().unwrap_or(0);
+        let end = lines.iter().map(|l| l.start + l.coeffs.len()).max().unwrap_or(0);
+        let mut res: Vec<_> = FallibleVec::try_with_capacity(lines.len())?;
+        res.extend(lines.iter().map(|l| CoeffsLine { start: l.start - first, coeffs: l.coeffs.clone() }));
+        Ok((first, NonZeroUsize::new(end - first).ok_or(Error::InvalidParameters)?, res))
+    }
+
+    /// Weights of a footprint that crosses the image edges, which are mapped back into the image
+    /// according to the `edge` mode. These aren't recycled, since they're unique to each side.
+    fn calc_edge_coeffs(s1: NonZeroUsize, edge: Edge, footprint: core::ops::RangeInclusive<isize>, weight: impl Fn(isize) -> f32) -> Result<CoeffsLine> {
+        let n = s1.get() as isize;
+        let map = |i: isize| match edge {
+            Edge::Mirror => {
+                let m = i.rem_euclid(2 * n);
+                Some(if m >= n { 2 * n - 1 - m } else { m } as usize)
+            },
+            Edge::Wrap => Some(i.rem_euclid(n) as usize),
+            Edge::Clamp | Edge::Constant => if (0..n).contains(&i) { Some(i as usize) } else { None },
+        };
+        let sum: f64 = footprint.clone().map(|i| weight(i) as f64).sum();
+        let start = footprint.clone().filter_map(map).min().unwrap_or(0);
+        let end = footprint.clone().filter_map(map).max().unwrap_or(0);
+        let mut coeffs: Vec<f32> = FallibleVec::try_with_capacity(end + 1 - start)?;
+        coeffs.resize(end + 1 - start, 0.);
+        for i in footprint {
+            if let Some(mapped) = map(i) {
+                coeffs[mapped - start] += (weight(i) as f64 / sum) as f32;
+            }
+        }
+        Ok(CoeffsLine { start, coeffs: coeffs.into() })
+    }
+
+    /// Weights are areas of source pixels covered by each destination pixel
+    fn calc_area_coeffs(s1: NonZeroUsize, s2: usize, offset: f64, recycled_coeffs: &mut RecycledCoeffs) -> Result<Vec<CoeffsLine>> {
+        let ratio = s1.get() as f64 / s2 as f64;
+        let s1f = s1.get() as f64;
+        let mut res: Vec<_> = FallibleVec::try_with_capacity(s2)?;
+        for x2 in 0..s2 {
+            let left = (x2 as f64 * ratio + offset).max(0.).min(s1f);
+            let right = ((x2 + 1) as f64 * ratio + offset).max(0.).min(s1f);
+            let start = (unsafe { floor(left) } as usize).min(s1.get() - 1);
+            let end = (unsafe { ceil(right) } as usize).min(s1.get()).max(start + 1);
+            let key = (end - start, (ratio as f32).to_ne_bytes(), ((start as f64 - left) as f32).to_ne_bytes());
+            let coeffs = if let Some(k) = recycled_coeffs.get(&key) { k.clone() } else {
+                let covered = |i: usize| (right.min(i as f64 + 1.) - left.max(i as f64)).max(0.);
+                let sum: f64 = (start..end).map(covered).sum();
+                let tmp = if sum > 0. {
+                    (start..end).map(|i| (covered(i) / sum) as f32).collect::<Arc<[_]>>()
+                } else {
+                    // Footprint shifted outside of the image, so it's clamped to the edge
+                    (start..end).map(|i| if i == start { 1. } else { 0. }).collect::<Arc<[_]>>()
+                };
+                recycled_coeffs.insert(key, tmp.clone())?;
+                tmp
+            };
+            res.push(CoeffsLine { start, coeffs });
+        }
+        Ok(res)
+    }
+
+    /// Folds a `[-a, 1+2a, -a]` unsharp mask across neighbouring destination pixels into the weights,
+    /// so sharpening costs no extra pass over the image. Weights still sum to 1.
+    fn sharpen_coeffs(lines: &[CoeffsLine], amount: f32) -> Result<Vec<CoeffsLine>> {
+        let last = lines.len() - 1;
+        let mut res: Vec<_> = FallibleVec::try_with_capacity(lines.len())?;
+        for (i, line) in lines.iter().enumerate() {
+            let taps = [(&lines[i.saturating_sub(1)], -amount), (line, 1. + 2. * amount), (&lines[(i + 1).min(last)], -amount)];
+            let start = taps.iter().map(|(l, _)| l.start).min().unwrap_or(line.start);
+            let end = taps.iter().map(|(l, _)| l.start + l.coeffs.len()).max().unwrap_or(line.start);
+            let mut coeffs: Vec<f32> = FallibleVec::try_with_capacity(end - start)?;
+            coeffs.resize(end - start, 0.);
+            for (l, weight) in taps.iter() {
+                for (c, k) in coeffs[l.start - start..].iter_mut().zip(l.coeffs.iter()) {
+                    *c += weight * k;
+                }
+            }
+            res.push(CoeffsLine { start, coeffs: coeffs.into() });
+        }
+        Ok(res)
+    }
 }
 
-impl<Format: PixelFormat> Resizer<Format> {
-    /// Create a new resizer instance.
+/// Number of pixels spanned by `height` rows of `width` pixels that are `stride` apart,
+/// or `None` if it can't be addressed
+#[inline]
+pub(crate) fn image_len(width: usize, height: usize, stride: usize) -> Option<usize> {
+    stride.checked_mul(height.checked_sub(1)?)?.checked_add(width)
+}
+
+/// Checks that an image of `width`×`height` pixels fits in memory,
+/// so that lengths of buffers never overflow when resizing
+pub(crate) fn check_area(width: usize, height: usize) -> Result<()> {
+    match width.checked_mul(height) {
+        Some(len) if len <= isize::MAX as usize => Ok(()),
+        _ => Err(Error::InvalidParameters),
+    }
+}
+
+impl Scale {
+    /// Like `check_buffers`, but for destination rows `dst_stride` apart
+    fn check_strided_buffers(&self, src_len: usize, src_stride: NonZeroUsize, dst_len: usize, dst_stride: NonZeroUsize) -> Result<()> {
+        let (width, height) = self.dest_dimensions();
+        if dst_stride.get() < width || !matches!(image_len(width, height, dst_stride.get()), Some(len) if dst_len >= len) {
+            return Err(Error::InvalidParameters);
+        }
+        self.check_buffers(src_len, src_stride, width * height)
+    }
+
+    /// Dimensions of the output image, which are swapped if it's `transpose`d
     #[inline]
-    pub fn new(source_width: usize, source_heigth: usize, dest_width: usize, dest_height: usize, pixel_format: Format, filter_type: Type) -> Result<Self> {
-        Ok(Self {
-            scale: Scale::new(source_width, source_heigth, dest_width, dest_height, filter_type)?,
-            tmp: Vec::new(),
-            pix_fmt: pixel_format,
-        })
+    fn dest_dimensions(&self) -> (usize, usize) {
+        if self.transpose { (self.h2(), self.w2()) } else { (self.w2(), self.h2()) }
+    }
+
+    /// Validates that the buffers are large enough for these dimensions
+    fn check_buffers(&self, src_len: usize, src_stride: NonZeroUsize, dst_len: usize) -> Result<()> {
+        if self.w1.get() > src_stride.get() ||
+            !matches!(image_len(self.w1.get(), self.h1.get(), src_stride.get()), Some(len) if src_len >= len) ||
+            dst_len != self.w2() * self.h2() {
+                return Err(Error::InvalidParameters)
+            }
+        Ok(())
+    }
+
+    /// Converts pixels to the output format without resampling, with the same result as resampling with identity weights.
+    /// Rows are copied as they are when the format allows it.
+    #[allow(clippy::too_many_arguments)]
+    fn convert_only<Format: PixelFormat>(&self, pix_fmt: &Format, src: &[Format::InputPixel], stride: NonZeroUsize, dst: &mut [Format::OutputPixel], dst_stride: usize, order: RowOrder, progress: Progress<'_>) -> Result<ControlFlow<()>> {
+        for y in 0..self.h1.get() {
+            self.convert_row(pix_fmt, src, stride, dst, dst_stride, order, 0..self.w1.get(), y);
+            if progress(y + 1).is_break() {
+                return Ok(ControlFlow::Break(()));
+            }
+        }
+        Ok(ControlFlow::Continue(()))
+    }
+
+    /// Writes the `cols` of the `y`th row of `convert_only`
+    #[allow(clippy::too_many_arguments)]
+    fn convert_row<Format: PixelFormat>(&self, pix_fmt: &Format, src: &[Format::InputPixel], stride: NonZeroUsize, dst: &mut [Format::OutputPixel], dst_stride: usize, order: RowOrder, cols: core::ops::Range<usize>, y: usize) {
+        let h1 = self.h1.get();
+        let src_row = &src[order.src_row(y, h1) * stride.get()..][cols.clone()];
+        let dst_y = order.dst_row(y, h1);
+        let dst_row = &mut dst[dst_y * dst_stride..][cols.clone()];
+        if !pix_fmt.copy_pixels(src_row, dst_row) {
+            for (x, (&inp, out)) in cols.zip(src_row.iter().zip(dst_row)) {
+                pix_fmt.write_pixel(out, copied(pix_fmt, inp), self.origin[0] + x, self.origin[1] + dst_y);
+            }
+        }
+    }
+
+    /// Converts the source pixels picked by `index_maps` without resampling, with the same result as resampling with their weights of 1
+    #[allow(clippy::too_many_arguments)]
+    fn gather<Format: PixelFormat>(&self, maps: &[Vec<usize>; 2], pix_fmt: &Format, src: &[Format::InputPixel], stride: NonZeroUsize, dst: &mut [Format::OutputPixel], dst_stride: usize, order: RowOrder, progress: Progress<'_>) -> Result<ControlFlow<()>> {
+        for y in 0..maps[1].len() {
+            self.gather_row(maps, pix_fmt, src, stride, dst, dst_stride, order, 0..maps[0].len(), y);
+            if progress(y + 1).is_break() {
+                return Ok(ControlFlow::Break(()));
+            }
+        }
+        Ok(ControlFlow::Continue(()))
+    }
+
+    /// Writes the `cols` of the `y`th row of `gather`
+    #[allow(clippy::too_many_arguments)]
+    fn gather_row<Format: PixelFormat>(&self, [x_map, y_map]: &[Vec<usize>; 2], pix_fmt: &Format, src: &[Format::InputPixel], stride: NonZeroUsize, dst: &mut [Format::OutputPixel], dst_stride: usize, order: RowOrder, cols: core::ops::Range<usize>, y: usize) {
+        let (h1, dest_height) = (self.h1.get(), self.dest_dimensions().1);
+        let src_row = &src[order.src_row(y_map[y], h1) * stride.get()..];
+        for (x, &src_x) in cols.clone().zip(&x_map[cols]) {
+            // The row becomes a column of the output when transposed
+            let (col, row) = if self.transpose { (y, order.dst_row(x, dest_height)) } else { (x, order.dst_row(y, dest_height)) };
+            pix_fmt.write_pixel(&mut dst[row * dst_stride + col], copied(pix_fmt, src_row[src_x]), self.origin[0] + col, self.origin[1] + row);
+        }
     }
 
     /// Stride is a length of the source row (>= W1)
-    fn resample_both_axes(&mut self, src: &[Format::InputPixel], stride: NonZeroUsize, mut dst: &mut [Format::OutputPixel]) -> Result<()> {
-        self.tmp.clear();
-        FallibleVec::try_reserve(&mut self.tmp, self.scale.w2() * self.scale.h1.get())?;
-
-        // Outer loop resamples W2xH1 to W2xH2
-        let mut src_rows = src.chunks(stride.get());
-        for row in &self.scale.coeffs_h {
-            let w2 = self.scale.w2();
-
-            // Inner loop resamples W1xH1 to W2xH1,
-            // but only as many rows as necessary to write a new line
-            // to the output
-            while self.tmp.len() < w2 * (row.start + row.coeffs.len()) {
-                let row = src_rows.next().unwrap();
-                let pix_fmt = &self.pix_fmt;
-                self.tmp.extend(self.scale.coeffs_w.iter().map(|col| {
-                    let mut accum = Format::new();
-                    let in_px = &row[col.start..col.start + col.coeffs.len()];
-                    for (coeff, in_px) in col.coeffs.iter().copied().zip(in_px.iter().copied()) {
-                        pix_fmt.add(&mut accum, in_px, coeff)
-                    }
-                    accum
-                }));
+    fn resample_both_axes<Format: PixelFormat>(&self, pix_fmt: &Format, tmp: &mut Vec<Format::Accumulator>, src: &[Format::InputPixel], stride: NonZeroUsize, dst: &mut [Format::OutputPixel]) -> Result<()> {
+        let tmp_len = self.tmp_len();
+        if let Some(missing) = tmp_len.checked_sub(tmp.len()).filter(|&n| n > 0) {
+            FallibleVec::try_reserve(tmp, missing)?;
+            tmp.resize(tmp_len, Format::new());
+        }
+        self.resample_into(pix_fmt, tmp, src, stride, dst, self.dest_dimensions().0, RowOrder::default(), &mut |_| ControlFlow::Continue(())).map(drop)
+    }
+
+    /// Averages blocks of source pixels with integer arithmetic, or returns `None` before writing anything if the format can't
+    #[allow(clippy::too_many_arguments)]
+    fn average_blocks<Format: PixelFormat>(&self, factors: [usize; 2], pix_fmt: &Format, src: &[Format::InputPixel], stride: NonZeroUsize, dst: &mut [Format::OutputPixel], dst_stride: usize, order: RowOrder, progress: Progress<'_>) -> Option<ControlFlow<()>> {
+        let [factor_x, factor_y] = factors;
+        let (h1, w2, h2) = (self.h1.get(), self.w2(), self.h2());
+        for y in 0..h2 {
+            // Rows of a block are reversed in bottom-up images
+            let top = order.src_row(y * factor_y, h1).min(order.src_row((y + 1) * factor_y - 1, h1));
+            let src_rows = &src[top * stride.get()..];
+            for (x, out) in dst[order.dst_row(y, h2) * dst_stride..][..w2].iter_mut().enumerate() {
+                *out = pix_fmt.average_block(&src_rows[x * factor_x..], stride.get(), factors)?;
             }
+            if progress(y + 1).is_break() {
+                return Some(ControlFlow::Break(()));
+            }
+        }
+        Some(ControlFlow::Continue(()))
+    }
 
-            let tmp_rows = &self.tmp[w2 * row.start..];
-            for (col, dst_px) in dst[0..w2].iter_mut().enumerate() {
-                let mut accum = Format::new();
-                for (coeff, other_row) in row.coeffs.iter().copied().zip(tmp_rows.chunks_exact(w2)) {
-                    Format::add_acc(&mut accum, other_row[col], coeff);
+    /// Number of accumulators needed between the two passes, none for copies of source pixels
+    #[inline]
+    fn tmp_len(&self) -> usize {
+        if self.index_maps.is_some() { 0 } else { self.w2() * self.h1.get() }
+    }
+
+    /// Same as `resample_both_axes`, but never allocates, and writes destination rows `dst_stride` apart.
+    /// `tmp` must have at least `tmp_len()` elements. `progress` is told the number of rows written so far.
+    #[allow(clippy::too_many_arguments)]
+    fn resample_into<Format: PixelFormat>(&self, pix_fmt: &Format, tmp: &mut [Format::Accumulator], src: &[Format::InputPixel], stride: NonZeroUsize, dst: &mut [Format::OutputPixel], dst_stride: usize, order: RowOrder, progress: Progress<'_>) -> Result<ControlFlow<()>> {
+        if self.identity {
+            return self.convert_only(pix_fmt, src, stride, dst, dst_stride, order, progress);
+        }
+        if let Some(maps) = &self.index_maps {
+            return self.gather(maps, pix_fmt, src, stride, dst, dst_stride, order, progress);
+        }
+        if let Some(res) = self.blocks.and_then(|blocks| self.average_blocks(blocks, pix_fmt, src, stride, dst, dst_stride, order, progress)) {
+            return Ok(res);
+        }
+        let tmp = tmp.get_mut(..self.tmp_len()).ok_or(Error::InvalidParameters)?;
+        // Wide images are resampled in vertical strips, so that rows of `tmp` read by the vertical pass stay in cache.
+        // Strips take turns every band of rows, so that progress is reported while the first strip is still being resampled.
+        let (h1, w2, strip) = (self.h1.get(), self.w2(), self.strip_width::<Format>());
+        let band = if strip < w2 { STRIP_BAND_ROWS } else { 1 };
+        // Rows of `tmp` resampled before the band, which is the same for every strip
+        let mut filled_before = 0;
+        for band_start in (0..self.h2()).step_by(band) {
+            let rows = band_start..self.h2().min(band_start + band);
+            for start in (0..w2.max(1)).step_by(strip) {
+                let cols = start..w2.min(start + strip);
+                // Every strip has its own rows of `tmp`, which are `cols.len()` wide
+                let tmp = &mut tmp[cols.start * h1..cols.end * h1];
+                let mut filled = filled_before;
+                for y in rows.clone() {
+                    self.resample_row(pix_fmt, tmp, &mut filled, src, stride, dst, dst_stride, order, cols.clone(), y);
                 }
-                *dst_px = self.pix_fmt.into_pixel(accum);
             }
-            dst = &mut dst[w2..];
+            filled_before = self.coeffs_h[rows.clone()].iter().map(|row| row.start + row.coeffs.len()).fold(filled_before, usize::max);
+            if progress(rows.end).is_break() {
+                return Ok(ControlFlow::Break(()));
+            }
         }
-        Ok(())
+        Ok(ControlFlow::Continue(()))
     }
 
-    /// Resize `src` image data into `dst`.
-    #[inline]
-    pub(crate) fn resize_internal(&mut self, src: &[Format::InputPixel], src_stride: NonZeroUsize, dst: &mut [Format::OutputPixel]) -> Result<()> {
-        // TODO(Kagami):
-        // * Multi-thread
-        // * SIMD
-        if self.scale.w1.get() > src_stride.get() ||
-            src.len() < (src_stride.get() * self.scale.h1.get()) + self.scale.w1.get() - src_stride.get() ||
-            dst.len() != self.scale.w2() * self.scale.h2() {
-                return Err(Error::InvalidParameters)
+    /// Number of output columns resampled together, so that the rows of `tmp` used for one output row fit in L2 cache
+    fn strip_width<Format: PixelFormat>(&self) -> usize {
+        let taps = self.coeffs_h.iter().map(|line| line.coeffs.len()).max().unwrap_or(1);
+        let row_bytes = core::mem::size_of::<Format::Accumulator>().max(1) * taps;
+        // Narrow strips would spend more time on overlapping source columns than they save
+        (STRIP_CACHE_BYTES / row_bytes).max(256).min(self.w2().max(1))
+    }
+
+    /// Writes the `cols` of the `y`th row of the vertical pass, resampling only as many source rows into `tmp` as it needs.
+    /// Rows must be written in order, with `filled` counting rows of `tmp` resampled so far, which are `cols.len()` wide.
+    /// Scales that only copy source pixels write them directly, and don't use `tmp`.
+    #[allow(clippy::too_many_arguments)]
+    fn resample_row<Format: PixelFormat>(&self, pix_fmt: &Format, tmp: &mut [Format::Accumulator], filled: &mut usize, src: &[Format::InputPixel], stride: NonZeroUsize, dst: &mut [Format::OutputPixel], dst_stride: usize, order: RowOrder, cols: core::ops::Range<usize>, y: usize) {
+        if self.identity {
+            return self.convert_row(pix_fmt, src, stride, dst, dst_stride, order, cols, y);
+        }
+        if let Some(maps) = &self.index_maps {
+            return self.gather_row(maps, pix_fmt, src, stride, dst, dst_stride, order, cols, y);
+        }
+        let (h1, dest_height) = (self.h1.get(), self.dest_dimensions().1);
+        let w2 = cols.len();
+        let row = &self.coeffs_h[y];
+        // Resamples W1xH1 to W2xH1, but only as many rows as necessary to write a new line to the output
+        while *filled < row.start + row.coeffs.len() {
+            span!(TRACE, "horizontal_pass", row = *filled);
+            let src_row = &src[order.src_row(*filled, h1) * stride.get()..];
+            for (tmp_px, col) in tmp[w2 * *filled..w2 * (*filled + 1)].iter_mut().zip(&self.coeffs_w[cols.clone()]) {
+                let mut accum = Format::new();
+                let in_px = &src_row[col.start..col.start + col.coeffs.len()];
+                for (coeff, in_px) in col.coeffs.iter().copied().zip(in_px.iter().copied()) {
+                    pix_fmt.add(&mut accum, in_px, coeff)
+                }
+                *tmp_px = accum;
+            }
+            *filled += 1;
+        }
+
+        // Resamples W2xH1 to W2xH2
+        span!(TRACE, "vertical_pass", row = y);
+        let tmp_rows = &tmp[w2 * row.start..];
+        let pixel = |col: usize| {
+            let mut accum = Format::new();
+            for (coeff, other_row) in row.coeffs.iter().copied().zip(tmp_rows.chunks_exact(w2)) {
+                Format::add_acc(&mut accum, other_row[col], coeff);
+            }
+            accum
+        };
+        if self.transpose {
+            // The row becomes a column of the output
+            for (i, col) in cols.enumerate() {
+                let dst_y = order.dst_row(col, dest_height);
+                pix_fmt.write_pixel(&mut dst[dst_y * dst_stride + y], pixel(i), y, dst_y);
             }
-        self.resample_both_axes(src, src_stride, dst)
+        } else {
+            let dst_y = order.dst_row(y, dest_height);
+            for (i, (x, dst_px)) in cols.clone().zip(&mut dst[dst_y * dst_stride..][cols]).enumerate() {
+                pix_fmt.write_pixel(dst_px, pixel(i), self.origin[0] + x, self.origin[1] + dst_y);
+            }
+        }
     }
 }
 
 impl<Format: PixelFormat> Resizer<Format> {
-    /// Resize `src` image data into `dst`.
+    /// Create a new resizer instance.
     #[inline]
-    pub fn resize(&mut self, src: &[Format::InputPixel], dst: &mut [Format::OutputPixel]) -> Result<()> {
-        self.resize_internal(src, self.scale.w1, dst)
+    pub fn new(source_width: usize, source_heigth: usize, dest_width: usize, dest_height: usize, pixel_format: Format, filter_type: Type) -> Result<Self> {
+        Self::with_options(source_width, source_heigth, dest_width, dest_height, pixel_format, filter_type, &Options::default())
     }
 
-    /// Resize `src` image data into `dst`, skipping `stride` pixels each row.
+    /// Create a new resizer instance with non-default [`Options`].
     #[inline]
-    pub fn resize_stride(&mut self, src: &[Format::InputPixel], src_stride: usize, dst: &mut [Format::OutputPixel]) -> Result<()> {
-        let src_stride = NonZeroUsize::new(src_stride).ok_or(Error::InvalidParameters)?;
-        self.resize_internal(src, src_stride, dst)
+    pub fn with_options(source_width: usize, source_heigth: usize, dest_width: usize, dest_height: usize, pixel_format: Format, filter_type: Type, options: &Options) -> Result<Self> {
+        Self::with_filters(source_width, source_heigth, dest_width, dest_height, pixel_format, &filter_type, &filter_type, options)
     }
-}
 
-/// Create a new resizer instance. Alias for `Resizer::new`.
-#[inline(always)]
-pub fn new<Format: PixelFormat>(src_width: usize, src_height: usize, dest_width: usize, dest_height: usize, pixel_format: Format, filter_type: Type) -> Result<Resizer<Format>> {
-    Resizer::new(src_width, src_height, dest_width, dest_height, pixel_format, filter_type)
-}
+    /// Create a new resizer instance that uses `horizontal` filter for rows and `vertical` filter for columns.
+    #[inline]
+    #[allow(clippy::too_many_arguments)]
+    pub fn with_axis_filters(source_width: usize, source_heigth: usize, dest_width: usize, dest_height: usize, pixel_format: Format, horizontal: Type, vertical: Type, options: &Options) -> Result<Self> {
+        Self::with_filters(source_width, source_heigth, dest_width, dest_height, pixel_format, &horizontal, &vertical, options)
+    }
 
-/// Use `new().resize()` instead.
-///
-/// Resize image data to the new dimension in a single step.
-///
-/// **NOTE:** If you need to resize to the same dimension multiple times,
-/// consider creating an resizer instance since it's faster.
-#[deprecated(note="Use resize::new().resize()")]
-#[allow(deprecated)]
-pub fn resize<Format: PixelFormat>(
-    src_width: usize, src_height: usize, dest_width: usize, dest_height: usize,
-    pixel_format: Format, filter_type: Type,
-    src: &[Format::InputPixel], dst: &mut [Format::OutputPixel],
-) -> Result<()> {
-    Resizer::<Format>::new(src_width, src_height, dest_width, dest_height, pixel_format, filter_type)?.resize(src, dst)
-}
+    /// Create a new resizer instance that takes coefficients from the `cache` when it has them, and adds them there otherwise.
+    ///
+    /// Useful when many resizers are created for the same few sizes, e.g. thumbnails.
+    #[inline]
+    #[allow(clippy::too_many_arguments)]
+    pub fn with_cache(source_width: usize, source_heigth: usize, dest_width: usize, dest_height: usize, pixel_format: Format, filter_type: Type, options: &Options, cache: &mut CoefficientCache) -> Result<Self> {
+        Self::with_filters_cached(source_width, source_heigth, dest_width, dest_height, pixel_format, &filter_type, &filter_type, options, Some(cache))
+    }
 
-/// Resizing may run out of memory
-#[derive(Debug)]
-pub enum Error {
-    /// Allocation failed
-    OutOfMemory,
-    /// e.g. width or height can't be 0
-    InvalidParameters,
-}
+    #[inline]
+    #[allow(clippy::too_many_arguments)]
+    fn with_filters(source_width: usize, source_heigth: usize, dest_width: usize, dest_height: usize, pixel_format: Format, horizontal: &Type, vertical: &Type, options: &Options) -> Result<Self> {
+        Self::with_filters_cached(source_width, source_heigth, dest_width, dest_height, pixel_format, horizontal, vertical, options, None)
+    }
 
-impl From<fallible_collections::TryReserveError> for Error {
-    #[inline(always)]
-    fn from(_: fallible_collections::TryReserveError) -> Self {
-        Self::OutOfMemory
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn with_filters_cached(source_width: usize, source_heigth: usize, dest_width: usize, dest_height: usize, pixel_format: Format, horizontal: &Type, vertical: &Type, options: &Options, cache: Option<&mut CoefficientCache>) -> Result<Self> {
+        span!(DEBUG, "new", source_width, source_heigth, dest_width, dest_height, format = core::any::type_name::<Format>());
+        let (scale, prescale) = Self::scales(source_width, source_heigth, dest_width, dest_height, horizontal, vertical, options, cache)?;
+        let mut resizer = Self::with_scale(scale, pixel_format)?;
+        resizer.prescale = prescale;
+        resizer.options = options.clone();
+        resizer.filters = FilterKey::new(horizontal).zip(FilterKey::new(vertical)).map(|(h, v)| [h, v]);
+        Ok(resizer)
     }
-}
 
-impl fmt::Display for Error {
-    #[cold]
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        f.write_str(match self {
-            Self::OutOfMemory => "out of memory",
-            Self::InvalidParameters => "invalid parameters"
-        })
+    #[allow(clippy::too_many_arguments)]
+    fn scales(source_width: usize, source_heigth: usize, dest_width: usize, dest_height: usize, horizontal: &Type, vertical: &Type, options: &Options, mut cache: Option<&mut CoefficientCache>) -> Result<(Scale, Option<Scale>)> {
+        // Axes reduced more than 3x are box-filtered to 2x of the destination size first
+        let reduced = |s1: usize, s2: usize| if options.multi_step && s2 > 0 && s1 > s2 * 3 { s2 * 2 } else { s1 };
+        let (scaled_width, scaled_height) = if options.transposes() { (dest_height, dest_width) } else { (dest_width, dest_height) };
+        let (mid_width, mid_height) = (reduced(source_width, scaled_width), reduced(source_heigth, scaled_height));
+        if (mid_width, mid_height) == (source_width, source_heigth) {
+            return Ok((Scale::with_cache(source_width, source_heigth, dest_width, dest_height, horizontal, vertical, options, cache)?, None));
+        }
+        let prescale = Scale::with_cache(source_width, source_heigth, mid_width, mid_height, &Type::Area, &Type::Area, &Options::default(), cache.as_deref_mut())?;
+        let (offset_x, offset_y) = options.offset;
+        let mid_options = options.clone().offset(
+            offset_x * mid_width as f64 / source_width as f64,
+            offset_y * mid_height as f64 / source_heigth as f64);
+        Ok((Scale::with_cache(mid_width, mid_height, dest_width, dest_height, horizontal, vertical, &mid_options, cache)?, Some(prescale)))
     }
-}
 
-#[test]
-fn oom() {
-    let _ = new(2, 2, isize::max_value() as _, isize::max_value() as _, Pixel::Gray16, Type::Triangle);
-}
+    /// Change source and destination dimensions, keeping the filter and options.
+    ///
+    /// Scratch buffers are kept, and so are coefficients of an axis whose size didn't change.
+    /// Fails with [`Error::InvalidParameters`] if the resizer uses a [`Type::Custom`] filter or [`Resizer::from_coefficients`] tables;
+    /// pass the filter again with [`Resizer::set_filter`] first.
+    pub fn set_dimensions(&mut self, source_width: usize, source_heigth: usize, dest_width: usize, dest_height: usize) -> Result<()> {
+        let [horizontal, vertical] = self.filters.ok_or(Error::InvalidParameters)?;
+        self.rebuild(source_width, source_heigth, dest_width, dest_height, &horizontal.to_type(), &vertical.to_type())
+    }
 
-#[test]
-fn niche() {
-    assert_eq!(core::mem::size_of::<Resizer<formats::Gray<f32, f32>>>(), core::mem::size_of::<Option<Resizer<formats::Gray<f32, f32>>>>());
-}
+    /// Change the filter, keeping dimensions, options and scratch buffers.
+    pub fn set_filter(&mut self, filter_type: Type) -> Result<()> {
+        let (source_width, source_heigth) = self.source_dimensions();
+        let (dest_width, dest_height) = self.scale.dest_dimensions();
+        self.rebuild(source_width, source_heigth, dest_width, dest_height, &filter_type, &filter_type)
+    }
 
-#[test]
-fn zeros() {
-    assert!(new(1, 1, 1, 0, Pixel::Gray16, Type::Triangle).is_err());
-    assert!(new(1, 1, 0, 1, Pixel::Gray8, Type::Catrom).is_err());
-    assert!(new(1, 0, 1, 1, Pixel::RGBAF32, Type::Lanczos3).is_err());
-    assert!(new(0, 1, 1, 1, Pixel::RGB8, Type::Mitchell).is_err());
-}
+    fn source_dimensions(&self) -> (usize, usize) {
+        let scale = self.prescale.as_ref().unwrap_or(&self.scale);
+        (scale.w1.get(), scale.h1.get())
+    }
 
-#[test]
-fn premultiply() {
-    use px::RGBA;
-    let mut r = new(2, 2, 3, 4, Pixel::RGBA8P, Type::Triangle).unwrap();
-    let mut dst = std::vec![RGBA::new(0u8,0,0,0u8); 12];
-    r.resize(&[
+    fn rebuild(&mut self, source_width: usize, source_heigth: usize, dest_width: usize, dest_height: usize, horizontal: &Type, vertical: &Type) -> Result<()> {
+        let filters = FilterKey::new(horizontal).zip(FilterKey::new(vertical)).map(|(h, v)| [h, v]);
+        // Current coefficients are offered as a cache, so that an axis that stays the same is reused
+        let mut current = CoefficientCache::new();
+        if let (None, Some([h, v])) = (&self.prescale, self.filters) {
+            let (offset_x, offset_y) = self.options.offset;
+            // Cached lines aren't flipped
+            let unflipped = |lines: &Vec<CoeffsLine>, flip: bool| {
+                let mut lines = lines.clone();
+                if flip {
+                    lines.reverse();
+                }
+                lines
+            };
+            let (reverse_w, reverse_h) = self.options.reversed_lines();
+            current.insert(AxisKey::with_filter(self.scale.w1.get(), self.scale.coeffs_w.len(), h, &self.options, offset_x), unflipped(&self.scale.coeffs_w, reverse_w))?;
+            current.insert(AxisKey::with_filter(self.scale.h1.get(), self.scale.coeffs_h.len(), v, &self.options, offset_y), unflipped(&self.scale.coeffs_h, reverse_h))?;
+        }
+        let (scale, prescale) = Self::scales(source_width, source_heigth, dest_width, dest_height, horizontal, vertical, &self.options, Some(&mut current))?;
+        self.scale = scale;
+        self.prescale = prescale;
+        self.filters = filters;
+        Ok(())
+    }
+
+    #[inline]
+    fn with_scale(scale: Scale, pixel_format: Format) -> Result<Self> {
+        Ok(Self {
+            scale,
+            prescale: None,
+            tmp: Vec::new(),
+            pix_fmt: pixel_format,
+            options: Options::default(),
+            filters: None,
+        })
+    }
+
+    /// Weights used to compute each pixel of a destination row, from left to right
+    #[inline]
+    #[must_use]
+    pub fn horizontal_coefficients(&self) -> Coefficients<'_> {
+        Coefficients(self.scale.coeffs_w.iter(), self.scale.w1.get())
+    }
+
+    /// Weights used to compute each pixel of a destination column, from top to bottom
+    #[inline]
+    #[must_use]
+    pub fn vertical_coefficients(&self) -> Coefficients<'_> {
+        Coefficients(self.scale.coeffs_h.iter(), self.scale.h1.get())
+    }
+
+    /// Create a resizer that uses the given tables of weights instead of a filter, e.g. to replicate another scaler exactly.
+    ///
+    /// Each entry of a table computes one destination pixel, from the source pixel at `start` index
+    /// and consecutive pixels after it, multiplied by `weights`. Weights aren't normalized.
+    /// The tables have the same layout as [`Resizer::horizontal_coefficients`].
+    pub fn from_coefficients<W: AsRef<[f32]>>(source_width: usize, source_heigth: usize, pixel_format: Format,
+        horizontal: impl IntoIterator<Item = (usize, W)>, vertical: impl IntoIterator<Item = (usize, W)>,
+    ) -> Result<Self> {
+        let w1 = NonZeroUsize::new(source_width).ok_or(Error::InvalidParameters)?;
+        let h1 = NonZeroUsize::new(source_heigth).ok_or(Error::InvalidParameters)?;
+        let scale = Scale::from_lines(w1, h1, false, Scale::lines_from(w1, horizontal)?, Scale::lines_from(h1, vertical)?)?;
+        check_area(w1.get(), h1.get())?;
+        check_area(scale.w2(), scale.h2())?;
+        check_area(scale.w2(), h1.get())?;
+        Self::with_scale(scale, pixel_format)
+    }
+
+    /// Resize `src` image data into `dst`, with rows of `dst` `dst_stride` apart.
+    #[inline]
+    pub(crate) fn resize_internal(&mut self, src: &[Format::InputPixel], src_stride: NonZeroUsize, dst: &mut [Format::OutputPixel], dst_stride: NonZeroUsize) -> Result<()> {
+        self.resize_progress(src, src_stride, dst, dst_stride, RowOrder::default(), &mut |_| ControlFlow::Continue(())).map(drop)
+    }
+
+    fn resize_progress(&mut self, src: &[Format::InputPixel], src_stride: NonZeroUsize, dst: &mut [Format::OutputPixel], dst_stride: NonZeroUsize, order: RowOrder, progress: Progress<'_>) -> Result<ControlFlow<()>> {
+        self.reserve_scratch()?;
+        let mut tmp = core::mem::take(&mut self.tmp);
+        let res = self.resize_with_scratch_stride(src, src_stride, dst, dst_stride, order, &mut tmp, progress);
+        self.tmp = tmp;
+        res
+    }
+
+    /// Grows the resizer's own scratch buffer to [`Resizer::scratch_len`]
+    fn reserve_scratch(&mut self) -> Result<()> {
+        let scratch_len = self.scratch_len();
+        if let Some(missing) = scratch_len.checked_sub(self.tmp.len()).filter(|&n| n > 0) {
+            FallibleVec::try_reserve(&mut self.tmp, missing)?;
+            self.tmp.resize(scratch_len, Format::new());
+        }
+        Ok(())
+    }
+
+    /// Number of accumulators that [`Resizer::resize_with_scratch`] needs.
+    #[inline]
+    #[must_use]
+    pub fn scratch_len(&self) -> usize {
+        match &self.prescale {
+            Some(prescale) => (self.scale.w1.get() * self.scale.h1.get()).saturating_add(prescale.tmp_len().max(self.scale.tmp_len())),
+            None => self.scale.tmp_len(),
+        }
+    }
+
+    /// Resize `src` image data into `dst`, using `scratch` instead of the resizer's own buffers, so that it never allocates.
+    ///
+    /// `scratch` must have at least [`Resizer::scratch_len`] elements, which can be allocated once up front.
+    /// Coefficients are computed when the resizer is created, so after that resizing needs no heap at all.
+    ///
+    /// ```rust
+    /// use resize::{Pixel::Gray8, PixelFormat, Type::Triangle};
+    /// let resizer = resize::new(4, 4, 2, 2, Gray8, Triangle)?;
+    /// let mut scratch = vec![resize::formats::Gray::<u8, u8>::new(); resizer.scratch_len()];
+    /// let mut dst = [rgb::alt::Gray(0u8); 4];
+    /// resizer.resize_with_scratch(&[rgb::alt::Gray(1u8); 16], &mut dst, &mut scratch)?;
+    /// # Ok::<_, resize::Error>(())
+    /// ```
+    #[inline]
+    pub fn resize_with_scratch(&self, src: &[Format::InputPixel], dst: &mut [Format::OutputPixel], scratch: &mut [Format::Accumulator]) -> Result<()> {
+        self.resize_with_scratch_stride(src, self.source_stride(), dst, self.dest_stride(dst.len())?, RowOrder::default(), scratch, &mut |_| ControlFlow::Continue(())).map(drop)
+    }
+
+    /// Stride of contiguous source rows
+    #[inline]
+    fn source_stride(&self) -> NonZeroUsize {
+        self.prescale.as_ref().unwrap_or(&self.scale).w1
+    }
+
+    /// Stride of contiguous destination rows, in `dst_len` pixels that must be exactly the output image
+    #[inline]
+    fn dest_stride(&self, dst_len: usize) -> Result<NonZeroUsize> {
+        let (width, height) = self.scale.dest_dimensions();
+        if image_len(width, height, width) != Some(dst_len) {
+            return Err(Error::InvalidParameters);
+        }
+        NonZeroUsize::new(width).ok_or(Error::InvalidParameters)
+    }
+
+    /// Number of rows reported to the progress callback. Multi-step resizing counts rows of both steps.
+    fn progress_rows(&self) -> usize {
+        self.prescale.as_ref().map_or(0, |p| p.h2()) + self.scale.h2()
+    }
+
+    /// Validates buffers of both steps of multi-step resizing
+    fn check_buffers(&self, src_len: usize, src_stride: NonZeroUsize, dst_len: usize, dst_stride: NonZeroUsize) -> Result<()> {
+        match &self.prescale {
+            Some(prescale) => {
+                let mid_len = self.scale.w1.get() * self.scale.h1.get();
+                prescale.check_buffers(src_len, src_stride, mid_len)?;
+                self.scale.check_strided_buffers(mid_len, self.scale.w1, dst_len, dst_stride)
+            },
+            None => self.scale.check_strided_buffers(src_len, src_stride, dst_len, dst_stride),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn resize_with_scratch_stride(&self, src: &[Format::InputPixel], src_stride: NonZeroUsize, dst: &mut [Format::OutputPixel], dst_stride: NonZeroUsize, order: RowOrder, scratch: &mut [Format::Accumulator], progress: Progress<'_>) -> Result<ControlFlow<()>> {
+        // TODO(Kagami): SIMD. Multi-threading is in parallel.rs.
+        if scratch.len() < self.scratch_len() {
+            return Err(Error::InvalidParameters);
+        }
+        self.check_buffers(src.len(), src_stride, dst.len(), dst_stride)?;
+        span!(DEBUG, "resize", source = ?self.source_dimensions(), dest = ?self.scale.dest_dimensions(), format = core::any::type_name::<Format>());
+        if let Some(prescale) = &self.prescale {
+            span!(DEBUG, "multi_step", reduced_width = prescale.w2(), reduced_height = prescale.h2());
+            let mid_len = self.scale.w1.get() * self.scale.h1.get();
+            let (prescaled, tmp) = scratch.split_at_mut(mid_len);
+            let prescale_order = RowOrder { dst_bottom_up: false, ..order };
+            if prescale.resample_into(&px::KeepAccumulator(&self.pix_fmt), tmp, src, src_stride, prescaled, prescale.w2(), prescale_order, progress)?.is_break() {
+                return Ok(ControlFlow::Break(()));
+            }
+            let done = prescale.h2();
+            let order = RowOrder { src_bottom_up: false, ..order };
+            return self.scale.resample_into(&px::FromAccumulator(&self.pix_fmt), tmp, prescaled, self.scale.w1, dst, dst_stride.get(), order, &mut |rows| progress(done + rows));
+        }
+        self.scale.resample_into(&self.pix_fmt, scratch, src, src_stride, dst, dst_stride.get(), order, progress)
+    }
+}
+
+impl<Format: PixelFormat> Resizer<Format> {
+    /// Resize `src` image data into `dst`.
+    #[inline]
+    pub fn resize(&mut self, src: &[Format::InputPixel], dst: &mut [Format::OutputPixel]) -> Result<()> {
+        self.resize_internal(src, self.source_stride(), dst, self.dest_stride(dst.len())?)
+    }
+
+    /// Resize `src` image data into `dst`, calling `progress(rows_done, total_rows)` after each row.
+    /// Very wide images are resampled a few rows at a time, and report them together.
+    ///
+    /// If `progress` returns [`ControlFlow::Break`], resizing stops early and this returns `Ok(ControlFlow::Break(()))`.
+    /// `dst` is then only partially written.
+    ///
+    /// ```rust
+    /// use core::ops::ControlFlow;
+    /// # use rgb::FromSlice;
+    /// # let (src, mut dst) = (vec![0u8; 64 * 48 * 3], vec![0u8; 16 * 12 * 3]);
+    /// let mut resizer = resize::new(64, 48, 16, 12, resize::Pixel::RGB8, resize::Type::Lanczos3)?;
+    /// let flow = resizer.resize_with_progress(src.as_rgb(), dst.as_rgb_mut(), |done, total| {
+    ///     println!("{}%", done * 100 / total);
+    ///     ControlFlow::Continue(())
+    /// })?;
+    /// assert!(flow.is_continue());
+    /// # Ok::<_, resize::Error>(())
+    /// ```
+    pub fn resize_with_progress(&mut self, src: &[Format::InputPixel], dst: &mut [Format::OutputPixel], mut progress: impl FnMut(usize, usize) -> ControlFlow<()>) -> Result<ControlFlow<()>> {
+        let total = self.progress_rows();
+        self.resize_progress(src, self.source_stride(), dst, self.dest_stride(dst.len())?, RowOrder::default(), &mut |rows| progress(rows, total))
+    }
+
+    /// Resize `src` image data into `dst`, with rows `src_stride` and `dst_stride` pixels apart.
+    ///
+    /// Input and output pixels are types of the format, which may have different depths:
+    ///
+    /// ```rust
+    /// use resize::{formats, Resizer, Type::Lanczos3};
+    /// use rgb::{RGB8, RGB16};
+    /// let src = vec![RGB8::new(255, 128, 0); 100 * 50];
+    /// let mut dst = vec![RGB16::new(0, 0, 0); 64 * 25];
+    /// let mut resizer = Resizer::new(40, 50, 20, 25, formats::Rgb::<u8, u16>::instance(), Lanczos3)?;
+    /// // Left 40 columns of `src`, into right 20 columns of `dst`
+    /// resizer.resize_typed(&src, 100, &mut dst[44..], 64)?;
+    /// assert_eq!(dst[44], RGB16::new(65535, 32896, 0));
+    /// # Ok::<_, resize::Error>(())
+    /// ```
+    #[inline]
+    pub fn resize_typed(&mut self, src: &[Format::InputPixel], src_stride: usize, dst: &mut [Format::OutputPixel], dst_stride: usize) -> Result<()> {
+        let src_stride = NonZeroUsize::new(src_stride).ok_or(Error::InvalidParameters)?;
+        let dst_stride = NonZeroUsize::new(dst_stride).ok_or(Error::InvalidParameters)?;
+        self.resize_internal(src, src_stride, dst, dst_stride)
+    }
+
+    /// Compute only the `cols` × `rows` rectangle of the output image into `dst`, which must have exactly that many pixels.
+    ///
+    /// Only the source pixels under the filter footprints of the rectangle are read, so tiles of a huge output
+    /// can be computed on demand. Tiles are identical to the same area of a full [`Resizer::resize`].
+    /// This isn't supported with [`Options::multi_step`] or [`Options::rotation`].
+    ///
+    /// ```rust
+    /// use resize::{Pixel::Gray8, Type::Lanczos3};
+    /// use rgb::FromSlice;
+    /// let src = vec![0u8; 4096 * 4096];
+    /// let mut tile = vec![0u8; 256 * 256];
+    /// let mut resizer = resize::new(4096, 4096, 1024, 1024, Gray8, Lanczos3)?;
+    /// resizer.resize_tile(src.as_gray(), tile.as_gray_mut(), 256..512, 768..1024)?;
+    /// # Ok::<_, resize::Error>(())
+    /// ```
+    pub fn resize_tile(&mut self, src: &[Format::InputPixel], dst: &mut [Format::OutputPixel], cols: core::ops::Range<usize>, rows: core::ops::Range<usize>) -> Result<()> {
+        if self.prescale.is_some() {
+            return Err(Error::InvalidParameters);
+        }
+        let (tile, x, y) = self.scale.tile(cols, rows)?;
+        let stride = self.scale.w1;
+        let offset = y * stride.get() + x;
+        self.scale.check_buffers(src.len(), stride, self.scale.w2() * self.scale.h2())?;
+        tile.check_buffers(src.len() - offset, stride, dst.len())?;
+        tile.resample_both_axes(&self.pix_fmt, &mut self.tmp, &src[offset..], stride, dst)
+    }
+
+    /// Resize `src` image data into a sub-rectangle of a larger `dst` image, with rows `dst_stride` pixels apart.
+    ///
+    /// The output's top-left corner is written at column `dst_x` and row `dst_y` of `dst`. Pixels outside of it are left untouched,
+    /// so this can composite thumbnails into an atlas.
+    ///
+    /// ```rust
+    /// use resize::{Pixel::Gray8, Type::Triangle};
+    /// use rgb::FromSlice;
+    /// let mut atlas = [0u8; 4 * 3];
+    /// let mut resizer = resize::new(4, 4, 2, 2, Gray8, Triangle)?;
+    /// resizer.resize_into([9u8; 16].as_gray(), atlas.as_gray_mut(), 4, 1, 1)?;
+    /// assert_eq!(atlas, [0, 0, 0, 0, 0, 9, 9, 0, 0, 9, 9, 0]);
+    /// # Ok::<_, resize::Error>(())
+    /// ```
+    pub fn resize_into(&mut self, src: &[Format::InputPixel], dst: &mut [Format::OutputPixel], dst_stride: usize, dst_x: usize, dst_y: usize) -> Result<()> {
+        let dst_stride = NonZeroUsize::new(dst_stride).ok_or(Error::InvalidParameters)?;
+        let width = self.scale.dest_dimensions().0;
+        if width > dst_stride.get() || dst_x > dst_stride.get() - width {
+            return Err(Error::InvalidParameters);
+        }
+        let offset = dst_y.checked_mul(dst_stride.get()).and_then(|o| o.checked_add(dst_x)).ok_or(Error::InvalidParameters)?;
+        let height = self.scale.dest_dimensions().1;
+        let end = (height - 1).checked_mul(dst_stride.get()).and_then(|len| offset.checked_add(width).and_then(|e| len.checked_add(e))).ok_or(Error::InvalidParameters)?;
+        let dst = dst.get_mut(offset..end).ok_or(Error::InvalidParameters)?;
+        self.resize_internal(src, self.source_stride(), dst, dst_stride)
+    }
+
+    /// Resize `src` image data into `dst`, with rows `src_stride` and `dst_stride` pixels apart,
+    /// where a negative stride means the rows are stored bottom-up, as in BMP/DIB bitmaps.
+    ///
+    /// Slices always start at the lowest address, so for a bottom-up buffer the top row of the image is the last row of the slice.
+    ///
+    /// ```rust
+    /// use resize::{Pixel::Gray8, Type::Triangle};
+    /// use rgb::FromSlice;
+    /// let bottom_up_src = [0u8, 0, 0, 0, 255, 255, 255, 255];
+    /// let mut top_down_dst = [0u8; 2];
+    /// let mut resizer = resize::new(4, 2, 1, 2, Gray8, Triangle)?;
+    /// resizer.resize_signed_stride(bottom_up_src.as_gray(), -4, top_down_dst.as_gray_mut(), 1)?;
+    /// assert_eq!(top_down_dst, [255, 0]);
+    /// # Ok::<_, resize::Error>(())
+    /// ```
+    pub fn resize_signed_stride(&mut self, src: &[Format::InputPixel], src_stride: isize, dst: &mut [Format::OutputPixel], dst_stride: isize) -> Result<()> {
+        let order = RowOrder { src_bottom_up: src_stride < 0, dst_bottom_up: dst_stride < 0 };
+        let src_stride = NonZeroUsize::new(src_stride.unsigned_abs()).ok_or(Error::InvalidParameters)?;
+        let dst_stride = NonZeroUsize::new(dst_stride.unsigned_abs()).ok_or(Error::InvalidParameters)?;
+        self.resize_progress(src, src_stride, dst, dst_stride, order, &mut |_| ControlFlow::Continue(())).map(drop)
+    }
+
+    /// Resize `src` image data into `dst`, skipping `stride` pixels each row.
+    #[inline]
+    pub fn resize_stride(&mut self, src: &[Format::InputPixel], src_stride: usize, dst: &mut [Format::OutputPixel]) -> Result<()> {
+        let src_stride = NonZeroUsize::new(src_stride).ok_or(Error::InvalidParameters)?;
+        self.resize_internal(src, src_stride, dst, self.dest_stride(dst.len())?)
+    }
+
+    /// Check that [`Resizer::resize_stride`] would accept buffers of these lengths, without resizing anything.
+    ///
+    /// Returns the same error as resizing would, so that untrusted inputs can be rejected up front.
+    /// Lengths, strides and dimensions of any size give an error instead of overflowing or panicking.
+    pub fn validate(&self, src_len: usize, src_stride: usize, dst_len: usize) -> Result<()> {
+        let src_stride = NonZeroUsize::new(src_stride).ok_or(Error::InvalidParameters)?;
+        self.check_buffers(src_len, src_stride, dst_len, self.dest_stride(dst_len)?)
+    }
+}
+
+impl<Format: PixelFormat> Resizer<Format> {
+    /// Resize many images of the same dimensions, reusing coefficients and scratch buffers.
+    ///
+    /// Stops at the first error. To spread the work across threads, give each thread a [clone](Clone) of the resizer.
+    pub fn resize_all<'a, 'b>(&mut self, images: impl IntoIterator<Item = (&'a [Format::InputPixel], &'b mut [Format::OutputPixel])>) -> Result<()>
+    where Format::InputPixel: 'a, Format::OutputPixel: 'b {
+        images.into_iter().try_for_each(|(src, dst)| self.resize(src, dst))
+    }
+
+    /// Resize `src` image data into a new buffer, which has the length needed for the destination dimensions.
+    ///
+    /// ```rust
+    /// use resize::{Pixel::RGB8, Type::Lanczos3};
+    /// let src = vec![rgb::RGB8::new(0, 0, 0); 64 * 48];
+    /// let thumbnail = resize::new(64, 48, 16, 12, RGB8, Lanczos3)?.resize_to_vec(&src)?;
+    /// assert_eq!(thumbnail.len(), 16 * 12);
+    /// # Ok::<_, resize::Error>(())
+    /// ```
+    ///
+    /// With the `imgref` feature, `resize_img_vec` does the same for images that have padding between rows.
+    pub fn resize_to_vec(&mut self, src: &[Format::InputPixel]) -> Result<Vec<Format::OutputPixel>>
+    where Format::OutputPixel: Clone + Default {
+        let (w2, h2) = self.scale.dest_dimensions();
+        let mut dst = Vec::new();
+        FallibleVec::try_reserve(&mut dst, w2 * h2)?;
+        dst.resize(w2 * h2, Format::OutputPixel::default());
+        self.resize(src, &mut dst)?;
+        Ok(dst)
+    }
+}
+
+/// Clones share the coefficient tables, and only get their own scratch buffers,
+/// so they're cheap to make for every thread.
+impl<Format: PixelFormat + Clone> Clone for Resizer<Format> {
+    fn clone(&self) -> Self {
+        Self {
+            scale: self.scale.clone(),
+            prescale: self.prescale.clone(),
+            pix_fmt: self.pix_fmt.clone(),
+            options: self.options.clone(),
+            filters: self.filters,
+            tmp: Vec::new(),
+        }
+    }
+}
+
+/// Polyphase filter table of one axis, see [`Resizer::horizontal_coefficients`].
+///
+/// For every destination pixel it yields index of the first source pixel,
+/// and weights of consecutive source pixels starting from it. Weights add up to 1.
+#[derive(Debug, Clone)]
+pub struct Coefficients<'a>(core::slice::Iter<'a, CoeffsLine>, usize);
+
+impl<'a> Iterator for Coefficients<'a> {
+    type Item = (usize, &'a [f32]);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|line| (line.start, &line.coeffs[..]))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl ExactSizeIterator for Coefficients<'_> {}
+
+impl Coefficients<'_> {
+    /// Lay out the remaining weights the way libswscale and zimg do, for cross-validation with them
+    pub fn to_filter_bank(&self) -> Result<FilterBank> {
+        let lines = self.0.as_slice();
+        let filter_width = lines.iter().map(|l| l.coeffs.len()).max().unwrap_or(0);
+        let mut bank = FilterBank {
+            filter_width,
+            left: FallibleVec::try_with_capacity(lines.len())?,
+            data: FallibleVec::try_with_capacity(lines.len() * filter_width)?,
+            data_i16: FallibleVec::try_with_capacity(lines.len() * filter_width)?,
+        };
+        for line in lines {
+            // Windows that would reach past the end are moved left, with zero weights in front
+            let left = line.start.min(self.1.saturating_sub(filter_width));
+            let pad = line.start - left;
+            let quantized = fixed::quantize_weights(&line.coeffs)?;
+            bank.left.push(left);
+            for i in 0..filter_width {
+                let tap = i.checked_sub(pad).filter(|&t| t < line.coeffs.len());
+                bank.data.push(tap.map_or(0., |t| line.coeffs[t]));
+                bank.data_i16.push(tap.map_or(0, |t| quantized[t]));
+            }
+        }
+        Ok(bank)
+    }
+}
+
+/// Weights of all pixels of an axis in a fixed-width matrix, like `SwsFilter` of libswscale
+/// and `FilterContext` of zimg.
+///
+/// Destination pixel `i` is computed from `filter_width` source pixels starting at `left[i]`,
+/// with weights in `data[i * filter_width..(i + 1) * filter_width]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterBank {
+    /// Number of weights per destination pixel, including zero padding
+    pub filter_width: usize,
+    /// Index of the first source pixel for each destination pixel
+    pub left: Vec<usize>,
+    /// Weights in row-major order
+    pub data: Vec<f32>,
+    /// Weights in 14-bit fixed-point, each row adding up to exactly `1 << 14` like in libswscale
+    pub data_i16: Vec<i16>,
+}
+
+/// Create a new resizer instance. Alias for `Resizer::new`.
+#[inline(always)]
+pub fn new<Format: PixelFormat>(src_width: usize, src_height: usize, dest_width: usize, dest_height: usize, pixel_format: Format, filter_type: Type) -> Result<Resizer<Format>> {
+    Resizer::new(src_width, src_height, dest_width, dest_height, pixel_format, filter_type)
+}
+
+/// Resample a single line of samples, e.g. a signal, an envelope or a lookup table, from `src.len()` to `dst.len()` samples.
+///
+/// Uses the same coefficients as resizing images. To resample many lines of the same lengths,
+/// reuse a `Resizer::new(src_len, 1, dst_len, 1, …)` instead.
+///
+/// ```rust
+/// use rgb::FromSlice;
+/// let signal = [0f32, 1., 0., -1., 0.];
+/// let mut upsampled = [0f32; 9];
+/// resize::resample_line(signal.as_gray(), upsampled.as_gray_mut(), resize::Pixel::GrayF32, resize::Type::Catrom)?;
+/// # Ok::<_, resize::Error>(())
+/// ```
+pub fn resample_line<Format: PixelFormat>(src: &[Format::InputPixel], dst: &mut [Format::OutputPixel], pixel_format: Format, filter_type: Type) -> Result<()> {
+    let scale = Scale::new(src.len(), 1, dst.len(), 1, &filter_type)?;
+    let stride = scale.w1;
+    scale.resample_both_axes(&pixel_format, &mut Vec::new(), src, stride, dst)
+}
+
+/// Use `new().resize()` instead.
+///
+/// Resize image data to the new dimension in a single step.
+///
+/// **NOTE:** If you need to resize to the same dimension multiple times,
+/// consider creating an resizer instance since it's faster.
+#[deprecated(note="Use resize::new().resize()")]
+#[allow(deprecated)]
+#[allow(clippy::too_many_arguments)]
+pub fn resize<Format: PixelFormat>(
+    src_width: usize, src_height: usize, dest_width: usize, dest_height: usize,
+    pixel_format: Format, filter_type: Type,
+    src: &[Format::InputPixel], dst: &mut [Format::OutputPixel],
+) -> Result<()> {
+    Resizer::<Format>::new(src_width, src_height, dest_width, dest_height, pixel_format, filter_type)?.resize(src, dst)
+}
+
+/// Resizing may run out of memory
+#[derive(Debug)]
+pub enum Error {
+    /// Allocation failed
+    OutOfMemory,
+    /// e.g. width or height can't be 0
+    InvalidParameters,
+}
+
+impl From<fallible_collections::TryReserveError> for Error {
+    #[inline(always)]
+    fn from(_: fallible_collections::TryReserveError) -> Self {
+        Self::OutOfMemory
+    }
+}
+
+impl fmt::Display for Error {
+    #[cold]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::OutOfMemory => "out of memory",
+            Self::InvalidParameters => "invalid parameters"
+        })
+    }
+}
+
+#[test]
+fn oom() {
+    let _ = new(2, 2, isize::MAX as _, isize::MAX as _, Pixel::Gray16, Type::Triangle);
+}
+
+#[test]
+fn niche() {
+    assert_eq!(core::mem::size_of::<Resizer<formats::Gray<f32, f32>>>(), core::mem::size_of::<Option<Resizer<formats::Gray<f32, f32>>>>());
+}
+
+#[test]
+fn zeros() {
+    assert!(new(1, 1, 1, 0, Pixel::Gray16, Type::Triangle).is_err());
+    assert!(new(1, 1, 0, 1, Pixel::Gray8, Type::Catrom).is_err());
+    assert!(new(1, 0, 1, 1, Pixel::RGBAF32, Type::Lanczos3).is_err());
+    assert!(new(0, 1, 1, 1, Pixel::RGB8, Type::Mitchell).is_err());
+}
+
+#[test]
+fn premultiply() {
+    use px::RGBA;
+    let mut r = new(2, 2, 3, 4, Pixel::RGBA8P, Type::Triangle).unwrap();
+    let mut dst = std::vec![RGBA::new(0u8,0,0,0u8); 12];
+    r.resize(&[
         RGBA::new(255,127,3,255), RGBA::new(0,0,0,0),
         RGBA::new(255,255,255,0), RGBA::new(0,255,255,0),
     ], &mut dst).unwrap();
@@ -523,13 +2425,1981 @@ fn premultiply_solid() {
     ]);
 }
 
+#[test]
+fn premultiply_float() {
+    use px::RGBA;
+    let mut r = new(2, 1, 1, 1, Pixel::RGBAF32P, Type::Triangle).unwrap();
+    let mut dst = std::vec![RGBA::new(0.,0.,0.,0.); 1];
+    r.resize(&[
+        RGBA::new(1.,0.5,0.25,1.), RGBA::new(0.,1.,1.,0.),
+    ], &mut dst).unwrap();
+    assert_eq!(&dst, &[RGBA::new(1.,0.5,0.25,0.5)]);
+}
+
+#[test]
+fn gray_alpha_premultiply() {
+    use px::GrayAlpha;
+    let mut r = new(2, 1, 3, 1, Pixel::GrayAlpha8P, Type::Triangle).unwrap();
+    let mut dst = std::vec![GrayAlpha(0u8, 0u8); 3];
+    r.resize(&[GrayAlpha(200, 255), GrayAlpha(17, 0)], &mut dst).unwrap();
+    assert_eq!(&dst, &[GrayAlpha(200, 255), GrayAlpha(200, 128), GrayAlpha(0, 0)]);
+
+    let mut r = new(2, 1, 3, 1, Pixel::GrayAlpha8, Type::Triangle).unwrap();
+    r.resize(&[GrayAlpha(200, 255), GrayAlpha(0, 0)], &mut dst).unwrap();
+    assert_eq!(&dst, &[GrayAlpha(200, 255), GrayAlpha(100, 128), GrayAlpha(0, 0)]);
+}
+
+#[test]
+fn bgra_premultiply() {
+    use px::BGRA;
+    let mut r = new(2, 1, 3, 1, Pixel::BGRA8P, Type::Triangle).unwrap();
+    let mut dst = std::vec![BGRA { b: 0u8, g: 0, r: 0, a: 0 }; 3];
+    r.resize(&[
+        BGRA { b: 3, g: 127, r: 255, a: 255 }, BGRA { b: 9, g: 9, r: 9, a: 0 },
+    ], &mut dst).unwrap();
+    assert_eq!(&dst, &[
+        BGRA { b: 3, g: 127, r: 255, a: 255 }, BGRA { b: 3, g: 127, r: 255, a: 128 }, BGRA { b: 0, g: 0, r: 0, a: 0 },
+    ]);
+}
+
+#[test]
+fn planar() {
+    use rgb::FromSlice;
+
+    let luma = PlaneDimensions { src_width: 4, src_height: 2, dest_width: 2, dest_height: 1 };
+    let chroma = PlaneDimensions { src_width: 2, src_height: 1, dest_width: 1, dest_height: 1 };
+    let mut r = PlanarResizer::new(&[luma, chroma, chroma], Pixel::Gray8, Type::Triangle).unwrap();
+    assert_eq!(3, r.planes());
+
+    let y = [10u8, 10, 50, 50, 10, 10, 50, 50, 0, 0];
+    let (u, v) = ([100u8, 200], [7u8, 7, 255]);
+    let (mut y2, mut u2, mut v2) = ([0u8; 2], [0u8; 1], [0u8; 1]);
+    r.resize(&[
+        Plane::new(y.as_gray(), 4), Plane::new(u.as_gray(), 2), Plane::new(v.as_gray(), 3),
+    ], &mut [y2.as_gray_mut(), u2.as_gray_mut(), v2.as_gray_mut()]).unwrap();
+    assert_eq!((y2, u2, v2), ([16, 44], [150], [7]));
+
+    assert!(r.resize(&[Plane::new(y.as_gray(), 4)], &mut [y2.as_gray_mut()]).is_err());
+}
+
+#[test]
+fn yuv420() {
+    use rgb::FromSlice;
+
+    let mut r = Yuv420Resizer::new(4, 2, 8, 4, ChromaSiting::Left, Type::Triangle).unwrap();
+    let y = [16u8, 16, 235, 235, 16, 16, 235, 235];
+    let uv = [[128u8, 64], [128, 64]];
+    let (mut y2, mut uv2) = ([0u8; 32], [[0u8; 2]; 8]);
+    r.resize_nv12(Plane::new(y.as_gray(), 4), Plane::new(&uv, 2), y2.as_gray_mut(), &mut uv2).unwrap();
+    assert_eq!(&y2[..8], &[16, 16, 16, 71, 180, 235, 235, 235]);
+    assert_eq!(uv2, [[128, 64]; 8]);
+
+    let (u, v) = ([1u8, 2], [3u8, 4]);
+    let (mut u2, mut v2) = ([0u8; 4], [0u8; 4]);
+    assert!(r.resize_i420([Plane::new(y.as_gray(), 4), Plane::new(u.as_gray(), 2), Plane::new(v.as_gray(), 2)],
+        [y2.as_gray_mut(), u2.as_gray_mut(), v2.as_gray_mut()]).is_err());
+    let (mut u2, mut v2) = ([0u8; 8], [0u8; 8]);
+    r.resize_i420([Plane::new(y.as_gray(), 4), Plane::new(u.as_gray(), 2), Plane::new(v.as_gray(), 2)],
+        [y2.as_gray_mut(), u2.as_gray_mut(), v2.as_gray_mut()]).unwrap();
+    assert_eq!(&u2[..4], &[1, 1, 2, 2]);
+}
+
+#[test]
+fn video_scaler() {
+    use rgb::FromSlice;
+
+    // Progressive frames match Yuv420Resizer, whatever the strides of each frame
+    let y: Vec<u8> = (0..8 * 8).map(|i| (i * 7 % 251) as u8).collect();
+    let (u, v): (Vec<u8>, Vec<u8>) = ((0..16).map(|i| i * 9).collect(), (0..16).map(|i| 255 - i * 5).collect());
+    let (mut y1, mut u1, mut v1) = ([0u8; 6 * 4], [0u8; 3 * 2], [0u8; 3 * 2]);
+    Yuv420Resizer::new(8, 8, 6, 4, ChromaSiting::Left, Type::Catrom).unwrap().resize_i420(
+        [Plane::new(y.as_gray(), 8), Plane::new(u.as_gray(), 4), Plane::new(v.as_gray(), 4)],
+        [y1.as_gray_mut(), u1.as_gray_mut(), v1.as_gray_mut()]).unwrap();
+    let mut scaler = VideoScaler::new(8, 8, 6, 4, ChromaSiting::Left, Type::Catrom).unwrap();
+    for pad in [0, 3] {
+        let padded = |plane: &[u8], width: usize| plane.chunks(width).flat_map(|row| row.iter().copied().chain(core::iter::repeat_n(0, pad))).collect::<Vec<u8>>();
+        let (y_pad, u_pad, v_pad) = (padded(&y, 8), padded(&u, 4), padded(&v, 4));
+        let (mut y2, mut u2, mut v2) = (std::vec![0u8; (6 + pad) * 4], std::vec![0u8; (3 + pad) * 2], std::vec![0u8; (3 + pad) * 2]);
+        scaler.scale(
+            VideoFrame::I420([Plane::new(y_pad.as_gray(), 8 + pad), Plane::new(u_pad.as_gray(), 4 + pad), Plane::new(v_pad.as_gray(), 4 + pad)]),
+            VideoFrameMut::I420([PlaneMut::new(y2.as_gray_mut(), 6 + pad), PlaneMut::new(u2.as_gray_mut(), 3 + pad), PlaneMut::new(v2.as_gray_mut(), 3 + pad)]),
+            PictureStructure::Progressive).unwrap();
+        let unpadded = |plane: &[u8], width: usize| plane.chunks(width + pad).flat_map(|row| row[..width].iter().copied()).collect::<Vec<u8>>();
+        assert_eq!((unpadded(&y2, 6), unpadded(&u2, 3), unpadded(&v2, 3)), (y1.to_vec(), u1.to_vec(), v1.to_vec()));
+    }
+
+    // Interlaced frames match InterlacedResizer on luma, and single fields give the same rows
+    let mut y1 = [0u8; 6 * 4];
+    InterlacedResizer::new(8, 8, 6, 4, Pixel::Gray8, Type::Catrom).unwrap().resize(y.as_gray(), y1.as_gray_mut()).unwrap();
+    let uv: Vec<[u8; 2]> = u.iter().zip(&v).map(|(&u, &v)| [u, v]).collect();
+    let (mut y2, mut uv2) = ([0u8; 6 * 4], [[0u8; 2]; 3 * 2]);
+    scaler.scale(
+        VideoFrame::Nv12(Plane::new(y.as_gray(), 8), Plane::new(&uv, 4)),
+        VideoFrameMut::Nv12(PlaneMut::new(y2.as_gray_mut(), 6), PlaneMut::new(&mut uv2, 3)),
+        PictureStructure::Interlaced).unwrap();
+    assert_eq!(y1, y2);
+    let mut field = [0u8; 6 * 2];
+    let mut field_uv = [[0u8; 2]; 3];
+    scaler.scale(
+        VideoFrame::Nv12(Plane::new(y[8..].as_gray(), 16), Plane::new(&uv[4..], 8)),
+        VideoFrameMut::Nv12(PlaneMut::new(field.as_gray_mut(), 6), PlaneMut::new(&mut field_uv, 3)),
+        PictureStructure::BottomField).unwrap();
+    assert_eq!((&field[..6], &field[6..]), (&y2[6..12], &y2[18..]));
+    assert_eq!(field_uv, uv2[3..]);
+
+    // Planes can be longer than the picture, also when rows aren't padded
+    let (mut y3, mut uv3) = ([0u8; 6 * 4 + 5], [[0u8; 2]; 3 * 2 + 1]);
+    scaler.scale(
+        VideoFrame::Nv12(Plane::new(y.as_gray(), 8), Plane::new(&uv, 4)),
+        VideoFrameMut::Nv12(PlaneMut::new(y3.as_gray_mut(), 6), PlaneMut::new(&mut uv3, 3)),
+        PictureStructure::Interlaced).unwrap();
+    assert_eq!(y3[..6 * 4], y2);
+
+    // Layouts must match, and fields need heights that are multiples of 4
+    assert!(scaler.scale(
+        VideoFrame::Nv12(Plane::new(y.as_gray(), 8), Plane::new(&uv, 4)),
+        VideoFrameMut::I420([PlaneMut::new(y2.as_gray_mut(), 6), PlaneMut::new(u1.as_gray_mut(), 3), PlaneMut::new(v1.as_gray_mut(), 3)]),
+        PictureStructure::Progressive).is_err());
+    let mut scaler = VideoScaler::new(8, 6, 6, 4, ChromaSiting::Center, Type::Catrom).unwrap();
+    assert!(scaler.scale(
+        VideoFrame::Nv12(Plane::new(y.as_gray(), 8), Plane::new(&uv, 4)),
+        VideoFrameMut::Nv12(PlaneMut::new(y2.as_gray_mut(), 6), PlaneMut::new(&mut uv2, 3)),
+        PictureStructure::Interlaced).is_err());
+}
+
+#[test]
+fn duo() {
+    let mut r = new(2, 1, 3, 1, Pixel::Duo16, Type::Triangle).unwrap();
+    let mut dst = [[0u16; 2]; 3];
+    r.resize(&[[1000, 65535], [3000, 0]], &mut dst).unwrap();
+    assert_eq!(dst, [[1000, 65535], [2000, 32768], [3000, 0]]);
+}
+
+#[test]
+fn float_unquantized() {
+    use px::RGB;
+    let mut r = new(2, 1, 3, 1, Pixel::RGBF32, Type::Triangle).unwrap();
+    let mut dst = [RGB::new(0f32, 0., 0.); 3];
+    r.resize(&[RGB::new(-0.5, 0.001, 1000.), RGB::new(0.25, 0.002, 3000.)], &mut dst).unwrap();
+    assert_eq!(dst[0], RGB::new(-0.5, 0.001, 1000.));
+    assert_eq!(dst[2], RGB::new(0.25, 0.002, 3000.));
+    assert!(dst[1].r > -0.5 && dst[1].r < 0.25 && dst[1].r.fract() != 0.);
+    assert!(dst[1].b > 1000. && dst[1].b < 3000.);
+}
+
+#[test]
+#[cfg(feature = "half")]
+fn half_float() {
+    use half::f16;
+    use px::RGB;
+    let mut r = new(2, 1, 3, 1, Pixel::RGBF16, Type::Triangle).unwrap();
+    let mut dst = [RGB::new(f16::ZERO, f16::ZERO, f16::ZERO); 3];
+    let (a, b) = (RGB::new(f16::from_f32(0.5), f16::ONE, f16::from_f32(-2.)), RGB::new(f16::from_f32(1.5), f16::ONE, f16::from_f32(4.)));
+    r.resize(&[a, b], &mut dst).unwrap();
+    assert_eq!(dst, [a, RGB::new(f16::ONE, f16::ONE, f16::ONE), b]);
+}
+
+#[test]
+fn bit_depth() {
+    use px::BitDepth;
+    use rgb::FromSlice;
+
+    let mut r = new(2, 1, 4, 1, BitDepth::new(Pixel::Gray16, 10), Type::Lanczos3).unwrap();
+    let mut dst = [0u16; 4];
+    r.resize([0u16, 1023].as_gray(), dst.as_gray_mut()).unwrap();
+    assert_eq!(dst[0], 0);
+    assert_eq!(dst[3], 1023);
+    assert!(dst.iter().all(|&v| v <= 1023));
+
+    // Out of range bit depths are clamped instead of panicking
+    assert_eq!(BitDepth::new(Pixel::Gray16, 0).max_value(), 1);
+    assert_eq!(BitDepth::new(Pixel::Gray16, 40).max_value(), 65535);
+}
+
+#[test]
+fn big_endian() {
+    use px::RGB;
+    let mut r = new(2, 1, 3, 1, Pixel::RGB16BE, Type::Triangle).unwrap();
+    let mut dst = [RGB::new(0u16, 0, 0); 3];
+    r.resize(&[RGB::new(0x0100u16.to_be(), 2, 0xFFFF), RGB::new(0x0300u16.to_be(), 2, 0xFFFF)], &mut dst).unwrap();
+    assert_eq!(dst, [
+        RGB::new(0x0100u16.to_be(), 2, 0xFFFF), RGB::new(0x0200u16.to_be(), 2, 0xFFFF), RGB::new(0x0300u16.to_be(), 2, 0xFFFF),
+    ]);
+}
+
+#[test]
+fn cross_depth() {
+    use px::{Gray, RGBA};
+    let mut r = new(2, 1, 3, 1, Pixel::RGBA8to16, Type::Triangle).unwrap();
+    let mut dst = [RGBA::new(0u16, 0, 0, 0); 3];
+    r.resize(&[RGBA::new(255u8, 0, 1, 255), RGBA::new(255, 0, 1, 255)], &mut dst).unwrap();
+    assert_eq!(dst, [RGBA::new(65535, 0, 257, 65535); 3]);
+
+    let mut r = new(1, 1, 1, 1, Pixel::Gray16to8, Type::Triangle).unwrap();
+    let mut dst = [Gray::new(0u8)];
+    r.resize(&[Gray::new(65535u16)], &mut dst).unwrap();
+    assert_eq!(dst, [Gray::new(255)]);
+    r.resize(&[Gray::new(32896u16)], &mut dst).unwrap();
+    assert_eq!(dst, [Gray::new(128)]);
+}
+
+#[test]
+fn rgb_to_gray() {
+    use px::{Gray, LumaWeights, RGB, RGBA};
+    let mut r = new(2, 1, 1, 1, Pixel::RGB8toGray8, Type::Triangle).unwrap();
+    let mut dst = [Gray::new(0u8)];
+    r.resize(&[RGB::new(255, 0, 0), RGB::new(255, 0, 0)], &mut dst).unwrap();
+    assert_eq!(dst, [Gray::new(54)]);
+
+    let mut r = new(2, 1, 1, 1, Pixel::RGBA8toGray8.with_weights(LumaWeights::BT601), Type::Triangle).unwrap();
+    r.resize(&[RGBA::new(0, 255, 0, 0), RGBA::new(0, 255, 0, 255)], &mut dst).unwrap();
+    assert_eq!(dst, [Gray::new(150)]);
+}
+
+#[test]
+fn cmyk() {
+    use px::CMYK;
+    let src = [0u8, 10, 20, 255, 100, 110, 120, 55];
+    let mut dst = [0u8; 12];
+    let mut r = new(2, 1, 3, 1, Pixel::CMYK8, Type::Triangle).unwrap();
+    r.resize(CMYK::from_slice(&src), CMYK::from_slice_mut(&mut dst)).unwrap();
+    assert_eq!(dst, [0, 10, 20, 255, 50, 60, 70, 155, 100, 110, 120, 55]);
+}
+
+#[test]
+fn area() {
+    use rgb::FromSlice;
+
+    let mut r = new(6, 1, 4, 1, Pixel::Gray8, Type::Area).unwrap();
+    let mut dst = [0u8; 4];
+    r.resize([0u8, 30, 60, 90, 120, 150].as_gray(), dst.as_gray_mut()).unwrap();
+    assert_eq!(dst, [10, 50, 100, 140]);
+
+    let mut r = new(3, 1, 1, 1, Pixel::Gray8, Type::Area).unwrap();
+    let mut dst = [0u8; 1];
+    r.resize([0u8, 255, 30].as_gray(), dst.as_gray_mut()).unwrap();
+    assert_eq!(dst, [95]);
+}
+
+#[test]
+fn gaussian_filter() {
+    use rgb::FromSlice;
+
+    for sigma in [0., -1., f32::NAN, f32::INFINITY] {
+        assert!(new(4, 4, 2, 2, Pixel::Gray8, Type::Gaussian(sigma)).is_err());
+        assert!(Filter::new_gaussian(sigma).is_err());
+    }
+    assert!(new(4, 4, 2, 2, Pixel::Gray8, Type::Custom(Filter::new_gaussian(0.5).unwrap())).is_ok());
+    let mut r = new(4, 1, 8, 1, Pixel::Gray8, Type::Gaussian(0.5)).unwrap();
+    let mut dst = [0u8; 8];
+    r.resize([0u8, 0, 255, 255].as_gray(), dst.as_gray_mut()).unwrap();
+    assert!(dst.windows(2).all(|w| w[0] <= w[1]), "{:?}", dst);
+    assert_eq!((dst[0], dst[7]), (0, 255));
+}
+
+#[test]
+fn windowed_sinc_filter() {
+    assert_eq!(windowed_sinc(Window::Hamming, 1.0, 0.0), 1.0);
+    assert_eq!(windowed_sinc(Window::Hann, 3.0, 3.0), 0.0);
+    assert!((windowed_sinc(Window::Hamming, 1.0, 0.5) - 0.3438).abs() < 0.0001);
+    assert!(windowed_sinc(Window::Blackman, 2.0, 1.5) < 0.0);
+    for radius in [0., -1., f32::NAN, f32::INFINITY] {
+        assert!(new(4, 4, 3, 3, Pixel::Gray8, Type::WindowedSinc(Window::Blackman, radius)).is_err());
+    }
+    assert!(new(4, 4, 3, 3, Pixel::Gray8, Type::WindowedSinc(Window::Blackman, 4.0)).is_ok());
+}
+
+#[test]
+fn splines() {
+    for (kernel, support) in [(spline16 as fn(f32) -> f32, 2), (spline36, 3), (spline64, 4)].iter().copied() {
+        assert_eq!(kernel(0.), 1.);
+        for i in 1..=support {
+            assert!(kernel(i as f32).abs() < 1e-6);
+            assert!(kernel(i as f32 - 1e-3).abs() < 1e-2, "continuous at {}", i);
+        }
+        // interpolating filters preserve flat areas
+        let sum: f32 = (-support..support).map(|i| kernel(i as f32 + 0.3)).sum();
+        assert!((sum - 1.).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn magic_kernel_sharp() {
+    for (kernel, support) in [(magic_kernel_sharp_2013 as fn(f32) -> f32, 2.5f32), (magic_kernel_sharp_2021, 4.5)].iter().copied() {
+        assert!(kernel(support).abs() < 1e-6);
+        assert_eq!(kernel(support + 0.01), 0.);
+        for &knot in &[0.5f32, 1.5, 2.5, 3.5] {
+            if knot < support {
+                assert!((kernel(knot - 1e-4) - kernel(knot + 1e-4)).abs() < 1e-3, "continuous at {}", knot);
+            }
+        }
+        let sum: f32 = (-5..5).map(|i| kernel(i as f32 + 0.25)).sum();
+        assert!((sum - 1.).abs() < 1e-4, "{}", sum);
+    }
+}
+
+#[test]
+fn cubic_presets() {
+    use rgb::FromSlice;
+
+    let src = [0u8, 0, 200, 200];
+    let mut a = [0u8; 7];
+    let mut b = [0u8; 7];
+    new(4, 1, 7, 1, Pixel::Gray8, Type::Mitchell).unwrap().resize(src.as_gray(), a.as_gray_mut()).unwrap();
+    new(4, 1, 7, 1, Pixel::Gray8, Type::Cubic { b: 1. / 3., c: 1. / 3. }).unwrap().resize(src.as_gray(), b.as_gray_mut()).unwrap();
+    assert_eq!(a, b);
+    new(4, 1, 7, 1, Pixel::Gray8, Type::BSpline).unwrap().resize(src.as_gray(), b.as_gray_mut()).unwrap();
+    assert!(b.windows(2).all(|w| w[0] <= w[1]));
+    assert_ne!(a, b);
+    assert_eq!(cubic_bc(0., 0., 1.), 0.);
+    assert_eq!(cubic_bc(0., 0., 0.), 1.);
+}
+
+#[test]
+fn ewa() {
+    use rgb::FromSlice;
+
+    let mut r = EwaResizer::new(4, 4, 3, 2, Pixel::Gray8, 3).unwrap();
+    let mut dst = [0u8; 6];
+    r.resize([77u8; 16].as_gray(), dst.as_gray_mut()).unwrap();
+    assert_eq!(dst, [77; 6]);
+
+    let mut r = EwaResizer::new(2, 2, 4, 4, Pixel::Gray8, 2).unwrap();
+    let mut dst = [0u8; 16];
+    r.resize([0u8, 255, 0, 255].as_gray(), dst.as_gray_mut()).unwrap();
+    assert!(dst[0] < 30 && dst[3] > 225);
+    assert!(EwaResizer::new(2, 2, 4, 4, Pixel::Gray8, 5).is_err());
+    assert!(r.resize([0u8; 3].as_gray(), dst.as_gray_mut()).is_err());
+}
+
+#[test]
+fn anti_ringing() {
+    use px::AntiRinging;
+    use rgb::FromSlice;
+
+    let src = [10u8, 10, 10, 10, 200, 200, 200, 200];
+    let mut plain = [0u8; 20];
+    let mut clamped = [0u8; 20];
+    new(8, 1, 20, 1, Pixel::Gray8, Type::Lanczos3).unwrap().resize(src.as_gray(), plain.as_gray_mut()).unwrap();
+    new(8, 1, 20, 1, AntiRinging(Pixel::Gray8), Type::Lanczos3).unwrap().resize(src.as_gray(), clamped.as_gray_mut()).unwrap();
+    assert!(plain.iter().any(|v| !(10..=200).contains(v)));
+    assert!(clamped.iter().all(|v| (10..=200).contains(v)));
+    assert_eq!((clamped[0], clamped[19]), (10, 200));
+}
+
+#[test]
+fn sharpen() {
+    let src = [0u8, 0, 0, 0, 255, 255, 255, 255];
+    let mut soft = [0u8; 4];
+    new(8, 1, 4, 1, Pixel::Gray8, Type::Triangle).unwrap().resize(rgb::FromSlice::as_gray(&src[..]), rgb::FromSlice::as_gray_mut(&mut soft[..])).unwrap();
+    let options = Options::default().sharpen(0.5);
+    let mut sharp = [0u8; 4];
+    Resizer::with_options(8, 1, 4, 1, Pixel::Gray8, Type::Triangle, &options).unwrap()
+        .resize(rgb::FromSlice::as_gray(&src[..]), rgb::FromSlice::as_gray_mut(&mut sharp[..])).unwrap();
+    assert!(sharp[1] < soft[1] && sharp[2] > soft[2]);
+    assert_eq!((sharp[0], sharp[3]), (0, 255));
+
+    assert!(Resizer::with_options(8, 1, 4, 1, Pixel::Gray8, Type::Triangle, &options.sharpen(f32::NAN)).is_err());
+}
+
+#[test]
+fn axis_filters() {
+    use rgb::FromSlice;
+    let src = [0u8, 0, 255, 255, 0, 0, 255, 255];
+    let mut dst = [0u8; 9];
+    Resizer::with_axis_filters(4, 2, 3, 3, Pixel::Gray8, Type::Point, Type::Triangle, &Options::default()).unwrap()
+        .resize(src.as_gray(), dst.as_gray_mut()).unwrap();
+    assert_eq!(dst, [0, 255, 255, 0, 255, 255, 0, 255, 255]);
+    Resizer::with_axis_filters(4, 2, 3, 3, Pixel::Gray8, Type::Triangle, Type::Point, &Options::default()).unwrap()
+        .resize(src.as_gray(), dst.as_gray_mut()).unwrap();
+    assert!(dst[1] > 0 && dst[1] < 255);
+}
+
+#[test]
+fn alpha_filter() {
+    use px::RGBA;
+    let src = [RGBA::new(10u8, 20, 30, 0), RGBA::new(10, 20, 30, 0), RGBA::new(10, 20, 30, 255), RGBA::new(10, 20, 30, 255)];
+    let mut dst = [RGBA::new(0u8, 0, 0, 0); 8];
+    AlphaResizer::new(4, 1, 8, 1, Pixel::RGBA8, Type::Lanczos3, Type::Triangle).unwrap().resize(&src, &mut dst).unwrap();
+    assert!(dst.iter().all(|px| px.rgb() == px::RGB::new(10, 20, 30)));
+    assert_eq!(dst.iter().map(|px| px.a).collect::<Vec<_>>(), [0, 0, 0, 64, 191, 255, 255, 255]);
+
+    let mut lanczos = [RGBA::new(0u8, 0, 0, 0); 8];
+    new(4, 1, 8, 1, Pixel::RGBA8, Type::Lanczos3).unwrap().resize(&src, &mut lanczos).unwrap();
+    assert_ne!(dst, lanczos);
+}
+
+#[test]
+fn edge_modes() {
+    use rgb::FromSlice;
+    let src = [100u8, 100, 100, 100, 200, 200, 200, 200];
+    let resize = |edge, dst: &mut [u8]| {
+        Resizer::with_options(8, 1, 4, 1, Pixel::Gray8, Type::Triangle, &Options::default().edge(edge)).unwrap()
+            .resize(src.as_gray(), dst.as_gray_mut()).unwrap();
+    };
+    let mut clamp = [0u8; 4];
+    resize(Edge::Clamp, &mut clamp);
+    assert_eq!((clamp[0], clamp[3]), (100, 200));
+    let mut mirror = [0u8; 4];
+    resize(Edge::Mirror, &mut mirror);
+    assert_eq!((mirror[0], mirror[3]), (100, 200));
+    let mut wrap = [0u8; 4];
+    resize(Edge::Wrap, &mut wrap);
+    assert!(wrap[0] > 100 && wrap[3] < 200);
+    let mut constant = [0u8; 4];
+    resize(Edge::Constant, &mut constant);
+    assert!(constant[0] < 100 && constant[3] < 200);
+    let mut fill = [0u8; 4];
+    Resizer::with_options(8, 1, 4, 1, px::Fill::new(Pixel::Gray8, px::Gray::new(100)), Type::Triangle, &Options::default().edge(Edge::Constant)).unwrap()
+        .resize(src.as_gray(), fill.as_gray_mut()).unwrap();
+    assert_eq!(fill[0], 100);
+    assert!(fill[3] < 200);
+
+    // Kernels much wider than the image don't take long to set up
+    let wide = std::vec![
+        (Type::Gaussian(1e6), Edge::Wrap),
+        (Type::WindowedSinc(Window::Blackman, 1e7), Edge::Mirror),
+        (Type::Custom(Filter::new(Box::new(|_| 1.), f32::INFINITY)), Edge::Constant),
+    ];
+    for (filter, edge) in wide {
+        let mut dst = [0u8; 4];
+        Resizer::with_options(4, 4, 2, 2, Pixel::Gray8, filter, &Options::default().edge(edge)).unwrap()
+            .resize([100u8; 16].as_gray(), dst.as_gray_mut()).unwrap();
+        if edge != Edge::Constant {
+            assert_eq!(dst, [100; 4]);
+        }
+    }
+}
+
+#[test]
+fn alignment() {
+    use rgb::FromSlice;
+    let src = [0u8, 90];
+    let mut dst = [0u8; 4];
+    let resize = |alignment, dst: &mut [u8]| {
+        Resizer::with_options(2, 1, 4, 1, Pixel::Gray8, Type::Triangle, &Options::default().alignment(alignment)).unwrap()
+            .resize(src.as_gray(), dst.as_gray_mut()).unwrap();
+    };
+    resize(Alignment::Centers, &mut dst);
+    assert_eq!(dst, [0, 23, 68, 90]);
+    resize(Alignment::Corners, &mut dst);
+    assert_eq!(dst, [0, 30, 60, 90]);
+    resize(Alignment::Asymmetric, &mut dst);
+    assert_eq!(dst, [0, 45, 90, 90]);
+}
+
+#[test]
+fn phase_offset() {
+    use rgb::FromSlice;
+    let src = [0u8, 100, 200, 100];
+    let mut dst = [0u8; 4];
+    Resizer::with_options(4, 1, 4, 1, Pixel::Gray8, Type::Triangle, &Options::default().offset(0.5, 0.)).unwrap()
+        .resize(src.as_gray(), dst.as_gray_mut()).unwrap();
+    assert_eq!(dst, [50, 150, 150, 100]);
+    assert!(Resizer::with_options(4, 1, 4, 1, Pixel::Gray8, Type::Triangle, &Options::default().offset(f64::NAN, 0.)).is_err());
+}
+
+#[test]
+fn dither() {
+    use px::Gray;
+    let src = [Gray::new(100.25f32 / 255.); 64 * 64];
+    let mut dst = [Gray::new(0u8); 64 * 64];
+    new(64, 64, 64, 64, formats::Gray::<f32, u8>(core::marker::PhantomData), Type::Triangle).unwrap().resize(&src, &mut dst).unwrap();
+    assert!(dst.iter().all(|px| px.value() == 100));
+
+    let mut resizer = new(64, 64, 64, 64, px::Dither::new(formats::Gray::<f32, u8>(core::marker::PhantomData), 1. / 255.), Type::Triangle).unwrap();
+    resizer.resize(&src, &mut dst).unwrap();
+    assert!(dst.iter().all(|px| (100..=101).contains(&px.value())));
+    let mean = dst.iter().map(|px| px.value() as f32).sum::<f32>() / (64. * 64.);
+    assert!((mean - 100.25).abs() < 0.01, "{}", mean);
+
+    // Same pixels every time, and from every thread
+    let mut again = [Gray::new(0u8); 64 * 64];
+    resizer.resize(&src, &mut again).unwrap();
+    assert_eq!(dst, again);
+    fn sync<T: Sync>(_: &T) {}
+    sync(&resizer);
+}
+
+#[test]
+fn rounding() {
+    use px::{Rounding, RoundingMode};
+    use rgb::FromSlice;
+    let resize = |mode| {
+        let mut dst = [0u8; 4];
+        new(2, 1, 4, 1, Rounding::new(Pixel::Gray8, mode, 1.), Type::Triangle).unwrap().resize([0u8, 2].as_gray(), dst.as_gray_mut()).unwrap();
+        dst
+    };
+    assert_eq!(resize(RoundingMode::HalfUp), [0, 1, 2, 2]);
+    assert_eq!(resize(RoundingMode::HalfEven), [0, 0, 2, 2]);
+    assert_eq!(resize(RoundingMode::Truncate), [0, 0, 1, 2]);
+}
+
+#[test]
+fn video_range() {
+    use px::{Range, VideoPlane, VideoRange};
+    use rgb::FromSlice;
+    let resize = |filter, plane, input, output, src: [u8; 4]| {
+        let mut dst = [0u8; 8];
+        new(4, 1, 8, 1, VideoRange::new(Pixel::Gray8, plane, input, output, 8), filter).unwrap().resize(src.as_gray(), dst.as_gray_mut()).unwrap();
+        dst
+    };
+    let point = |plane, input, output, src| {
+        let dst = resize(Type::Point, plane, input, output, src);
+        [dst[0], dst[2], dst[4], dst[6]]
+    };
+    let ringing = resize(Type::Lanczos3, VideoPlane::Luma, Range::Limited, Range::Limited, [16, 16, 235, 235]);
+    assert_eq!(ringing.iter().min(), Some(&16));
+    assert_eq!(ringing.iter().max(), Some(&235));
+    assert_eq!(point(VideoPlane::Luma, Range::Limited, Range::Full, [16, 0, 126, 235]), [0, 0, 128, 255]);
+    assert_eq!(point(VideoPlane::Luma, Range::Full, Range::Limited, [0, 128, 255, 255]), [16, 126, 235, 235]);
+    assert_eq!(point(VideoPlane::Chroma, Range::Limited, Range::Full, [128, 16, 240, 0]), [128, 0, 255, 0]);
+    assert_eq!(point(VideoPlane::Chroma, Range::Full, Range::Limited, [0, 128, 255, 255]), [16, 128, 239, 239]);
+
+    // Out of range bit depths are clamped instead of panicking
+    let format = |bits| std::format!("{:?}", VideoRange::new(Pixel::Gray16, VideoPlane::Luma, Range::Limited, Range::Full, bits));
+    assert_eq!(format(0), format(8));
+    assert_eq!(format(40), format(16));
+}
+
+#[test]
+fn huge_width_positions() {
+    use px::Gray;
+    let (w1, w2) = (100_003, 300_007);
+    let src: Vec<_> = (0..w1).map(|i| Gray::new((i % 2) as f32)).collect();
+    let mut dst = std::vec![Gray::new(0f32); w2];
+    new(w1, 1, w2, 1, Pixel::GrayF32, Type::Triangle).unwrap().resize(&src, &mut dst).unwrap();
+    for x2 in (w2 - 1000..w2 - 1).step_by(7) {
+        let x1 = (x2 as f64 + 0.5) * (w1 as f64 / w2 as f64) - 0.5;
+        let frac = x1 - unsafe { floor(x1) };
+        let expected = if (x1 as usize) & 1 == 0 { frac } else { 1. - frac };
+        assert!((dst[x2].value() as f64 - expected).abs() < 1e-4, "{} {} {}", x2, dst[x2].value(), expected);
+    }
+}
+
+#[test]
+fn fixed_point() {
+    use px::RGB;
+    let src: Vec<_> = (0..64 * 48).map(|i| RGB::new((i * 7 % 256) as u8, (i % 64 * 4) as u8, (i / 64 * 5) as u8)).collect();
+    let filters: [fn() -> Type; 3] = [|| Type::Point, || Type::Triangle, || Type::Lanczos3];
+    for filter in filters {
+        let (mut float, mut fixed) = (std::vec![RGB::new(0u8, 0, 0); 37 * 71], std::vec![RGB::new(0u8, 0, 0); 37 * 71]);
+        new(64, 48, 37, 71, Pixel::RGB8, filter()).unwrap().resize(&src, &mut float).unwrap();
+        FixedPointResizer::new(64, 48, 37, 71, Pixel::RGB8, filter()).unwrap().resize(&src, &mut fixed).unwrap();
+        for (a, b) in float.iter().zip(&fixed) {
+            assert!((a.r as i16 - b.r as i16).abs() <= 1 && (a.g as i16 - b.g as i16).abs() <= 1 && (a.b as i16 - b.b as i16).abs() <= 1, "{} {}", a, b);
+        }
+    }
+    assert!(FixedPointResizer::with_options(64, 48, 37, 71, Pixel::RGB8, Type::Lanczos3, &Options::default().sharpen(2.)).is_err());
+
+    // Weights of lines past constant edges sum to less than 1, and stay that way
+    let edge = Options::default().edge(Edge::Constant);
+    let (mut float, mut fixed) = (std::vec![RGB::new(0u8, 0, 0); 37 * 71], std::vec![RGB::new(0u8, 0, 0); 37 * 71]);
+    Resizer::with_options(64, 48, 37, 71, Pixel::RGB8, Type::Lanczos3, &edge).unwrap().resize(&src, &mut float).unwrap();
+    FixedPointResizer::with_options(64, 48, 37, 71, Pixel::RGB8, Type::Lanczos3, &edge).unwrap().resize(&src, &mut fixed).unwrap();
+    for (a, b) in float.iter().zip(&fixed) {
+        assert!((a.r as i16 - b.r as i16).abs() <= 1 && (a.g as i16 - b.g as i16).abs() <= 1 && (a.b as i16 - b.b as i16).abs() <= 1, "{} {}", a, b);
+    }
+}
+
+#[test]
+fn kernel_lut() {
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use px::Gray;
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+    let custom = || Type::Custom(Filter::new(Box::new(|x| {
+        CALLS.fetch_add(1, Ordering::Relaxed);
+        lanczos(3., x)
+    }), 3.));
+    let src: Vec<_> = (0..997).map(|i| Gray::new((i % 13) as f32 / 13.)).collect();
+    let (mut exact, mut lut) = (std::vec![Gray::new(0.); 3001], std::vec![Gray::new(0.); 3001]);
+    new(997, 1, 3001, 1, Pixel::GrayF32, custom()).unwrap().resize(&src, &mut exact).unwrap();
+    let exact_calls = CALLS.swap(0, Ordering::Relaxed);
+    Resizer::with_options(997, 1, 3001, 1, Pixel::GrayF32, custom(), &Options::default().kernel_lut(true)).unwrap().resize(&src, &mut lut).unwrap();
+    let lut_calls = CALLS.swap(0, Ordering::Relaxed);
+    assert!(lut_calls < exact_calls, "{} {}", lut_calls, exact_calls);
+    for (a, b) in exact.iter().zip(&lut) {
+        assert!((a.value() - b.value()).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn coefficients() {
+    let r = new(4, 3, 2, 6, Pixel::Gray8, Type::Triangle).unwrap();
+    let w: Vec<_> = r.horizontal_coefficients().collect();
+    assert_eq!((w[0].0, w[1].0), (0, 1));
+    assert_eq!(w[0].1, &[0.75 / 1.75, 0.75 / 1.75, 0.25 / 1.75]);
+    assert!(w[0].1.iter().eq(w[1].1.iter().rev()));
+    assert_eq!(r.vertical_coefficients().len(), 6);
+    assert!(r.vertical_coefficients().all(|(start, weights)| start + weights.len() <= 3 && (weights.iter().sum::<f32>() - 1.).abs() < 1e-6));
+}
+
+#[test]
+fn from_coefficients() {
+    use rgb::FromSlice;
+    let src = [10u8, 20, 30, 40, 50, 60];
+    let mut dst = [0u8; 2];
+    let mut r = Resizer::from_coefficients(3, 2, Pixel::Gray8, [(0, &[0.5, 0.5][..]), (1, &[0., 1.])], [(0, &[0.5, 0.5][..])]).unwrap();
+    r.resize(src.as_gray(), dst.as_gray_mut()).unwrap();
+    assert_eq!(dst, [30, 45]);
+
+    let same = new(7, 5, 3, 9, Pixel::Gray8, Type::Lanczos3).unwrap();
+    let copy = Resizer::from_coefficients(7, 5, Pixel::Gray8, same.horizontal_coefficients(), same.vertical_coefficients()).unwrap();
+    assert!(same.vertical_coefficients().eq(copy.vertical_coefficients()));
+
+    assert!(Resizer::from_coefficients(3, 2, Pixel::Gray8, [(2, &[0.5, 0.5][..])], [(0, &[1.][..])]).is_err());
+    assert!(Resizer::from_coefficients(3, 2, Pixel::Gray8, [(0, [1.])], Vec::<(usize, [f32; 1])>::new()).is_err());
+}
+
+#[test]
+fn filter_bank() {
+    let r = new(5, 1, 3, 1, Pixel::Gray8, Type::Triangle).unwrap();
+    let bank = r.horizontal_coefficients().to_filter_bank().unwrap();
+    assert_eq!(bank.left.len(), 3);
+    assert_eq!(bank.data.len(), 3 * bank.filter_width);
+    for (row, left) in bank.data_i16.chunks(bank.filter_width).zip(&bank.left) {
+        assert_eq!(row.iter().map(|&c| c as i32).sum::<i32>(), 1 << 14);
+        assert!(left + bank.filter_width <= 5);
+    }
+    let taps: Vec<_> = r.horizontal_coefficients().collect();
+    let last = bank.data.chunks(bank.filter_width).last().unwrap();
+    assert_eq!(&last[last.len() - taps[2].1.len()..], taps[2].1);
+}
+
+#[test]
+fn nearest() {
+    use px::RGBA;
+    let src: Vec<_> = (0..35u8).map(|i| RGBA::new(i, i * 2, i * 3, 255 - i)).collect();
+    for &(w2, h2) in &[(7, 5), (3, 2), (16, 11), (1, 1)] {
+        let (mut point, mut nearest) = (std::vec![RGBA::new(0u8, 0, 0, 0); w2 * h2], std::vec![RGBA::new(0u8, 0, 0, 0); w2 * h2]);
+        let mut r = new(7, 5, w2, h2, Pixel::RGBA8, Type::Point).unwrap();
+        assert!(r.scale.index_maps.is_some());
+        r.resize(&src, &mut point).unwrap();
+        NearestResizer::new(7, 5, w2, h2).unwrap().resize(&src, &mut nearest).unwrap();
+        assert_eq!(point, nearest);
+    }
+    let mut dst = [0u32; 4];
+    NearestResizer::new(2, 1, 4, 1).unwrap().resize_stride(&[1u32, 2, 99], 3, &mut dst).unwrap();
+    assert_eq!(dst, [1, 2, 2, 2]);
+    assert!(new(7, 5, 3, 2, Pixel::RGBA8, Type::Triangle).unwrap().scale.index_maps.is_none());
+}
+
+#[test]
+fn identity() {
+    use px::RGBA;
+    let src: Vec<_> = (0..4 * 3 + 2).map(|i| RGBA::new(i as u8 * 17, 255 - i as u8, 3, (i * 20) as u8)).collect();
+    let mut dst = std::vec![RGBA::new(0u8, 0, 0, 0); 12];
+    let mut r = new(4, 3, 4, 3, Pixel::RGBA8, Type::Lanczos3).unwrap();
+    assert!(r.scale.identity);
+    r.resize_stride(&src, 5, &mut dst).unwrap();
+    assert_eq!(&dst[4..8], &src[5..9]);
+
+    let shifted = Resizer::with_options(4, 3, 4, 3, Pixel::RGBA8, Type::Lanczos3, &Options::default().offset(0.5, 0.)).unwrap();
+    assert!(!shifted.scale.identity);
+
+    // Copied without a round trip through f32, which would lose the low bits
+    let src = [px::Gray::new(u32::MAX - 1), px::Gray::new(16_777_217)];
+    let mut dst = [px::Gray::new(0u32); 2];
+    new(2, 1, 2, 1, formats::Gray::<u32, u32>::default(), Type::Triangle).unwrap().resize(&src, &mut dst).unwrap();
+    assert_eq!(dst, src);
+    assert!(!formats::Gray::<u8, f32>::default().copy_pixels(&[px::Gray::new(1)], &mut [px::Gray::new(0.)]));
+}
+
+#[test]
+fn box_downscale() {
+    use px::RGB;
+    let src: Vec<_> = (0..12 * 6).map(|i| RGB::new((i * 37 % 256) as u16 * 257, i as u16, 65535)).collect();
+    for &(w2, h2) in &[(6, 3), (4, 2), (3, 3), (1, 1)] {
+        let (mut area, mut boxed) = (std::vec![RGB::new(0u16, 0, 0); w2 * h2], std::vec![RGB::new(0u16, 0, 0); w2 * h2]);
+        new(12, 6, w2, h2, Pixel::RGB16, Type::Area).unwrap().resize(&src, &mut area).unwrap();
+        BoxDownscaler::new(12, 6, w2, h2, Pixel::RGB16).unwrap().resize(&src, &mut boxed).unwrap();
+        assert_eq!(area, boxed);
+    }
+    assert_eq!(new(12, 6, 4, 2, Pixel::RGB16, Type::Area).unwrap().scale.blocks, Some([3, 3]));
+    assert_eq!(new(12, 6, 5, 2, Pixel::RGB16, Type::Area).unwrap().scale.blocks, None);
+    assert_eq!(new(12, 6, 6, 3, Pixel::RGB16, Type::Triangle).unwrap().scale.blocks, None);
+    // Blocks of bottom-up rows
+    let mut flipped = [0u8; 2];
+    new(2, 4, 1, 2, Pixel::Gray8, Type::Area).unwrap()
+        .resize_signed_stride(rgb::FromSlice::as_gray(&[1u8, 2, 3, 4, 10, 20, 30, 42][..]), -2, rgb::FromSlice::as_gray_mut(&mut flipped[..]), 1).unwrap();
+    assert_eq!(flipped, [26, 3]);
+    let mut dst = [0u8; 2];
+    BoxDownscaler::new(4, 1, 2, 1, Pixel::Gray8).unwrap().resize(rgb::FromSlice::as_gray(&[0u8, 1, 200, 255][..]), rgb::FromSlice::as_gray_mut(&mut dst[..])).unwrap();
+    assert_eq!(dst, [1, 228]);
+    assert!(BoxDownscaler::new(5, 4, 2, 2, Pixel::Gray8).is_err());
+}
+
+#[test]
+fn coefficient_bytes() {
+    use rgb::FromSlice;
+    let src: Vec<u8> = (0..64 * 48).map(|i| (i * 31 % 256) as u8).collect();
+    for options in [Options::default().rotation(Rotation::Cw90), Options::default().multi_step(true)] {
+        let mut r = Resizer::with_options(64, 48, 8, 6, Pixel::Gray8, Type::Lanczos3, &options).unwrap();
+        let (w2, h2) = if options.multi_step { (8, 6) } else { (6, 8) };
+        let mut expected = std::vec![0u8; w2 * h2];
+        r.resize(src.as_gray(), expected.as_gray_mut()).unwrap();
+
+        let bytes = r.coefficients_to_bytes().unwrap();
+        let mut restored = Resizer::from_coefficient_bytes(&bytes, Pixel::Gray8).unwrap();
+        let mut dst = std::vec![0u8; w2 * h2];
+        restored.resize(src.as_gray(), dst.as_gray_mut()).unwrap();
+        assert_eq!(dst, expected);
+        assert_eq!(restored.coefficients_to_bytes().unwrap(), bytes);
+        assert!(restored.set_dimensions(64, 48, 4, 3).is_err());
+
+        // Corrupted data is rejected
+        assert!(Resizer::from_coefficient_bytes(&bytes[..bytes.len() - 1], Pixel::Gray8).is_err());
+        let mut longer = bytes.clone();
+        longer.push(0);
+        assert!(Resizer::from_coefficient_bytes(&longer, Pixel::Gray8).is_err());
+        let mut version = bytes.clone();
+        version[4] = 2;
+        assert!(Resizer::from_coefficient_bytes(&version, Pixel::Gray8).is_err());
+        // Start of the first line past the source width
+        let mut start = bytes.clone();
+        start[40] = 1;
+        assert!(Resizer::from_coefficient_bytes(&start, Pixel::Gray8).is_err());
+    }
+}
+
+#[test]
+fn wide_strips() {
+    use rgb::FromSlice;
+    // Wide enough to be resampled in several strips, which must give the same result as whole rows
+    let (w1, h1) = (2000, 16);
+    let src: Vec<f32> = (0..w1 * h1 * 4).map(|i| (i * 7919 % 1000) as f32 / 1000.).collect();
+    // Rotated output is 4 pixels wide, but it's still scaled along long rows
+    for (rotation, w2, h2) in [(Rotation::None, w1 - 1, 4), (Rotation::Cw90, 4, w1 - 1)] {
+        let mut r = Resizer::with_options(w1, h1, w2, h2, Pixel::RGBAF32, Type::Lanczos3, &Options::default().rotation(rotation)).unwrap();
+        assert!(r.scale.strip_width::<formats::Rgba<f32, f32>>() < r.scale.w2());
+        let len = w2 * h2 * 4;
+        let mut dst = std::vec![0f32; len];
+        let mut rows = Vec::new();
+        assert!(r.resize_with_progress(src.as_rgba(), dst.as_rgba_mut(), |done, _| {
+            rows.push(done);
+            ControlFlow::Continue(())
+        }).unwrap().is_continue());
+        // Reported once per band of rows
+        assert_eq!(rows.last(), Some(&r.scale.h2()));
+        assert!(rows.windows(2).all(|w| w[0] < w[1] && w[1] - w[0] <= STRIP_BAND_ROWS), "{:?}", rows);
+        let mut expected = std::vec![0f32; len];
+        r.resize_steps(src.as_rgba(), expected.as_rgba_mut(), 1).unwrap().for_each(drop);
+        assert_eq!(dst, expected);
+    }
+}
+
+#[test]
+fn multi_step() {
+    use px::RGBA;
+    let src: Vec<_> = (0..400 * 300).map(|i| {
+        let (x, y) = (i % 400, i / 400);
+        RGBA::new((x / 2) as u8, (y * 255 / 299) as u8, ((x + y) % 256) as u8, if x < 200 { 255 } else { 128 })
+    }).collect();
+    let (mut direct, mut stepped) = (std::vec![RGBA::new(0u8, 0, 0, 0); 20 * 15], std::vec![RGBA::new(0u8, 0, 0, 0); 20 * 15]);
+    new(400, 300, 20, 15, Pixel::RGBA8P, Type::Lanczos3).unwrap().resize(&src, &mut direct).unwrap();
+    let mut r = Resizer::with_options(400, 300, 20, 15, Pixel::RGBA8P, Type::Lanczos3, &Options::default().multi_step(true)).unwrap();
+    assert_eq!(r.horizontal_coefficients().len(), 20);
+    r.resize(&src, &mut stepped).unwrap();
+    for (a, b) in direct.iter().zip(&stepped) {
+        assert!((i16::from(a.r) - i16::from(b.r)).abs() <= 3 && (i16::from(a.g) - i16::from(b.g)).abs() <= 3 && (i16::from(a.a) - i16::from(b.a)).abs() <= 3, "{} {}", a, b);
+    }
+    assert!(r.resize(&src[1..], &mut stepped).is_err());
+
+    // Offset and edges are applied to the second step in the same place
+    let options = Options::default().offset(40., -20.).edge(Edge::Mirror);
+    Resizer::with_options(400, 300, 20, 15, Pixel::RGBA8P, Type::Lanczos3, &options).unwrap().resize(&src, &mut direct).unwrap();
+    Resizer::with_options(400, 300, 20, 15, Pixel::RGBA8P, Type::Lanczos3, &options.multi_step(true)).unwrap().resize(&src, &mut stepped).unwrap();
+    for (a, b) in direct.iter().zip(&stepped) {
+        assert!((i16::from(a.r) - i16::from(b.r)).abs() <= 3 && (i16::from(a.g) - i16::from(b.g)).abs() <= 3 && (i16::from(a.a) - i16::from(b.a)).abs() <= 3, "{} {}", a, b);
+    }
+}
+
+#[test]
+fn mips() {
+    use px::Gray;
+    let src: Vec<_> = (0..8 * 5).map(|i| Gray::new((i * 6) as u8)).collect();
+    let levels = generate_mips(&src, 8, 5, Pixel::Gray8, Type::Triangle, false).unwrap();
+    let sizes: Vec<_> = levels.iter().map(|l| (l.width, l.height, l.pixels.len())).collect();
+    assert_eq!(sizes, [(4, 2, 8), (2, 1, 2), (1, 1, 1)]);
+    let mut first = [Gray::new(0u8); 8];
+    new(8, 5, 4, 2, Pixel::Gray8, Type::Triangle).unwrap().resize(&src, &mut first).unwrap();
+    assert_eq!(levels[0].pixels, first);
+
+    let direct = generate_mips(&src, 8, 5, Pixel::Gray8, Type::Triangle, true).unwrap();
+    let mut last = [Gray::new(0u8); 1];
+    new(8, 5, 1, 1, Pixel::Gray8, Type::Triangle).unwrap().resize(&src, &mut last).unwrap();
+    assert_eq!(direct[2].pixels, last);
+    assert!(generate_mips(&src[1..], 8, 5, Pixel::Gray8, Type::Triangle, false).is_err());
+}
+
+#[test]
+fn batch() {
+    use px::RGB;
+    fn assert_send_sync<T: Send + Sync>(_: &T) {}
+    let images: Vec<Vec<_>> = (0..5u8).map(|n| (0..16).map(|i| RGB::new(n * 40, i * 10, 7)).collect()).collect();
+    let mut outputs = std::vec![std::vec![RGB::new(0u8, 0, 0); 6]; 5];
+    let mut r = new(4, 4, 3, 2, Pixel::RGB8, Type::Lanczos3).unwrap();
+    assert_send_sync(&r);
+    r.resize_all(images.iter().map(|i| &i[..]).zip(outputs.iter_mut().map(|o| &mut o[..]))).unwrap();
+
+    let mut copy = r.clone();
+    assert!(copy.scale.coeffs_w.iter().zip(&r.scale.coeffs_w).all(|(a, b)| Arc::ptr_eq(&a.coeffs, &b.coeffs)));
+    let mut dst = std::vec![RGB::new(0u8, 0, 0); 6];
+    copy.resize(&images[3], &mut dst).unwrap();
+    assert_eq!(dst, outputs[3]);
+}
+
+#[test]
+fn coefficient_cache() {
+    let mut cache = CoefficientCache::new();
+    let a = Resizer::with_cache(40, 30, 10, 10, Pixel::RGB8, Type::Lanczos3, &Options::default(), &mut cache).unwrap();
+    assert_eq!(cache.len(), 2);
+    let b = Resizer::with_cache(40, 30, 10, 10, Pixel::Gray8, Type::Lanczos3, &Options::default(), &mut cache).unwrap();
+    assert_eq!(cache.len(), 2);
+    assert!(a.scale.coeffs_h.iter().zip(&b.scale.coeffs_h).all(|(a, b)| Arc::ptr_eq(&a.coeffs, &b.coeffs)));
+    let c = Resizer::with_cache(40, 30, 10, 10, Pixel::Gray8, Type::Lanczos3, &Options::default().sharpen(0.5), &mut cache).unwrap();
+    assert_eq!(cache.len(), 4);
+    let fresh = Resizer::with_options(40, 30, 10, 10, Pixel::Gray8, Type::Lanczos3, &Options::default().sharpen(0.5)).unwrap();
+    assert!(c.horizontal_coefficients().zip(fresh.horizontal_coefficients()).all(|(a, b)| a == b));
+    Resizer::with_cache(40, 30, 10, 10, Pixel::Gray8, Type::Custom(Filter::new(Box::new(|x| lanczos(3.0, x)), 3.0)), &Options::default(), &mut cache).unwrap();
+    assert_eq!(cache.len(), 4);
+    cache.clear();
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn reconfigure() {
+    use rgb::FromSlice;
+    let src: Vec<u8> = (0..40 * 30).map(|i| (i * 7) as u8).collect();
+    let mut r = new(40, 30, 10, 10, Pixel::Gray8, Type::Lanczos3).unwrap();
+    r.resize(src.as_gray(), std::vec![0; 10 * 10].as_gray_mut()).unwrap();
+    let rows = r.scale.coeffs_h.clone();
+    let mut dst = std::vec![0; 20 * 10];
+
+    r.set_dimensions(40, 30, 20, 10).unwrap();
+    assert!(r.scale.coeffs_h.iter().zip(&rows).all(|(a, b)| Arc::ptr_eq(&a.coeffs, &b.coeffs)));
+    r.resize(src.as_gray(), dst.as_gray_mut()).unwrap();
+    let mut expected = std::vec![0; 20 * 10];
+    new(40, 30, 20, 10, Pixel::Gray8, Type::Lanczos3).unwrap().resize(src.as_gray(), expected.as_gray_mut()).unwrap();
+    assert_eq!(dst, expected);
+
+    r.set_filter(Type::Triangle).unwrap();
+    r.resize(src.as_gray(), dst.as_gray_mut()).unwrap();
+    new(40, 30, 20, 10, Pixel::Gray8, Type::Triangle).unwrap().resize(src.as_gray(), expected.as_gray_mut()).unwrap();
+    assert_eq!(dst, expected);
+
+    r.set_filter(Type::Custom(Filter::new(Box::new(triangle_kernel), 1.0))).unwrap();
+    assert!(r.set_dimensions(40, 30, 10, 10).is_err());
+    assert_eq!(r.horizontal_coefficients().len(), 20);
+}
+
+#[test]
+fn builder() {
+    use rgb::FromSlice;
+    let src: Vec<u8> = (0..24 * 3).map(|i| (i * 5) as u8).collect();
+    let mut expected = std::vec![0; 6 * 3];
+    Resizer::with_axis_filters(4, 6, 2, 3, Pixel::RGB8, Type::Catrom, Type::Triangle, &Options::default().edge(Edge::Mirror).sharpen(0.2)).unwrap()
+        .resize(src.as_rgb(), expected.as_rgb_mut()).unwrap();
+    let mut dst = std::vec![0; 6 * 3];
+    Resizer::build(4, 6, 2, 3).axis_filters(Type::Catrom, Type::Triangle).edge(Edge::Mirror).sharpen(0.2)
+        .construct(Pixel::RGB8).unwrap()
+        .resize(src.as_rgb(), dst.as_rgb_mut()).unwrap();
+    assert_eq!(dst, expected);
+    assert!(Resizer::build(4, 6, 2, 3).sharpen(-1.).construct(Pixel::RGB8).is_err());
+}
+
+#[test]
+fn caller_scratch() {
+    use rgb::FromSlice;
+    let src: Vec<u8> = (0..64 * 48).map(|i| (i * 13) as u8).collect();
+    for options in [Options::default(), Options::default().multi_step(true)] {
+        let mut r = Resizer::with_options(64, 48, 8, 6, Pixel::Gray8, Type::Lanczos3, &options).unwrap();
+        let mut expected = std::vec![0; 8 * 6];
+        r.resize(src.as_gray(), expected.as_gray_mut()).unwrap();
+        let mut scratch = std::vec![formats::Gray::<u8, u8>::new(); r.scratch_len()];
+        let mut dst = std::vec![0; 8 * 6];
+        r.resize_with_scratch(src.as_gray(), dst.as_gray_mut(), &mut scratch).unwrap();
+        assert_eq!(dst, expected);
+        assert!(r.resize_with_scratch(src.as_gray(), dst.as_gray_mut(), &mut scratch[1..]).is_err());
+    }
+}
+
+#[test]
+#[cfg(feature = "capi")]
+fn capi() {
+    use capi::*;
+    let src: Vec<u8> = (0..6 * 4).collect();
+    let mut dst = [0xAA; 3 * 2];
+    unsafe {
+        let r = resize_new(4, 4, 2, 2, ResizePixelFormat::Gray8, ResizeFilter::Point);
+        assert!(!r.is_null());
+        assert_eq!(resize_run(r, src.as_ptr(), 6, dst.as_mut_ptr(), 3), RESIZE_OK);
+        assert_eq!(resize_run(r, src.as_ptr(), 3, dst.as_mut_ptr(), 3), RESIZE_ERROR_INVALID_PARAMETERS);
+        resize_free(r);
+        assert!(resize_new(0, 4, 2, 2, ResizePixelFormat::RGB8, ResizeFilter::Point).is_null());
+    }
+    assert_eq!(dst, [7, 9, 0xAA, 19, 21, 0xAA]);
+}
+
+#[test]
+#[cfg(feature = "wasm")]
+fn wasm() {
+    let mut r = wasm::Resizer::new(4, 4, 2, 2, wasm::PixelFormat::RGBA8, wasm::Filter::Triangle).ok().unwrap();
+    let dst = r.resize(&[200; 4 * 4 * 4]).ok().unwrap();
+    assert_eq!(dst, [200; 2 * 2 * 4]);
+}
+
+#[test]
+#[cfg(feature = "image")]
+fn image_buffers() {
+    let img = ::image::RgbaImage::from_pixel(8, 6, ::image::Rgba([10, 20, 30, 255]));
+    let small = image::resize_image(&img, 4, 3, Type::Lanczos3).unwrap();
+    assert!(small.pixels().all(|p| p.0 == [10, 20, 30, 255]));
+
+    let gray = ::image::DynamicImage::ImageLuma16(::image::ImageBuffer::from_pixel(8, 6, ::image::Luma([1000u16])));
+    let small = image::resize_dynamic_image(&gray, 2, 2, Type::Triangle).unwrap();
+    assert_eq!(small.as_luma16().unwrap().as_raw(), &[1000; 4]);
+    assert!(image::resize_dynamic_image(&gray, 0, 2, Type::Triangle).is_err());
+}
+
+#[test]
+#[cfg(feature = "imgref")]
+fn imgref_strides() {
+    use ::imgref::{Img, ImgVec};
+    use rgb::FromSlice;
+    let mut r = new(4, 2, 2, 1, Pixel::Gray8, Type::Point).unwrap();
+    let padded = Img::new_stride(std::vec![10u8, 10, 30, 30, 0, 10, 10, 30, 30].as_gray().to_vec(), 4, 2, 5);
+    let mut dst = ImgVec::new(std::vec![rgb::alt::Gray(99u8); 2 * 3], 3, 2);
+    r.resize_img(padded.as_ref(), dst.sub_image_mut(0, 0, 2, 1)).unwrap();
+    assert_eq!(dst.buf(), [10u8, 30, 99, 99, 99, 99].as_gray());
+    assert_eq!(r.resize_img_vec(padded.as_ref()).unwrap().buf(), [10u8, 30].as_gray());
+    assert!(r.resize_img(padded.as_ref(), dst.as_mut()).is_err());
+}
+
+#[test]
+#[cfg(feature = "ndarray")]
+fn ndarray_views() {
+    use ::ndarray::{s, Array2, Array3};
+    let wide = Array2::from_shape_fn((4, 8), |(_, c)| if c < 4 { 1. } else { 5. });
+    // Non-contiguous view: columns 0, 2, 4, 6
+    let small = ndarray::resize_array2(wide.slice(s![.., ..;2]), 2, 2, Type::Point).unwrap();
+    assert_eq!(small, Array2::from_shape_vec((2, 2), std::vec![1., 5., 1., 5.]).unwrap());
+    // Contiguous rows with gaps between them: columns 2..6
+    let middle = ndarray::resize_array2(wide.slice(s![.., 2..6]), 2, 2, Type::Point).unwrap();
+    assert_eq!(middle, Array2::from_shape_vec((2, 2), std::vec![1., 5., 1., 5.]).unwrap());
+    let bottom_right = ndarray::resize_array2(wide.slice(s![2.., 5..]), 1, 3, Type::Point).unwrap();
+    assert_eq!(bottom_right, Array2::from_shape_vec((1, 3), std::vec![5.; 3]).unwrap());
+
+    let rgb = Array3::from_shape_fn((4, 4, 3), |(_, _, ch)| ch as f32);
+    let small = ndarray::resize_array3(rgb.view(), 2, 3, Type::Lanczos3).unwrap();
+    assert_eq!(small.dim(), (2, 3, 3));
+    assert!(small.indexed_iter().all(|((_, _, ch), &v)| (v - ch as f32).abs() < 1e-5));
+}
+
+#[test]
+#[cfg(feature = "bytemuck")]
+fn bytes() {
+    let src: Vec<u16> = std::vec![1000; 4 * 4 * 3];
+    let mut dst = std::vec![0u16; 2 * 2 * 3];
+    let mut r = new(4, 4, 2, 2, Pixel::RGB16, Type::Triangle).unwrap();
+    r.resize_bytes(bytemuck::cast_slice(&src), bytemuck::cast_slice_mut(&mut dst)).unwrap();
+    assert_eq!(dst, [1000; 12]);
+    let bytes: &[u8] = bytemuck::cast_slice(&src);
+    assert!(bytes::pixels::<rgb::RGB<u16>>(&bytes[1..7]).is_err());
+    assert!(bytes::pixels::<rgb::RGB<u16>>(&bytes[..7]).is_err());
+    assert_eq!(bytes::pixels::<rgb::RGB<u16>>(&bytes[..12]).unwrap(), [rgb::RGB::new(1000, 1000, 1000); 2]);
+}
+
+#[test]
+fn progress() {
+    use rgb::{FromSlice, RGB8};
+    let src = std::vec![7u8; 64 * 48];
+    let mut dst = std::vec![0u8; 16 * 12];
+    let mut r = new(64, 48, 16, 12, Pixel::Gray8, Type::Lanczos3).unwrap();
+    let mut calls = Vec::new();
+    let flow = r.resize_with_progress(src.as_gray(), dst.as_gray_mut(), |done, total| {
+        calls.push((done, total));
+        if done == 3 { ControlFlow::Break(()) } else { ControlFlow::Continue(()) }
+    }).unwrap();
+    assert!(flow.is_break());
+    assert_eq!(calls, [(1, 12), (2, 12), (3, 12)]);
+    assert!(dst[..3 * 16].iter().all(|&p| p == 7) && dst[3 * 16..].iter().all(|&p| p == 0));
+
+    let mut r = Resizer::with_options(64, 48, 8, 6, Pixel::Gray8, Type::Lanczos3, &Options::default().multi_step(true)).unwrap();
+    let mut last = (0, 0);
+    let flow = r.resize_with_progress(src.as_gray(), dst[..8 * 6].as_gray_mut(), |done, total| {
+        last = (done, total);
+        ControlFlow::Continue(())
+    }).unwrap();
+    assert!(flow.is_continue());
+    assert_eq!(last, (12 + 6, 12 + 6));
+
+    // Wide images resampled in strips report progress before the first strip is done
+    let src = std::vec![RGB8::new(7, 7, 7); 8000 * 40];
+    let mut dst = std::vec![RGB8::new(0, 0, 0); 4000 * 20];
+    let mut r = new(8000, 40, 4000, 20, Pixel::RGB8, Type::Lanczos3).unwrap();
+    assert!(r.scale.strip_width::<formats::Rgb<u8, u8>>() < 4000);
+    let mut calls = Vec::new();
+    let flow = r.resize_with_progress(&src, &mut dst, |done, total| {
+        calls.push((done, total));
+        ControlFlow::Break(())
+    }).unwrap();
+    assert!(flow.is_break());
+    assert_eq!(calls, [(STRIP_BAND_ROWS, 20)]);
+    let (band, rest) = dst.split_at(STRIP_BAND_ROWS * 4000);
+    assert!(band.iter().all(|&p| p == RGB8::new(7, 7, 7)) && rest.iter().all(|&p| p == RGB8::new(0, 0, 0)));
+}
+
+#[test]
+fn flip() {
+    use rgb::FromSlice;
+    let src: Vec<u8> = (0..8 * 4).map(|i| i * 8).collect();
+    let mut plain = std::vec![0u8; 4 * 2];
+    new(8, 4, 4, 2, Pixel::Gray8, Type::Triangle).unwrap().resize(src.as_gray(), plain.as_gray_mut()).unwrap();
+    let mut r = Resizer::build(8, 4, 4, 2).filter(Type::Triangle).flip(true, true).construct(Pixel::Gray8).unwrap();
+    let mut flipped = std::vec![0u8; 4 * 2];
+    r.resize(src.as_gray(), flipped.as_gray_mut()).unwrap();
+    assert_eq!(flipped, plain.iter().rev().copied().collect::<Vec<_>>());
+
+    r.set_dimensions(8, 4, 4, 4).unwrap();
+    let mut flipped = std::vec![0u8; 4 * 4];
+    r.resize(src.as_gray(), flipped.as_gray_mut()).unwrap();
+    let mut plain = std::vec![0u8; 4 * 4];
+    new(8, 4, 4, 4, Pixel::Gray8, Type::Triangle).unwrap().resize(src.as_gray(), plain.as_gray_mut()).unwrap();
+    assert_eq!(flipped, plain.iter().rev().copied().collect::<Vec<_>>());
+}
+
+#[test]
+fn rotation() {
+    use rgb::FromSlice;
+    // 3x2:
+    // 1 2 3
+    // 4 5 6
+    let src = [1u8, 2, 3, 4, 5, 6];
+    let rotate = |rotation, w2, h2, flip| {
+        let mut dst = std::vec![0u8; w2 * h2];
+        Resizer::with_options(3, 2, w2, h2, Pixel::Gray8, Type::Point, &Options::default().rotation(rotation).flip(flip, false)).unwrap()
+            .resize(src.as_gray(), dst.as_gray_mut()).unwrap();
+        dst
+    };
+    assert_eq!(rotate(Rotation::Cw90, 2, 3, false), [4, 1, 5, 2, 6, 3]);
+    assert_eq!(rotate(Rotation::Cw180, 3, 2, false), [6, 5, 4, 3, 2, 1]);
+    assert_eq!(rotate(Rotation::Cw270, 2, 3, false), [3, 6, 2, 5, 1, 4]);
+    assert_eq!(rotate(Rotation::Cw90, 2, 3, true), [1, 4, 2, 5, 3, 6]);
+
+    // Rotating while downscaling matches downscaling first
+    let src: Vec<u8> = (0..64 * 48).map(|i| (i * 7 % 251) as u8).collect();
+    let mut upright = std::vec![0u8; 16 * 12];
+    Resizer::with_options(64, 48, 16, 12, Pixel::Gray8, Type::Lanczos3, &Options::default().multi_step(true)).unwrap()
+        .resize(src.as_gray(), upright.as_gray_mut()).unwrap();
+    let mut rotated = std::vec![0u8; 13 * 16];
+    let mut r = Resizer::build(64, 48, 12, 16).rotation(Rotation::Cw90).multi_step(true).construct(Pixel::Gray8).unwrap();
+    r.resize_typed(src.as_gray(), 64, rotated[..13 * 15 + 12].as_gray_mut(), 13).unwrap();
+    for y in 0..16 {
+        for x in 0..12 {
+            assert_eq!(rotated[y * 13 + x], upright[(11 - x) * 16 + y]);
+        }
+    }
+    assert!(FixedPointResizer::with_options(64, 48, 12, 16, Pixel::Gray8, Type::Lanczos3, &Options::default().rotation(Rotation::Cw90)).is_err());
+}
+
+#[test]
+fn bottom_up_rows() {
+    use rgb::FromSlice;
+    let src: Vec<u8> = (0..40 * 30).map(|i| (i * 13 % 251) as u8).collect();
+    let flip_rows = |img: &[u8], w: usize| img.chunks(w).rev().flatten().copied().collect::<Vec<u8>>();
+    for multi_step in [false, true] {
+        let options = Options::default().multi_step(multi_step);
+        let mut expected = std::vec![0u8; 10 * 7];
+        Resizer::with_options(40, 30, 10, 7, Pixel::Gray8, Type::Lanczos3, &options).unwrap()
+            .resize(src.as_gray(), expected.as_gray_mut()).unwrap();
+        let mut r = Resizer::with_options(40, 30, 10, 7, Pixel::Gray8, Type::Lanczos3, &options).unwrap();
+
+        let mut dst = std::vec![0u8; 10 * 7];
+        r.resize_signed_stride(flip_rows(&src, 40).as_gray(), -40, dst.as_gray_mut(), 10).unwrap();
+        assert_eq!(dst, expected);
+        r.resize_signed_stride(src.as_gray(), 40, dst.as_gray_mut(), -10).unwrap();
+        assert_eq!(dst, flip_rows(&expected, 10));
+        r.resize_signed_stride(flip_rows(&src, 40).as_gray(), -40, dst.as_gray_mut(), -10).unwrap();
+        assert_eq!(dst, flip_rows(&expected, 10));
+    }
+    let mut r = Resizer::new(4, 2, 4, 2, Pixel::Gray8, Type::Point).unwrap();
+    let mut dst = [0u8; 8];
+    r.resize_signed_stride([1u8, 2, 3, 4, 5, 6, 7, 8].as_gray(), -4, dst.as_gray_mut(), 4).unwrap();
+    assert_eq!(dst, [5, 6, 7, 8, 1, 2, 3, 4]);
+    assert!(r.resize_signed_stride([0u8; 8].as_gray(), 0, dst.as_gray_mut(), 4).is_err());
+}
+
+#[test]
+fn resize_into_offset() {
+    use rgb::FromSlice;
+    let src: Vec<u8> = (0..20 * 10).map(|i| (i * 3 % 256) as u8).collect();
+    let mut expected = std::vec![0u8; 8 * 4];
+    let mut r = Resizer::new(20, 10, 8, 4, Pixel::Gray8, Type::Lanczos3).unwrap();
+    r.resize(src.as_gray(), expected.as_gray_mut()).unwrap();
+
+    let mut atlas = std::vec![7u8; 12 * 7];
+    r.resize_into(src.as_gray(), atlas.as_gray_mut(), 12, 3, 2).unwrap();
+    for (y, row) in atlas.chunks(12).enumerate() {
+        for (x, &px) in row.iter().enumerate() {
+            if (3..11).contains(&x) && (2..6).contains(&y) {
+                assert_eq!(px, expected[(y - 2) * 8 + x - 3]);
+            } else {
+                assert_eq!(px, 7);
+            }
+        }
+    }
+    assert!(r.resize_into(src.as_gray(), atlas.as_gray_mut(), 12, 5, 2).is_err());
+    assert!(r.resize_into(src.as_gray(), atlas.as_gray_mut(), 12, 3, 4).is_err());
+
+    // Rows of the output stacked in a taller image
+    let mut column = std::vec![0u8; 8 * 10];
+    r.resize_into(src.as_gray(), column.as_gray_mut(), 8, 0, 5).unwrap();
+    assert_eq!(column[40..72], expected[..]);
+}
+
+#[test]
+fn output_tile() {
+    use rgb::FromSlice;
+    let src: Vec<u8> = (0..50 * 40).map(|i| (i * 11 % 253) as u8).collect();
+    let mut full = std::vec![0u8; 30 * 20];
+    let mut r = Resizer::new(50, 40, 30, 20, Pixel::Gray8, Type::Lanczos3).unwrap();
+    r.resize(src.as_gray(), full.as_gray_mut()).unwrap();
+    for (cols, rows) in [(0..30, 0..20), (0..8, 0..5), (11..19, 7..20), (29..30, 19..20)] {
+        let mut tile = std::vec![0u8; cols.len() * rows.len()];
+        r.resize_tile(src.as_gray(), tile.as_gray_mut(), cols.clone(), rows.clone()).unwrap();
+        for (y, tile_row) in rows.clone().zip(tile.chunks(cols.len())) {
+            assert_eq!(tile_row, &full[y * 30..][cols.clone()]);
+        }
+    }
+    let mut tile = [0u8; 4];
+    assert!(r.resize_tile(src.as_gray(), tile.as_gray_mut(), 28..32, 0..1).is_err());
+    assert!(r.resize_tile(src.as_gray(), tile.as_gray_mut(), 0..2, 0..1).is_err());
+}
+
+#[test]
+fn letterbox() {
+    use rgb::FromSlice;
+    let src = [200u8; 30 * 10];
+    let mut dst = [0u8; 12 * 9];
+    let mut r = LetterboxResizer::new(30, 10, 12, 9, Pixel::Gray8, Type::Lanczos3).unwrap();
+    assert_eq!(r.placement(), (0, 2, 12, 4));
+    r.resize(src.as_gray(), dst.as_gray_mut(), rgb::alt::Gray(7)).unwrap();
+    for (y, row) in dst.chunks(12).enumerate() {
+        let expected = if (2..6).contains(&y) { 200 } else { 7 };
+        assert!(row.iter().all(|&px| px == expected));
+    }
+
+    // Pillarbox, rounded to the nearest pixel
+    let r = LetterboxResizer::new(10, 30, 9, 9, Pixel::Gray8, Type::Lanczos3).unwrap();
+    assert_eq!(r.placement(), (3, 0, 3, 9));
+    assert_eq!(LetterboxResizer::new(1000, 1, 9, 9, Pixel::Gray8, Type::Lanczos3).unwrap().placement(), (0, 4, 9, 1));
+    assert!(LetterboxResizer::new(10, 30, 0, 9, Pixel::Gray8, Type::Lanczos3).is_err());
+}
+
+#[test]
+fn cover() {
+    use rgb::FromSlice;
+    let src: Vec<u8> = (0..30 * 10).map(|i| (i % 30) as u8).collect();
+    let r = CoverResizer::new(30, 10, 6, 4, Pixel::Gray8, Type::Triangle).unwrap();
+    assert_eq!(r.crop(), (7, 0, 15, 10));
+    assert_eq!(r.resizer().scale.dest_dimensions(), (6, 4));
+    let mut r = r.focus(0.9, 0.5);
+    assert_eq!(r.crop(), (15, 0, 15, 10));
+    assert_eq!(CoverResizer::new(30, 10, 6, 4, Pixel::Gray8, Type::Triangle).unwrap().focus(0.1, 0.).crop(), (0, 0, 15, 10));
+
+    let mut expected = std::vec![0u8; 6 * 4];
+    Resizer::new(15, 10, 6, 4, Pixel::Gray8, Type::Triangle).unwrap().resize_stride(src[15..].as_gray(), 30, expected.as_gray_mut()).unwrap();
+    let mut dst = std::vec![0u8; 6 * 4];
+    r.resize(src.as_gray(), dst.as_gray_mut()).unwrap();
+    assert_eq!(dst, expected);
+    assert!(r.resize(src[1..].as_gray(), dst.as_gray_mut()).is_err());
+
+    let r = CoverResizer::new(10, 30, 5, 5, Pixel::Gray8, Type::Triangle).unwrap();
+    assert_eq!(r.crop(), (0, 10, 10, 10));
+}
+
+#[test]
+fn target_dimensions() {
+    assert_eq!(Dimensions::fit(100, 100).of(1920, 1080).unwrap(), (100, 56));
+    assert_eq!(Dimensions::fit(100, 100).of(1080, 1920).unwrap(), (56, 100));
+    assert_eq!(Dimensions::fit(100, 100).of(10, 5).unwrap(), (100, 50));
+    assert_eq!(Dimensions::fit(100, 100).without_upscaling().of(10, 5).unwrap(), (10, 5));
+    assert_eq!(Dimensions::fit(3, 3).of(1000, 1).unwrap(), (3, 1));
+    assert_eq!(Dimensions::fill(100, 100).of(1920, 1080).unwrap(), (178, 100));
+    assert_eq!(Dimensions::fill(100, 50).of(100, 50).unwrap(), (100, 50));
+    assert_eq!(Dimensions::fill(100, 100).without_upscaling().of(150, 80).unwrap(), (150, 80));
+    assert_eq!(Dimensions::exact(100, 30).of(50, 50).unwrap(), (100, 30));
+    assert_eq!(Dimensions::exact(100, 30).without_upscaling().of(50, 50).unwrap(), (50, 30));
+    assert!(Dimensions::fit(0, 100).of(10, 10).is_err());
+    assert!(Dimensions::exact(10, 10).of(10, 0).is_err());
+
+    // Anamorphic sources
+    assert_eq!(Dimensions::fit(1920, 1080).pixel_aspect(4, 3).of(1440, 1080).unwrap(), (1920, 1080));
+    assert_eq!(Dimensions::fit(1000, 1000).pixel_aspect(4, 3).of(1440, 1080).unwrap(), (1000, 563));
+    assert_eq!(Dimensions::fill(100, 100).pixel_aspect(4, 3).of(1440, 1080).unwrap(), (178, 100));
+    assert_eq!(Dimensions::fit(4000, 4000).pixel_aspect(4, 3).without_upscaling().of(1440, 1080).unwrap(), (1920, 1080));
+    assert_eq!(Dimensions::exact(100, 30).pixel_aspect(4, 3).of(50, 50).unwrap(), (100, 30));
+    assert!(Dimensions::fit(10, 10).pixel_aspect(0, 1).of(10, 10).is_err());
+}
+
+#[test]
+fn masked() {
+    use px::Masked;
+    use rgb::FromSlice;
+    let src = [2., 2., f32::NAN, 2., 2., 2., 8., 8., f32::NAN, f32::NAN, f32::NAN, f32::NAN];
+    let mut dst = [0f32; 6];
+    new(12, 1, 6, 1, Masked::nan(Pixel::GrayF32), Type::Triangle).unwrap().resize(src.as_gray(), dst.as_gray_mut()).unwrap();
+    assert!((dst[0] - 2.).abs() < 1e-5 && (dst[1] - 2.).abs() < 1e-5, "{:?}", dst);
+    assert!(dst[3] > 2. && dst[3] < 8.);
+    assert!((dst[4] - 8.).abs() < 1e-5);
+    assert!(dst[5].is_nan());
+
+    // Sentinel values in 2D
+    let mut src = [100u16; 8 * 8];
+    src[9] = 0;
+    src[30] = 0;
+    let mut dst = [0u16; 4 * 4];
+    let format = Masked::new(Pixel::Gray16, |px| px.value() != 0);
+    new(8, 8, 4, 4, format, Type::Mitchell).unwrap().resize(src.as_gray(), dst.as_gray_mut()).unwrap();
+    assert_eq!(dst, [100; 16]);
+}
+
+#[test]
+fn coverage_mask() {
+    use rgb::FromSlice;
+    // Hard edge that rings with Lanczos
+    let src: Vec<f32> = (0..16).map(|x| if x < 8 { 0. } else { 1. }).collect();
+    let mut gray = [0f32; 24];
+    new(16, 1, 24, 1, Pixel::GrayF32, Type::Lanczos3).unwrap().resize(src.as_gray(), gray.as_gray_mut()).unwrap();
+    assert!(gray.iter().any(|&v| !(0. ..=1.).contains(&v)));
+    let mut mask = [0f32; 24];
+    new(16, 1, 24, 1, Pixel::CoverageF32, Type::Lanczos3).unwrap().resize(src.as_gray(), mask.as_gray_mut()).unwrap();
+    assert!(mask.iter().all(|&v| (0. ..=1.).contains(&v)));
+
+    // Area weighting keeps the total coverage, up to rounding of every pixel
+    let src: Vec<u8> = (0..64).map(|i| if i % 8 < 3 { 255 } else { 0 }).collect();
+    let mut dst = [0u8; 16];
+    new(8, 8, 4, 4, Pixel::Coverage8, Type::Area).unwrap().resize(src.as_gray(), dst.as_gray_mut()).unwrap();
+    let total = |px: &[u8]| px.iter().map(|&v| u32::from(v)).sum::<u32>();
+    assert!((total(&src) / 4).abs_diff(total(&dst)) <= 16);
+}
+
+#[test]
+fn interlaced() {
+    use rgb::FromSlice;
+    // Fields of different brightness don't bleed into each other
+    let src: Vec<u8> = (0..16 * 12).map(|i| if (i / 16) % 2 == 0 { 50 } else { 200 }).collect();
+    let mut dst = std::vec![0u8; 10 * 8];
+    InterlacedResizer::new(16, 12, 10, 8, Pixel::Gray8, Type::Lanczos3).unwrap().resize(src.as_gray(), dst.as_gray_mut()).unwrap();
+    for (y, row) in dst.chunks(10).enumerate() {
+        assert!(row.iter().all(|&px| px == if y % 2 == 0 { 50 } else { 200 }), "{:?}", dst);
+    }
+
+    // A vertical gradient stays in place in both fields
+    let src: Vec<f32> = (0..4 * 40).map(|i| (i / 4) as f32).collect();
+    let mut dst = std::vec![0f32; 4 * 20];
+    InterlacedResizer::new(4, 40, 4, 20, Pixel::GrayF32, Type::Triangle).unwrap().resize(src.as_gray(), dst.as_gray_mut()).unwrap();
+    for y in 2..18 {
+        // Output row `y` is centered on source row `2y + 0.5`
+        assert!((dst[y * 4] - (2 * y) as f32 - 0.5).abs() < 1e-4, "{} {:?}", y, dst);
+    }
+    assert!(InterlacedResizer::new(4, 41, 4, 20, Pixel::GrayF32, Type::Triangle).is_err());
+}
+
+#[test]
+fn chroma_subsampling() {
+    use rgb::FromSlice;
+    assert_eq!(Subsampling::Yuv420.chroma_dimensions(1919, 1079), (960, 540));
+    assert_eq!(Subsampling::Yuv422.chroma_dimensions(1919, 1079), (960, 1079));
+    // Chroma samples hold the luma x coordinate of their position
+    let left: Vec<f32> = (0..10 * 2).map(|i| (i % 10 * 2) as f32).collect();
+    let center: Vec<f32> = left.iter().map(|&x| x + 0.5).collect();
+    for (siting, src) in [(ChromaSiting::Left, &left), (ChromaSiting::Center, &center)] {
+        let mut dst = [0f32; 20 * 4];
+        ChromaResizer::new(20, 4, Subsampling::Yuv420, Subsampling::Yuv444, siting, Pixel::GrayF32, Type::Triangle).unwrap()
+            .resize(src.as_gray(), dst.as_gray_mut()).unwrap();
+        for x in 1..18 {
+            assert!((dst[x] - x as f32).abs() < 1e-4, "{:?} {:?}", siting, &dst[..20]);
+        }
+        let mut back = [0f32; 10 * 4];
+        ChromaResizer::new(20, 4, Subsampling::Yuv444, Subsampling::Yuv422, siting, Pixel::GrayF32, Type::Triangle).unwrap()
+            .resize(dst.as_gray(), back.as_gray_mut()).unwrap();
+        for x in 1..9 {
+            assert!((back[x] - src[x]).abs() < 1e-4, "{:?} {:?}", siting, &back[..10]);
+        }
+    }
+}
+
+#[test]
+fn tone_map() {
+    use px::ToneMap;
+    use rgb::FromSlice;
+    let src = [0f32, 0.25, 1., 4., 100., 0., 1., 1.];
+    let mut clamped = [0u8; 8];
+    new(8, 1, 8, 1, formats::Gray::<f32, u8>::instance(), Type::Point).unwrap().resize(src.as_gray(), clamped.as_gray_mut()).unwrap();
+    assert_eq!(clamped, [0, 64, 255, 255, 255, 0, 255, 255]);
+    let mut mapped = [0u8; 8];
+    new(8, 1, 8, 1, ToneMap::reinhard(formats::Gray::<f32, u8>::instance(), 1.), Type::Point).unwrap().resize(src.as_gray(), mapped.as_gray_mut()).unwrap();
+    assert_eq!(mapped, [0, 51, 128, 204, 252, 0, 128, 128]);
+    new(8, 1, 8, 1, ToneMap::aces(formats::Gray::<f32, u8>::instance(), 0.5), Type::Point).unwrap().resize(src.as_gray(), mapped.as_gray_mut()).unwrap();
+    assert!(mapped.windows(2).take(4).all(|w| w[0] < w[1]));
+
+    let src = [px::RGBA::new(4f32, 4., 4., 0.5)];
+    let mut dst = [px::RGBA::new(0u8, 0, 0, 0)];
+    new(1, 1, 1, 1, ToneMap::reinhard(formats::Rgba::<f32, u8>::instance(), 1.).keep_alpha(), Type::Point).unwrap().resize(&src, &mut dst).unwrap();
+    assert_eq!(dst[0], px::RGBA::new(204, 204, 204, 128));
+}
+
+#[test]
+fn gamma() {
+    use px::Gamma;
+    use rgb::FromSlice;
+    // Averaging black and white in linear light is brighter than in sRGB values
+    let src = [0u8, 255, 0, 255];
+    let mut dst = [0u8; 2];
+    new(4, 1, 2, 1, Gamma::new(Pixel::Gray8, 255.), Type::Area).unwrap().resize(src.as_gray(), dst.as_gray_mut()).unwrap();
+    assert_eq!(dst, [188, 188]);
+
+    // Flags skip the conversions
+    let src = [0.5f32; 4];
+    let mut dst = [0f32; 2];
+    new(4, 1, 2, 1, Gamma::new(Pixel::GrayF32, 1.).linear_input().linear_output(), Type::Area).unwrap().resize(src.as_gray(), dst.as_gray_mut()).unwrap();
+    assert_eq!(dst, [0.5, 0.5]);
+    new(4, 1, 2, 1, Gamma::new(Pixel::GrayF32, 1.).linear_input(), Type::Area).unwrap().resize(src.as_gray(), dst.as_gray_mut()).unwrap();
+    assert!((dst[0] - 0.735_357).abs() < 1e-4);
+    new(4, 1, 2, 1, Gamma::new(Pixel::GrayF32, 1.).linear_output(), Type::Area).unwrap().resize(src.as_gray(), dst.as_gray_mut()).unwrap();
+    assert!((dst[0] - 0.214_041).abs() < 1e-4);
+
+    // Identity round-trips
+    let src: Vec<u8> = (0..=255).collect();
+    let mut dst = std::vec![0u8; 256];
+    new(256, 1, 256, 1, Gamma::new(Pixel::Gray8, 255.), Type::Lanczos3).unwrap().resize(src.as_gray(), dst.as_gray_mut()).unwrap();
+    assert_eq!(src, dst);
+
+    // Alpha stays linear, unless it's included
+    let src = [px::RGBA::new(0u8, 0, 0, 0), px::RGBA::new(255, 255, 255, 255)];
+    let mut dst = [px::RGBA::new(0u8, 0, 0, 0)];
+    new(2, 1, 1, 1, Gamma::new(Pixel::RGBA8, 255.), Type::Area).unwrap().resize(&src, &mut dst).unwrap();
+    assert_eq!((dst[0].r, dst[0].a), (188, 128));
+    new(2, 1, 1, 1, Gamma::new(Pixel::RGBA8, 255.).include_alpha(), Type::Area).unwrap().resize(&src, &mut dst).unwrap();
+    assert_eq!(dst[0].a, 188);
+}
+
+#[test]
+fn sigmoidal() {
+    use px::{Gamma, Sigmoidal};
+    use rgb::FromSlice;
+    // Identity round-trips
+    let src: Vec<u8> = (0..=255).collect();
+    let mut dst = std::vec![0u8; 256];
+    new(256, 1, 256, 1, Sigmoidal::new(Gamma::new(Pixel::Gray8, 255.), 255., 6.5), Type::Lanczos3).unwrap().resize(src.as_gray(), dst.as_gray_mut()).unwrap();
+    assert_eq!(src, dst);
+
+    // Less overshoot around a high-contrast edge
+    let src: Vec<f32> = (0..16).map(|x| if x < 8 { 0.05 } else { 0.95 }).collect();
+    let overshoot = |dst: &[f32]| dst.iter().map(|&v| (0.05 - v).max(v - 0.95)).fold(0f32, f32::max);
+    let mut plain = [0f32; 64];
+    let mut sigmoid = [0f32; 64];
+    new(16, 1, 64, 1, Pixel::GrayF32, Type::Lanczos3).unwrap().resize(src.as_gray(), plain.as_gray_mut()).unwrap();
+    new(16, 1, 64, 1, Sigmoidal::new(Pixel::GrayF32, 1., 6.5), Type::Lanczos3).unwrap().resize(src.as_gray(), sigmoid.as_gray_mut()).unwrap();
+    assert!(overshoot(&sigmoid) < overshoot(&plain) / 2.);
+}
+
+#[test]
+fn output_modes() {
+    use px::{Output, OutputMode};
+    use rgb::{FromSlice, RGBA8};
+    let src = [RGBA8::new(255, 0, 0, 128); 4];
+    let blend = |format_premultiplied: bool, mode: OutputMode| {
+        let mut dst = [RGBA8::new(0, 0, 255, 255); 4];
+        if format_premultiplied {
+            new(2, 2, 2, 2, Output::new(Pixel::RGBA8P, mode), Type::Triangle).unwrap().resize(&src, &mut dst).unwrap();
+        } else {
+            new(2, 2, 2, 2, Output::new(Pixel::RGBA8, mode), Type::Triangle).unwrap().resize(&src, &mut dst).unwrap();
+        }
+        dst[0]
+    };
+    assert_eq!(blend(true, OutputMode::Replace), RGBA8::new(255, 0, 0, 128));
+    assert_eq!(blend(true, OutputMode::Blend), RGBA8::new(128, 0, 127, 255));
+    assert_eq!(blend(false, OutputMode::Blend), RGBA8::new(128, 0, 127, 255));
+    assert_eq!(blend(false, OutputMode::Add), RGBA8::new(255, 0, 255, 255));
+
+    // Also through the two-step downscaling, and rotated
+    let src = std::vec![10u16; 64 * 64];
+    let mut dst = std::vec![1000u16; 4 * 2];
+    let options = Options::default().multi_step(true).rotation(Rotation::Cw90);
+    Resizer::with_options(64, 64, 2, 4, Output::new(Pixel::Gray16, OutputMode::Add), Type::Lanczos3, &options).unwrap()
+        .resize(src.as_gray(), dst.as_gray_mut()).unwrap();
+    assert!(dst.iter().all(|&v| v == 1010), "{:?}", dst);
+
+    // Adapters around it still blend
+    let src = [RGBA8::new(255, 0, 0, 128); 4];
+    let mut dst = [RGBA8::new(0, 0, 255, 255); 4];
+    new(2, 2, 2, 2, px::Gamma::new(Output::new(Pixel::RGBA8, OutputMode::Blend), 255.), Type::Triangle).unwrap().resize(&src, &mut dst).unwrap();
+    assert_eq!(dst[0], RGBA8::new(128, 0, 127, 255));
+}
+
+#[test]
+fn supersample() {
+    use rgb::FromSlice;
+    // The filtering step is an identity here, so only the box average is left
+    let src = [0u8, 100, 200, 100, 0, 100, 200, 100];
+    let mut dst = [0u8; 2];
+    SupersampleResizer::new(8, 1, 2, 1, Pixel::Gray8, Type::Lanczos3, 4).unwrap().resize(src[..].as_gray(), dst.as_gray_mut()).unwrap();
+    assert_eq!(dst, [100, 100]);
+
+    // A one-pixel line on a flat background is smoothed
+    let mut src = std::vec![50u8; 30 * 30];
+    src.iter_mut().skip(14).step_by(30).for_each(|px| *px = 250);
+    let mut dst = std::vec![0u8; 7 * 5];
+    let mut resizer = SupersampleResizer::with_options(30, 30, 7, 5, Pixel::Gray8, Type::Triangle, 3, &Options::default().rotation(Rotation::Cw90)).unwrap();
+    resizer.resize(src.as_gray(), dst.as_gray_mut()).unwrap();
+    assert!(dst.iter().all(|&v| v >= 50), "{:?}", dst);
+    // Rotated, the vertical line becomes horizontal
+    assert!(dst.chunks(7).all(|row| row.iter().all(|&v| v == row[0])));
+    assert!(dst[2 * 7] > 60 && dst[0] == 50);
+
+    assert!(SupersampleResizer::new(8, 1, 2, 1, Pixel::Gray8, Type::Lanczos3, 0).is_err());
+    assert!(resizer.resize(&src.as_gray()[1..], dst.as_gray_mut()).is_err());
+}
+
+#[test]
+fn edge_directed() {
+    use rgb::FromSlice;
+    // Diagonal line, which interpolation across it would make dotted
+    let mut src = [0u8; 8 * 8];
+    (0..8).for_each(|i| src[i * 8 + i] = 200);
+    let mut dst = [0u8; 16 * 16];
+    let mut resizer = EdgeDirectedResizer::new(8, 8, 16, 16, Pixel::Gray8, Type::Catrom).unwrap();
+    assert!(resizer.is_edge_directed());
+    resizer.resize(src[..].as_gray(), dst.as_gray_mut()).unwrap();
+    for y in 0..8 {
+        for x in 0..8 {
+            assert_eq!(dst[2 * y * 16 + 2 * x], src[y * 8 + x]);
+        }
+    }
+    // Centers between pixels of the line continue it
+    for i in 1..6 {
+        assert!(dst[(2 * i + 1) * 16 + 2 * i + 1] > 180, "{}", dst[(2 * i + 1) * 16 + 2 * i + 1]);
+    }
+
+    let src = [77u8; 5 * 3];
+    let mut dst = [0u8; 10 * 6];
+    EdgeDirectedResizer::new(5, 3, 10, 6, Pixel::Gray8, Type::Catrom).unwrap().resize(src[..].as_gray(), dst.as_gray_mut()).unwrap();
+    assert!(dst.iter().all(|&v| v == 77));
+
+    // Other sizes use the regular filter
+    let mut resizer = EdgeDirectedResizer::new(5, 3, 7, 6, Pixel::Gray8, Type::Catrom).unwrap();
+    assert!(!resizer.is_edge_directed());
+    let mut dst = [0u8; 7 * 6];
+    resizer.resize(src[..].as_gray(), dst.as_gray_mut()).unwrap();
+    assert!(dst.iter().all(|&v| v == 77));
+    assert!(resizer.resize(src[..].as_gray(), &mut dst.as_gray_mut()[1..]).is_err());
+}
+
+#[test]
+fn resample_single_line() {
+    use rgb::FromSlice;
+    let src: Vec<f32> = (0..100).map(|x| x as f32).collect();
+    let mut dst = [0f32; 40];
+    resample_line(src.as_gray(), dst.as_gray_mut(), Pixel::GrayF32, Type::Triangle).unwrap();
+    let mut expected = [0f32; 40];
+    new(100, 1, 40, 1, Pixel::GrayF32, Type::Triangle).unwrap().resize(src.as_gray(), expected.as_gray_mut()).unwrap();
+    assert_eq!(dst, expected);
+    assert!((dst[20] - 50.75).abs() < 0.05);
+
+    let mut lut = [0u16; 7];
+    resample_line([0u16, 65535].as_gray(), lut.as_gray_mut(), Pixel::Gray16, Type::Triangle).unwrap();
+    assert_eq!(lut[0], 0);
+    assert_eq!(lut[6], 65535);
+    assert!(resample_line([0u16; 0].as_gray(), lut.as_gray_mut(), Pixel::Gray16, Type::Triangle).is_err());
+}
+
+#[test]
+fn volume() {
+    use rgb::FromSlice;
+    // Same depth, so slices are resized like 2D images
+    let src: Vec<u16> = (0..12 * 10 * 3).map(|i| (i * 977 % 65536) as u16).collect();
+    let mut dst = std::vec![0u16; 5 * 4 * 3];
+    VolumeResizer::new((12, 10, 3), (5, 4, 3), Pixel::Gray16, Type::Lanczos3).unwrap().resize(src.as_gray(), dst.as_gray_mut()).unwrap();
+    let mut r = new(12, 10, 5, 4, Pixel::Gray16, Type::Lanczos3).unwrap();
+    for (src, dst) in src.chunks(120).zip(dst.chunks(20)) {
+        let mut expected = [0u16; 20];
+        r.resize(src.as_gray(), expected.as_gray_mut()).unwrap();
+        assert_eq!(dst, expected);
+    }
+
+    // Gradient along the depth, into a padded buffer
+    let src: Vec<f32> = (0..4 * 4 * 20).map(|i| (i / 16) as f32).collect();
+    let mut depth = [0f32; 7];
+    resample_line(src.iter().step_by(16).copied().collect::<Vec<_>>().as_gray(), depth.as_gray_mut(), Pixel::GrayF32, Type::Triangle).unwrap();
+    let strides = VolumeStrides { row: 3, slice: 10 };
+    let mut dst = std::vec![-1f32; 10 * 7];
+    VolumeResizer::new((4, 4, 20), (2, 3, 7), Pixel::GrayF32, Type::Triangle).unwrap()
+        .resize_stride(src.as_gray(), VolumeStrides { row: 4, slice: 16 }, dst.as_gray_mut(), strides).unwrap();
+    for (z, slice) in dst.chunks(10).enumerate() {
+        for y in 0..3 {
+            assert!(slice[y * 3..][..2].iter().all(|&v| (v - depth[z]).abs() < 1e-4));
+            assert_eq!(slice[y * 3 + 2], -1.);
+        }
+    }
+    let mut short = std::vec![0f32; 10 * 6];
+    assert!(VolumeResizer::new((4, 4, 20), (2, 3, 7), Pixel::GrayF32, Type::Triangle).unwrap()
+        .resize_stride(src.as_gray(), VolumeStrides { row: 4, slice: 16 }, short.as_gray_mut(), strides).is_err());
+}
+
+#[test]
+fn double_precision() {
+    use rgb::FromSlice;
+    // Large values with small differences, that f32 can't add up exactly
+    let src: Vec<f64> = (0..200).map(|x| 1e6 + x as f64 * 0.001).collect();
+    let mut single = [0f64; 50];
+    let mut double = [0f64; 50];
+    new(200, 1, 50, 1, Pixel::GrayF64, Type::Lanczos3).unwrap().resize(src.as_gray(), single.as_gray_mut()).unwrap();
+    let mut r = new(200, 1, 50, 1, Pixel::GrayF64Precise, Type::Lanczos3).unwrap();
+    r.resize(src.as_gray(), double.as_gray_mut()).unwrap();
+    let expected: Vec<f64> = r.scale.coeffs_w.iter().map(|line| {
+        line.coeffs.iter().zip(&src[line.start..]).map(|(&c, &v)| c as f64 * v).sum()
+    }).collect();
+    let err = |dst: &[f64]| dst.iter().zip(&expected).map(|(a, b)| (a - b).abs()).fold(0., f64::max);
+    assert!(err(&double) < 1e-6, "{}", err(&double));
+    assert!(err(&single) > 1e-3);
+
+    let src = [px::RGB::new(65535u16, 1, 32768); 16];
+    let mut dst = [px::RGB::new(0u16, 0, 0); 9];
+    new(4, 4, 3, 3, Pixel::RGB16Precise, Type::Lanczos3).unwrap().resize(&src, &mut dst).unwrap();
+    assert!(dst.iter().all(|&px| px == px::RGB::new(65535, 1, 32768)));
+    let mut dst8 = [px::RGBA::new(0u8, 0, 0, 0); 1];
+    new(1, 1, 1, 1, formats::PreciseRgba::<f32, u8>::instance(), Type::Point).unwrap().resize(&[px::RGBA::new(1f32, 0.5, 0., 2.)], &mut dst8).unwrap();
+    assert_eq!(dst8[0], px::RGBA::new(255, 128, 0, 255));
+}
+
+#[test]
+fn u32_subpixels() {
+    use rgb::FromSlice;
+    let src = [u32::MAX, u32::MAX - 1, 3_000_000_001, 1, 0, 7];
+    let mut dst = [0u32; 6];
+    new(6, 1, 6, 1, Pixel::Gray32, Type::Lanczos3).unwrap().resize(src.as_gray(), dst.as_gray_mut()).unwrap();
+    assert_eq!(src, dst);
+    let mut dst = [0u32; 3];
+    new(6, 1, 3, 1, Pixel::Gray32, Type::Area).unwrap().resize(src.as_gray(), dst.as_gray_mut()).unwrap();
+    assert_eq!(dst, [u32::MAX, 1_500_000_001, 4]);
+
+    // Overshoot is clamped. Weights are single precision, so they don't add up to exactly 1.
+    let src = [px::RGB::new(0u32, u32::MAX, 5); 4].iter().chain(&[px::RGB::new(u32::MAX, 0, 5); 4]).copied().collect::<Vec<_>>();
+    let mut dst = [px::RGB::new(0u32, 0, 0); 13];
+    new(8, 1, 13, 1, Pixel::RGB32, Type::Lanczos3).unwrap().resize(&src, &mut dst).unwrap();
+    assert!(dst[0].r == 0 && dst[0].g > u32::MAX - 1000 && dst[0].b == 5);
+    assert!(dst[12].r > u32::MAX - 1000 && dst[12].g == 0 && dst[12].b == 5);
+    let mut dst8 = [px::RGB::new(0u8, 0, 0); 8];
+    new(8, 1, 8, 1, formats::PreciseRgb::<u32, u8>::instance(), Type::Point).unwrap().resize(&src, &mut dst8).unwrap();
+    assert_eq!(dst8[0], px::RGB::new(0, 255, 0));
+}
+
+#[test]
+fn signed_16bit() {
+    use rgb::FromSlice;
+    let src = [-1024i16, -1024, -1024, 3071, 3071, 3071];
+    let mut dst = [0i16; 6];
+    new(6, 1, 6, 1, Pixel::GrayI16, Type::Lanczos3).unwrap().resize(src.as_gray(), dst.as_gray_mut()).unwrap();
+    assert_eq!(src, dst);
+    let mut dst = [0i16; 3];
+    new(6, 1, 3, 1, Pixel::GrayI16Precise, Type::Area).unwrap().resize(src.as_gray(), dst.as_gray_mut()).unwrap();
+    assert_eq!(dst, [-1024, 1024, 3071]);
+
+    // Ringing is clamped to the signed range
+    let src = [i16::MIN, i16::MIN, i16::MIN, i16::MAX, i16::MAX, i16::MAX];
+    let mut dst = [0i16; 17];
+    new(6, 1, 17, 1, Pixel::GrayI16, Type::Lanczos3).unwrap().resize(src.as_gray(), dst.as_gray_mut()).unwrap();
+    assert_eq!((dst[0], dst[16]), (i16::MIN, i16::MAX));
+}
+
+#[test]
+fn palette() {
+    use px::{Palette, RGB};
+    let palette: Vec<RGB<u8>> = (0..=255).map(|i| RGB::new(i, 255 - i, 7)).collect();
+    let indices: Vec<u8> = (0..16 * 8).map(|i| (i * 37 % 256) as u8).collect();
+    let expanded: Vec<RGB<u8>> = indices.iter().map(|&i| palette[i as usize]).collect();
+    let mut expected = [RGB::new(0u8, 0, 0); 6 * 3];
+    new(16, 8, 6, 3, Pixel::RGB8, Type::Lanczos3).unwrap().resize(&expanded, &mut expected).unwrap();
+    let mut dst = [RGB::new(0u8, 0, 0); 6 * 3];
+    new(16, 8, 6, 3, Palette::new(Pixel::RGB8, &palette), Type::Lanczos3).unwrap().resize(&indices, &mut dst).unwrap();
+    assert_eq!(dst, expected);
+
+    // Missing entries are black
+    let mut dst = [RGB::new(1u8, 1, 1); 2];
+    new(2, 1, 2, 1, Palette::new(Pixel::RGB8, &palette[..1]), Type::Point).unwrap().resize(&[0, 200], &mut dst).unwrap();
+    assert_eq!(dst, [RGB::new(0, 255, 7), RGB::new(0, 0, 0)]);
+}
+
+#[test]
+fn pillow_opencv_compat() {
+    use rgb::FromSlice;
+    let src = [0u8, 100, 200, 255];
+    let mut dst = [0u8; 2];
+    CompatResizer::pillow(4, 1, 2, 1, Pixel::Gray8, PillowFilter::Bilinear).unwrap().resize(src[..].as_gray(), dst[..].as_gray_mut()).unwrap();
+    assert_eq!([71, 209], dst);
+
+    let src = [0u8, 100];
+    let mut dst = [0u8; 4];
+    CompatResizer::opencv(2, 1, 4, 1, Pixel::Gray8, OpenCvInterpolation::Linear).unwrap().resize(src[..].as_gray(), dst[..].as_gray_mut()).unwrap();
+    assert_eq!([0, 25, 75, 100], dst);
+    // Halving averages pairs, rounding half up
+    let src = [1u8, 2, 3, 4];
+    let mut dst = [0u8; 2];
+    CompatResizer::opencv(4, 1, 2, 1, Pixel::Gray8, OpenCvInterpolation::Linear).unwrap().resize(src[..].as_gray(), dst[..].as_gray_mut()).unwrap();
+    assert_eq!([2, 4], dst);
+
+    // Flat images stay flat, including at the edges
+    let src = [77u8; 9 * 7];
+    let mut dst = [0u8; 20 * 3];
+    CompatResizer::pillow(9, 7, 20, 3, Pixel::Gray8, PillowFilter::Lanczos).unwrap().resize(src[..].as_gray(), dst[..].as_gray_mut()).unwrap();
+    assert!(dst.iter().all(|&v| v == 77));
+    CompatResizer::opencv(9, 7, 20, 3, Pixel::Gray8, OpenCvInterpolation::Cubic).unwrap().resize(src[..].as_gray(), dst[..].as_gray_mut()).unwrap();
+    assert!(dst.iter().all(|&v| v == 77));
+
+    // Both passes, with edge taps on both axes
+    let src: Vec<u8> = (0..4).flat_map(|y| (0..5).map(move |x| ((x * 53 + y * 97 + x * y * 31) % 256) as u8)).collect();
+    let mut dst = [0u8; 3 * 7];
+    for (filter, expected) in [
+        (PillowFilter::Bilinear, [23, 106, 189, 62, 92, 172, 125, 69, 144, 134, 89, 117, 131, 112, 97, 111, 122, 132, 98, 128, 154]),
+        (PillowFilter::Bicubic, [10, 109, 197, 54, 91, 178, 130, 64, 143, 145, 80, 109, 134, 108, 94, 107, 127, 134, 92, 136, 159]),
+        (PillowFilter::Lanczos, [3, 112, 200, 52, 91, 181, 130, 64, 142, 163, 67, 100, 134, 102, 99, 104, 132, 133, 85, 146, 160]),
+    ] {
+        CompatResizer::pillow(5, 4, 3, 7, Pixel::Gray8, filter).unwrap().resize(src.as_gray(), dst[..].as_gray_mut()).unwrap();
+        assert_eq!(expected, dst, "{:?}", filter);
+    }
+    for (interpolation, expected) in [
+        (OpenCvInterpolation::Linear, [18, 106, 194, 56, 71, 178, 117, 16, 152, 136, 89, 126, 142, 161, 106, 106, 105, 129, 83, 71, 144]),
+        (OpenCvInterpolation::Cubic, [2, 116, 203, 54, 63, 187, 126, 7, 160, 155, 89, 118, 140, 170, 98, 107, 114, 129, 82, 61, 155]),
+    ] {
+        CompatResizer::opencv(5, 4, 3, 7, Pixel::Gray8, interpolation).unwrap().resize(src.as_gray(), dst[..].as_gray_mut()).unwrap();
+        assert_eq!(expected, dst, "{:?}", interpolation);
+    }
+    let mut dst = [0u8; 8 * 3];
+    for (interpolation, expected) in [
+        (OpenCvInterpolation::Linear, [16, 42, 75, 85, 108, 144, 181, 206, 146, 133, 115, 97, 79, 62, 116, 160, 61, 104, 155, 110, 118, 179, 145, 113]),
+        (OpenCvInterpolation::Cubic, [6, 37, 77, 81, 106, 145, 188, 213, 174, 149, 114, 100, 64, 33, 102, 171, 50, 109, 161, 103, 113, 187, 153, 104]),
+    ] {
+        CompatResizer::opencv(5, 4, 8, 3, Pixel::Gray8, interpolation).unwrap().resize(src.as_gray(), dst[..].as_gray_mut()).unwrap();
+        assert_eq!(expected, dst, "{:?}", interpolation);
+    }
+}
+
+#[test]
+#[cfg(feature = "deterministic")]
+fn deterministic_output() {
+    use rgb::FromSlice;
+    let src: Vec<f32> = (0..37 * 23).map(|i| (i * 7919 % 256) as f32 / 255.).collect();
+    let mut dst = std::vec![0f32; 16 * 51];
+    let mut hash = 0xcbf2_9ce4_8422_2325u64;
+    for filter in [Type::Lanczos3, Type::Gaussian(0.7), Type::WindowedSinc(Window::Blackman, 4.), Type::Mitchell] {
+        new(37, 23, 16, 51, Pixel::GrayF32, filter).unwrap().resize(src.as_gray(), dst.as_gray_mut()).unwrap();
+        for v in &dst {
+            hash = (hash ^ u64::from(v.to_bits())).wrapping_mul(0x100_0000_01b3);
+        }
+    }
+    // The same on every platform
+    assert_eq!(16_818_285_628_159_969_961, hash);
+}
+
+#[test]
+fn untrusted_inputs() {
+    use rgb::FromSlice;
+    assert!(new(usize::MAX, 2, 4, 4, Pixel::Gray8, Type::Triangle).is_err());
+    assert!(new(4, 4, usize::MAX / 2, 3, Pixel::Gray8, Type::Triangle).is_err());
+    assert!(BoxDownscaler::new(usize::MAX - 1, 2, 1, 1, Pixel::Gray8).is_err());
+
+    let resizer = new(4, 4, 2, 2, Pixel::Gray8, Type::Triangle).unwrap();
+    assert!(resizer.validate(16, 4, 4).is_ok());
+    assert!(resizer.validate(15, 4, 4).is_err());
+    assert!(resizer.validate(16, 0, 4).is_err());
+    assert!(resizer.validate(usize::MAX, usize::MAX, 4).is_err());
+    assert!(resizer.validate(16, 4, usize::MAX).is_err());
+
+    // Strided destinations may be longer than the image, also when rows aren't padded, unlike contiguous ones
+    let mut resizer = new(4, 4, 2, 2, Pixel::Gray8, Type::Triangle).unwrap();
+    assert!(resizer.resize_typed([0u8; 16].as_gray(), 4, [0u8; 6].as_gray_mut(), 2).is_ok());
+    assert!(resizer.resize_typed([0u8; 16].as_gray(), 4, [0u8; 3].as_gray_mut(), 2).is_err());
+    assert!(resizer.resize([0u8; 16].as_gray(), [0u8; 6].as_gray_mut()).is_err());
+    assert!(resizer.resize_into([0u8; 16].as_gray(), [0u8; 16].as_gray_mut(), 4, 2, usize::MAX / 4).is_err());
+
+    let src = [0u8; 16];
+    let mut dst = [0u8; 4];
+    assert!(FixedPointResizer::new(4, 4, 2, 2, Pixel::Gray8, Type::Triangle).unwrap().resize_stride(src[..].as_gray(), usize::MAX, dst[..].as_gray_mut()).is_err());
+    assert!(NearestResizer::new(4, 4, 2, 2).unwrap().resize_stride(&src, usize::MAX, &mut dst).is_err());
+    assert!(BoxDownscaler::new(4, 4, 2, 2, Pixel::Gray8).unwrap().resize_stride(src[..].as_gray(), usize::MAX, dst[..].as_gray_mut()).is_err());
+
+    // Fields skip every other row of the frame
+    let (y, uv) = ([0u8; 64], [[0u8; 2]; 16]);
+    let (mut y2, mut uv2) = ([0u8; 16], [[0u8; 2]; 4]);
+    let mut scaler = VideoScaler::new(8, 8, 4, 4, ChromaSiting::Left, Type::Triangle).unwrap();
+    let mut scale = |y_stride| scaler.scale(
+        VideoFrame::Nv12(Plane::new(y.as_gray(), y_stride), Plane::new(&uv, 4)),
+        VideoFrameMut::Nv12(PlaneMut::new(y2.as_gray_mut(), 4), PlaneMut::new(&mut uv2, 2)),
+        PictureStructure::Interlaced);
+    assert!(scale(8).is_ok());
+    assert!(scale(usize::MAX / 2 + 1).is_err());
+}
+
+#[test]
+fn resize_to_vec() {
+    use rgb::FromSlice;
+    let src: Vec<u8> = (0..8 * 6 * 3).map(|i| i as u8).collect();
+    let mut resizer = new(8, 6, 3, 5, Pixel::RGB8, Type::Mitchell).unwrap();
+    let mut dst = std::vec![0u8; 3 * 5 * 3];
+    resizer.resize(src.as_rgb(), dst.as_rgb_mut()).unwrap();
+    assert_eq!(dst.as_rgb(), resizer.resize_to_vec(src.as_rgb()).unwrap());
+    assert!(resizer.resize_to_vec(&src.as_rgb()[1..]).is_err());
+
+    let rotated = Resizer::build(8, 6, 3, 5).rotation(Rotation::Cw90).construct(Pixel::RGB8).unwrap().resize_to_vec(src.as_rgb()).unwrap();
+    assert_eq!(rotated.len(), 15);
+}
+
+#[test]
+fn resize_steps() {
+    use rgb::FromSlice;
+    let src: Vec<u8> = (0..64 * 48).map(|i| (i * 7 % 251) as u8).collect();
+    for options in [Options::default(), Options::default().multi_step(true), Options::default().rotation(Rotation::Cw90)] {
+        let mut resizer = Resizer::with_options(64, 48, 12, 9, Pixel::Gray8, Type::Lanczos3, &options).unwrap();
+        let mut expected = std::vec![0u8; 12 * 9];
+        resizer.resize(src.as_gray(), expected.as_gray_mut()).unwrap();
+        let mut dst = std::vec![0u8; 12 * 9];
+        let steps = resizer.resize_steps(src.as_gray(), dst.as_gray_mut(), 4).unwrap();
+        let total = steps.total();
+        let done: Vec<usize> = steps.collect();
+        assert_eq!(done.last(), Some(&total));
+        assert!(done.windows(2).all(|w| w[1] - w[0] <= 4));
+        assert_eq!(expected, dst);
+    }
+
+    // Scales that only copy pixels
+    for (w1, h1, w2, h2, filter, options) in [
+        (8, 8, 16, 16, Type::Point, Options::default()),
+        (8, 8, 8, 8, Type::Lanczos3, Options::default()),
+        (8, 6, 6, 8, Type::Lanczos3, Options::default().rotation(Rotation::Cw90)),
+    ] {
+        let mut resizer = Resizer::with_options(w1, h1, w2, h2, Pixel::Gray8, filter, &options).unwrap();
+        let mut expected = std::vec![0u8; w2 * h2];
+        resizer.resize(src[..w1 * h1].as_gray(), expected.as_gray_mut()).unwrap();
+        let mut dst = std::vec![0u8; w2 * h2];
+        resizer.resize_steps(src[..w1 * h1].as_gray(), dst.as_gray_mut(), 3).unwrap().for_each(drop);
+        assert_eq!(expected, dst);
+    }
+
+    let mut resizer = new(64, 48, 12, 9, Pixel::Gray8, Type::Lanczos3).unwrap();
+    assert!(resizer.resize_steps(&src.as_gray()[1..], std::vec![0u8; 12 * 9].as_gray_mut(), 4).is_err());
+}
+
+#[test]
+fn resize_parallel() {
+    use rgb::FromSlice;
+    /// Runs chunks backwards, as if the last one finished first
+    struct Reversed;
+    impl Parallelism for Reversed {
+        fn concurrency(&self) -> usize {
+            3
+        }
+        fn for_each_chunk<T: Send>(&self, chunks: Vec<T>, work: &(dyn Fn(T) + Sync)) {
+            chunks.into_iter().rev().for_each(work);
+        }
+    }
+
+    let src: Vec<u8> = (0..64 * 48 * 3).map(|i| (i * 7 % 251) as u8).collect();
+    let mut resizer = new(64, 48, 30, 17, Pixel::RGB8, Type::Lanczos3).unwrap();
+    let mut expected = std::vec![0u8; 30 * 17 * 3];
+    resizer.resize(src.as_rgb(), expected.as_rgb_mut()).unwrap();
+    let mut dst = std::vec![0u8; 30 * 17 * 3];
+    resizer.resize_parallel(src.as_rgb(), dst.as_rgb_mut(), &Reversed).unwrap();
+    assert_eq!(expected, dst);
+    dst.fill(0);
+    resizer.resize_parallel(src.as_rgb(), dst.as_rgb_mut(), &SingleThreaded).unwrap();
+    assert_eq!(expected, dst);
+    assert!(resizer.resize_parallel(&src.as_rgb()[1..], dst.as_rgb_mut(), &SingleThreaded).is_err());
+}
+
+#[test]
+#[cfg(feature = "threads")]
+fn resize_threaded() {
+    use rgb::FromSlice;
+    let src: Vec<u8> = (0..97 * 61).map(|i| (i * 13 % 253) as u8).collect();
+    let mut expected = std::vec![0u8; 40 * 45];
+    new(97, 61, 40, 45, Pixel::Gray8, Type::Lanczos3).unwrap().resize(src.as_gray(), expected.as_gray_mut()).unwrap();
+    for threads in [0, 1, 3, 64] {
+        let resizer = Resizer::build(97, 61, 40, 45).threads(threads).construct(Pixel::Gray8).unwrap();
+        let mut dst = std::vec![0u8; 40 * 45];
+        resizer.resize_threaded(src.as_gray(), dst.as_gray_mut()).unwrap();
+        assert_eq!(expected, dst);
+    }
+}
+
+#[test]
+#[cfg(feature = "wgpu")]
+fn gpu_resizer() {
+    use core::future::Future;
+    use core::task::{Context, Poll, Waker};
+    use rgb::FromSlice;
+
+    /// wgpu's native futures are ready once polled, or soon after
+    fn block_on<F: Future>(fut: F) -> F::Output {
+        let mut fut = core::pin::pin!(fut);
+        loop {
+            if let Poll::Ready(out) = fut.as_mut().poll(&mut Context::from_waker(Waker::noop())) {
+                return out;
+            }
+        }
+    }
+
+    let instance = wgpu::Instance::default();
+    let device = block_on(instance.request_adapter(&Default::default()))
+        .and_then(|adapter| block_on(adapter.request_device(&Default::default(), None)).ok());
+    let (device, queue) = match device {
+        Some(device) => device,
+        // No adapter here, so only the fallback can be tested
+        None => return,
+    };
+
+    let src: Vec<u8> = (0..97 * 61 * 4).map(|i| (i * 13 % 253) as u8).collect();
+    let mut expected = std::vec![0u8; 40 * 45 * 4];
+    new(97, 61, 40, 45, Pixel::RGBA8P, Type::Lanczos3).unwrap().resize(src.as_rgba(), expected.as_rgba_mut()).unwrap();
+    let mut gpu = GpuResizer::new(new(97, 61, 40, 45, Pixel::RGBA8P, Type::Lanczos3).unwrap(), &device, &queue).unwrap();
+    assert!(gpu.on_gpu());
+    let mut dst = std::vec![0u8; 40 * 45 * 4];
+    gpu.resize(src.as_rgba(), dst.as_rgba_mut()).unwrap();
+    assert!(expected.iter().zip(&dst).all(|(&a, &b)| (i16::from(a) - i16::from(b)).abs() <= 1));
+    assert!(gpu.resize(src.as_rgba(), dst[4..].as_rgba_mut()).is_err());
+
+    let resizer = Resizer::build(97, 61, 10, 7).multi_step(true).construct(Pixel::RGBA8P).unwrap();
+    let mut gpu = GpuResizer::new(resizer, &device, &queue).unwrap();
+    assert!(!gpu.on_gpu());
+    let mut expected = std::vec![0u8; 10 * 7 * 4];
+    Resizer::build(97, 61, 10, 7).multi_step(true).construct(Pixel::RGBA8P).unwrap().resize(src.as_rgba(), expected.as_rgba_mut()).unwrap();
+    let mut dst = std::vec![0u8; 10 * 7 * 4];
+    gpu.resize(src.as_rgba(), dst.as_rgba_mut()).unwrap();
+    assert_eq!(expected, dst);
+}
+
+#[test]
+#[cfg(feature = "tracing")]
+fn tracing_spans() {
+    extern crate std as libstd;
+    use libstd::sync::{Arc, Mutex};
+    use rgb::FromSlice;
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata};
+
+    /// Collects names of spans that are entered
+    struct Spans(Arc<Mutex<Vec<&'static str>>>, Mutex<Vec<&'static str>>);
+    impl tracing::Subscriber for Spans {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            let mut names = self.1.lock().unwrap();
+            names.push(span.metadata().name());
+            Id::from_u64(names.len() as u64)
+        }
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+        fn event(&self, _: &Event<'_>) {}
+        fn enter(&self, span: &Id) {
+            let name = self.1.lock().unwrap()[span.into_u64() as usize - 1];
+            self.0.lock().unwrap().push(name);
+        }
+        fn exit(&self, _: &Id) {}
+    }
+
+    let entered = Arc::new(Mutex::new(Vec::new()));
+    tracing::subscriber::with_default(Spans(entered.clone(), Mutex::new(Vec::new())), || {
+        let mut resizer = new(4, 4, 2, 2, Pixel::Gray8, Type::Triangle).unwrap();
+        resizer.resize([0u8; 16].as_gray(), [0u8; 4].as_gray_mut()).unwrap();
+    });
+    let entered = entered.lock().unwrap();
+    assert_eq!(entered[..3], ["new", "coefficients", "resize"]);
+    assert_eq!(entered.iter().filter(|&&name| name == "horizontal_pass").count(), 4);
+    assert_eq!(entered.iter().filter(|&&name| name == "vertical_pass").count(), 2);
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn serde_params() {
+    use rgb::FromSlice;
+    let json = r#"{"dest_width": 2, "dest_height": 1, "filter": {"cubic": {"b": 0.5, "c": 0.25}}, "options": {"edge": "mirror", "rotation": "cw180"}}"#;
+    let params: ResizeParams = serde_json::from_str(json).unwrap();
+    assert!(matches!(params.filter, Type::Cubic { b, c } if b == 0.5 && c == 0.25));
+    assert_eq!(params.options.edge, Edge::Mirror);
+    assert_eq!(params.options.rotation, Rotation::Cw180);
+    assert!(!params.options.multi_step);
+    let mut dst = [0u8; 2];
+    params.resizer(4, 1, Pixel::Gray8).unwrap().resize([0u8, 0, 255, 255][..].as_gray(), dst.as_gray_mut()).unwrap();
+    assert!(dst[0] > 200 && dst[1] < 50, "{:?}", dst);
+
+    for (filter, expected) in [
+        (Type::Lanczos3, r#""lanczos3""#),
+        (Type::MagicKernelSharp2021, r#""magic_kernel_sharp_2021""#),
+        (Type::Gaussian(0.5), r#"{"gaussian":{"sigma":0.5}}"#),
+        (Type::WindowedSinc(Window::Hamming, 1.0), r#"{"windowed_sinc":{"window":"hamming","radius":1.0}}"#),
+    ] {
+        let json = serde_json::to_string(&filter).unwrap();
+        assert_eq!(json, expected);
+        let back: Type = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&back).unwrap(), expected);
+    }
+    assert!(serde_json::to_string(&Type::Custom(Filter::new(Box::new(|x| x), 1.0))).is_err());
+    assert!(serde_json::from_str::<Type>(r#""custom""#).is_err());
+}
+
+#[test]
+fn parse_filter_type() {
+    use std::string::ToString;
+    for text in ["lanczos3", "mitchell", "bspline", "magic_kernel_sharp_2021", "cubic:0.33,0.33", "gaussian:0.5", "windowed_sinc:hamming,1"] {
+        let filter: Type = text.parse().unwrap();
+        assert_eq!(filter.to_string(), text);
+    }
+    assert!(matches!("cubic: 0.5 , 0".parse(), Ok(Type::Cubic { b, c }) if b == 0.5 && c == 0.));
+    assert!(matches!("windowed_sinc:blackman,2.5".parse(), Ok(Type::WindowedSinc(Window::Blackman, r)) if r == 2.5));
+    for bad in ["", "Lanczos3", "lanczos3:", "cubic", "cubic:1", "cubic:1,2,3", "gaussian:nan", "gaussian:x", "windowed_sinc:sinc,3", "custom"] {
+        assert!(bad.parse::<Type>().is_err(), "{}", bad);
+    }
+    assert_eq!(Type::Custom(Filter::new(Box::new(|x| x), 1.0)).to_string(), "custom");
+}
+
+#[test]
+#[cfg(feature = "metrics")]
+fn quality_metrics() {
+    use rgb::FromSlice;
+    let a = [0u8, 0, 0, 0];
+    let b = [0u8, 0, 0, 255];
+    assert_eq!(metrics::psnr(a[..].as_gray(), a[..].as_gray()).unwrap(), f64::INFINITY);
+    assert!((metrics::psnr(a[..].as_gray(), b[..].as_gray()).unwrap() - 6.0206).abs() < 0.001);
+    assert!(metrics::psnr(a[..].as_gray(), b[..3].as_gray()).is_err());
+    assert!(metrics::ssim(a[..].as_gray(), b[..].as_gray(), 3).is_err());
+
+    // Smooth gradient, which a good filter reproduces better after a round trip through a smaller size
+    let src: Vec<u8> = (0..32 * 32).map(|i| ((i % 32) * 4 + (i / 32) * 3) as u8).collect();
+    let round_trip = |filter: Type| {
+        let mut small = std::vec![0u8; 16 * 16];
+        let mut back = std::vec![0u8; 32 * 32];
+        new(32, 32, 16, 16, Pixel::Gray8, filter).unwrap().resize(src.as_gray(), small.as_gray_mut()).unwrap();
+        new(16, 16, 32, 32, Pixel::Gray8, Type::Triangle).unwrap().resize(small.as_gray(), back.as_gray_mut()).unwrap();
+        back
+    };
+    let (good, bad) = (round_trip(Type::Lanczos3), round_trip(Type::Point));
+    assert!(metrics::psnr(src.as_gray(), good.as_gray()).unwrap() > metrics::psnr(src.as_gray(), bad.as_gray()).unwrap());
+    assert!(metrics::ssim(src.as_gray(), good.as_gray(), 32).unwrap() > metrics::ssim(src.as_gray(), bad.as_gray(), 32).unwrap());
+    assert!((metrics::ssim(src.as_gray(), src.as_gray(), 32).unwrap() - 1.).abs() < 1e-9);
+    let inverted: Vec<u8> = src.iter().map(|&v| 255 - v).collect();
+    assert!(metrics::ssim(src.as_gray(), inverted.as_gray(), 32).unwrap() < 0.);
+}
+
 #[test]
 fn resize_stride() {
     use rgb::FromSlice;
 
     let mut r = new(2, 2, 3, 4, Pixel::Gray16, Type::Triangle).unwrap();
     let mut dst = std::vec![0; 12];
-    r.resize_stride(&[
+    r.resize_stride([
         65535,65535,1,2,
         65535,65535,3,4,
     ].as_gray(), 4, dst.as_gray_mut()).unwrap();
@@ -542,7 +4412,7 @@ fn resize_float() {
 
     let mut r = new(2, 2, 3, 4, Pixel::GrayF32, Type::Triangle).unwrap();
     let mut dst = std::vec![0.; 12];
-    r.resize_stride(&[
+    r.resize_stride([
         65535.,65535.,1.,2.,
         65535.,65535.,3.,4.,
     ].as_gray(), 4, dst.as_gray_mut()).unwrap();
@@ -0,0 +1,147 @@
+//! Image quality metrics, for comparing results of different filters or resizers.
+//!
+//! Both images must have the same pixel type and size. All components are compared, including alpha,
+//! and the result is the average over them.
+//!
+//! ```rust
+//! use resize::metrics::{psnr, ssim};
+//! use rgb::RGB8;
+//! let a = vec![RGB8::new(10, 20, 30); 16 * 16];
+//! let b = vec![RGB8::new(11, 20, 30); 16 * 16];
+//! assert!(psnr(&a, &b)? > 50.);
+//! assert!(ssim(&a, &b, 16)? > 0.99);
+//! # Ok::<_, resize::Error>(())
+//! ```
+use crate::{exp, log10, Error, Result};
+use fallible_collections::FallibleVec;
+#[allow(deprecated)]
+use rgb::ComponentSlice;
+use std::vec::Vec;
+
+/// Type of pixel components that can be compared
+pub trait Sample: Copy {
+    /// Value of full intensity, e.g. 255 for `u8` and 1 for `f32`
+    const MAX: f64;
+    /// The value as a float, in the `0..=MAX` range
+    fn to_f64(self) -> f64;
+}
+
+macro_rules! impl_sample {
+    ($($ty:ty => $max:expr),*) => {$(
+        impl Sample for $ty {
+            const MAX: f64 = $max;
+            #[inline(always)]
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+        }
+    )*}
+}
+
+impl_sample!(u8 => 255., u16 => 65535., f32 => 1., f64 => 1.);
+
+/// Peak signal-to-noise ratio in decibels. Higher is better, and identical images give infinity.
+///
+/// Fails with [`Error::InvalidParameters`] if images are empty or differ in size.
+#[allow(deprecated)]
+pub fn psnr<P, T: Sample>(a: &[P], b: &[P]) -> Result<f64> where [P]: ComponentSlice<T> {
+    let (a, b) = components(a, b)?;
+    let sum = a.iter().zip(b).map(|(&a, &b)| {
+        let diff = a.to_f64() - b.to_f64();
+        diff * diff
+    }).sum::<f64>();
+    let mse = sum / a.len() as f64;
+    if mse == 0. {
+        return Ok(f64::INFINITY);
+    }
+    Ok(10. * unsafe { log10(T::MAX * T::MAX / mse) })
+}
+
+/// Structural similarity index of images `width` pixels wide, from -1 to 1. Identical images give 1.
+///
+/// Uses the usual 11×11 Gaussian window with σ = 1.5, shrunk for images smaller than that.
+///
+/// Fails with [`Error::InvalidParameters`] if images are empty or differ in size,
+/// and with [`Error::OutOfMemory`] if the temporary buffers can't be allocated.
+#[allow(deprecated)]
+pub fn ssim<P, T: Sample>(a: &[P], b: &[P], width: usize) -> Result<f64> where [P]: ComponentSlice<T> {
+    if width == 0 || !a.len().is_multiple_of(width) {
+        return Err(Error::InvalidParameters);
+    }
+    let height = a.len() / width;
+    let (a_components, b_components) = components(a, b)?;
+    let channels = a_components.len() / a.len();
+
+    let size = 11.min(width).min(height);
+    let mut weights: Vec<f64> = FallibleVec::try_with_capacity(size)?;
+    let center = (size - 1) as f64 / 2.;
+    weights.extend((0..size).map(|i| {
+        let x = (i as f64 - center) / 1.5;
+        unsafe { exp(-0.5 * x * x) }
+    }));
+    let total = weights.iter().sum::<f64>();
+    weights.iter_mut().for_each(|w| *w /= total);
+
+    let c1 = (0.01 * T::MAX) * (0.01 * T::MAX);
+    let c2 = (0.03 * T::MAX) * (0.03 * T::MAX);
+    let mut plane_a: Vec<f64> = FallibleVec::try_with_capacity(a.len())?;
+    let mut plane_b: Vec<f64> = FallibleVec::try_with_capacity(a.len())?;
+    let mut sum = 0.;
+    for channel in 0..channels {
+        plane_a.clear();
+        plane_b.clear();
+        plane_a.extend(a_components.iter().skip(channel).step_by(channels).map(|&c| c.to_f64()));
+        plane_b.extend(b_components.iter().skip(channel).step_by(channels).map(|&c| c.to_f64()));
+        sum += ssim_plane(&plane_a, &plane_b, width, &weights, c1, c2)?;
+    }
+    Ok(sum / channels as f64)
+}
+
+#[allow(deprecated)]
+fn components<'a, P, T>(a: &'a [P], b: &'a [P]) -> Result<(&'a [T], &'a [T])> where [P]: ComponentSlice<T> {
+    if a.is_empty() || a.len() != b.len() {
+        return Err(Error::InvalidParameters);
+    }
+    Ok((ComponentSlice::as_slice(a), ComponentSlice::as_slice(b)))
+}
+
+/// Mean of the SSIM map over positions where the whole window fits in the image
+fn ssim_plane(a: &[f64], b: &[f64], width: usize, weights: &[f64], c1: f64, c2: f64) -> Result<f64> {
+    let height = a.len() / width;
+    let out_width = width + 1 - weights.len();
+    let out_height = height + 1 - weights.len();
+
+    // Horizontally filtered means of a, b, a², b² and ab
+    let mut rows: Vec<[f64; 5]> = FallibleVec::try_with_capacity(out_width * height)?;
+    for (row_a, row_b) in a.chunks_exact(width).zip(b.chunks_exact(width)) {
+        rows.extend((0..out_width).map(|x| {
+            let mut moments = [0.; 5];
+            for ((&a, &b), &w) in row_a[x..].iter().zip(&row_b[x..]).zip(weights) {
+                moments[0] += w * a;
+                moments[1] += w * b;
+                moments[2] += w * a * a;
+                moments[3] += w * b * b;
+                moments[4] += w * a * b;
+            }
+            moments
+        }));
+    }
+
+    let mut sum = 0.;
+    for y in 0..out_height {
+        for x in 0..out_width {
+            let mut moments = [0.; 5];
+            for (row, &w) in rows[y * out_width + x..].iter().step_by(out_width).zip(weights) {
+                for (m, &r) in moments.iter_mut().zip(row) {
+                    *m += w * r;
+                }
+            }
+            let [mean_a, mean_b, sq_a, sq_b, prod] = moments;
+            let var_a = sq_a - mean_a * mean_a;
+            let var_b = sq_b - mean_b * mean_b;
+            let covar = prod - mean_a * mean_b;
+            sum += ((2. * mean_a * mean_b + c1) * (2. * covar + c2)) / ((mean_a * mean_a + mean_b * mean_b + c1) * (var_a + var_b + c2));
+        }
+    }
+    Ok(sum / (out_width * out_height) as f64)
+}
@@ -0,0 +1,58 @@
+use crate::px::{FromAccumulator, KeepAccumulator};
+use crate::{Error, Options, PixelFormat, Result, Scale, Type};
+use core::num::NonZeroUsize;
+use fallible_collections::FallibleVec;
+use std::vec::Vec;
+
+/// A single level of a mipmap chain, see [`generate_mips`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MipLevel<Pixel> {
+    /// Width in pixels
+    pub width: usize,
+    /// Height in pixels
+    pub height: usize,
+    /// Pixel data, without padding between rows
+    pub pixels: Vec<Pixel>,
+}
+
+/// Generate a chain of mipmaps, each half the size of the previous one (rounded down), down to 1×1.
+///
+/// The first level is half the size of `src`, which isn't included. By default each level is made from the
+/// previous one, at full precision of the accumulator. With `from_original` every level is resized
+/// directly from `src`, which is slower, but avoids compounding of the filter's blur.
+pub fn generate_mips<Format: PixelFormat>(src: &[Format::InputPixel], width: usize, height: usize, pixel_format: Format, filter_type: Type, from_original: bool) -> Result<Vec<MipLevel<Format::OutputPixel>>>
+{
+    let stride = NonZeroUsize::new(width).ok_or(Error::InvalidParameters)?;
+    let mut levels: Vec<MipLevel<Format::OutputPixel>> = Vec::new();
+    let mut tmp = Vec::new();
+    // Levels are kept as accumulators, and converted to pixels at the end
+    let (mut prev, mut next) = (Vec::new(), Vec::new());
+    let (mut w, mut h) = (width, height);
+    while w > 1 || h > 1 {
+        let (w2, h2) = ((w / 2).max(1), (h / 2).max(1));
+        next.clear();
+        FallibleVec::try_reserve(&mut next, w2 * h2)?;
+        next.resize(w2 * h2, Format::new());
+        if from_original || levels.is_empty() {
+            let scale = Scale::with_options(width, height, w2, h2, &filter_type, &filter_type, &Options::default())?;
+            scale.check_buffers(src.len(), stride, next.len())?;
+            scale.resample_both_axes(&KeepAccumulator(&pixel_format), &mut tmp, src, stride, &mut next)?;
+        } else {
+            let scale = Scale::with_options(w, h, w2, h2, &filter_type, &filter_type, &Options::default())?;
+            let prev_stride = NonZeroUsize::new(w).ok_or(Error::InvalidParameters)?;
+            scale.resample_both_axes(&KeepAccumulator(&FromAccumulator(&pixel_format)), &mut tmp, &prev, prev_stride, &mut next)?;
+        }
+        let mut pixels: Vec<Format::OutputPixel> = FallibleVec::try_with_capacity(w2 * h2)?;
+        pixels.extend(next.iter().enumerate().map(|(i, &acc)| {
+            // Levels are new images, so there's no previous pixel to write over
+            let mut px = pixel_format.into_pixel(acc);
+            pixel_format.write_pixel(&mut px, acc, i % w2, i / w2);
+            px
+        }));
+        core::mem::swap(&mut prev, &mut next);
+        FallibleVec::try_push(&mut levels, MipLevel { width: w2, height: h2, pixels })?;
+        w = w2;
+        h = h2;
+    }
+    Ok(levels)
+}
@@ -0,0 +1,76 @@
+//! Resizing of [`ndarray`](::ndarray) arrays of `f32`, enabled with the `ndarray` feature.
+//!
+//! Arrays are indexed `[row, column]`, or `[row, column, channel]`. Views with any strides are accepted.
+//! Ones with contiguous rows, like slices of columns of a bigger array, are read in place, and others are copied first.
+use crate::{formats, Error, Resizer, Result, Type};
+use ::ndarray::{Array2, Array3, ArrayView2, ArrayView3, Axis};
+use core::convert::TryFrom;
+use fallible_collections::FallibleVec;
+use rgb::FromSlice;
+use std::borrow::Cow;
+use std::vec::Vec;
+
+/// Zeroed `Vec` of the given length, that doesn't abort when out of memory
+fn zeroed(len: usize) -> Result<Vec<f32>> {
+    let mut data = Vec::new();
+    FallibleVec::try_reserve(&mut data, len)?;
+    data.resize(len, 0.);
+    Ok(data)
+}
+
+/// Rows of the plane and the distance between them, borrowed if each row is contiguous, or copied one after another otherwise
+fn plane_rows<'a>(plane: &ArrayView2<'a, f32>) -> Result<(Cow<'a, [f32]>, usize)> {
+    let (rows, cols) = plane.dim();
+    if let Some(data) = plane.to_slice() {
+        return Ok((Cow::Borrowed(data), cols));
+    }
+    if let [row_stride, 1] = *plane.strides() {
+        let len = usize::try_from(row_stride).ok().filter(|&stride| stride >= cols)
+            .and_then(|stride| stride.checked_mul(rows.checked_sub(1)?)?.checked_add(cols).map(|len| (stride, len)));
+        if let Some((stride, len)) = len {
+            // Safety: rows are `stride` elements apart in a single array, which has every element between them
+            let data = unsafe { core::slice::from_raw_parts(plane.as_ptr(), len) };
+            return Ok((Cow::Borrowed(data), stride));
+        }
+    }
+    let mut data = Vec::new();
+    FallibleVec::try_reserve(&mut data, rows.checked_mul(cols).ok_or(Error::InvalidParameters)?)?;
+    data.extend(plane.iter().copied());
+    Ok((Cow::Owned(data), cols))
+}
+
+fn resizer(src_rows: usize, src_cols: usize, dest_rows: usize, dest_cols: usize, filter: Type) -> Result<Resizer<formats::Gray<f32, f32>>> {
+    Resizer::new(src_cols, src_rows, dest_cols, dest_rows, crate::Pixel::GrayF32, filter)
+}
+
+/// Resize a 2D array to `dest_rows`×`dest_cols`.
+///
+/// ```rust
+/// let data = ndarray::Array2::<f32>::ones((48, 64));
+/// let small = resize::ndarray::resize_array2(data.view(), 12, 16, resize::Type::Lanczos3)?;
+/// assert_eq!(small.dim(), (12, 16));
+/// # Ok::<_, resize::Error>(())
+/// ```
+pub fn resize_array2(src: ArrayView2<'_, f32>, dest_rows: usize, dest_cols: usize, filter: Type) -> Result<Array2<f32>> {
+    let (rows, cols) = src.dim();
+    let mut resizer = resizer(rows, cols, dest_rows, dest_cols, filter)?;
+    let mut dst = zeroed(dest_rows * dest_cols)?;
+    let (data, stride) = plane_rows(&src)?;
+    resizer.resize_stride(data.as_gray(), stride, dst.as_gray_mut())?;
+    Array2::from_shape_vec((dest_rows, dest_cols), dst).map_err(|_| Error::InvalidParameters)
+}
+
+/// Resize every channel of a 3D array to `dest_rows`×`dest_cols`, keeping the number of channels.
+pub fn resize_array3(src: ArrayView3<'_, f32>, dest_rows: usize, dest_cols: usize, filter: Type) -> Result<Array3<f32>> {
+    let (rows, cols, channels) = src.dim();
+    let mut resizer = resizer(rows, cols, dest_rows, dest_cols, filter)?;
+    let mut dst = Array3::from_shape_vec((dest_rows, dest_cols, channels), zeroed(dest_rows.checked_mul(dest_cols).and_then(|n| n.checked_mul(channels)).ok_or(Error::InvalidParameters)?)?)
+        .map_err(|_| Error::InvalidParameters)?;
+    let mut plane = zeroed(dest_rows * dest_cols)?;
+    for channel in 0..channels {
+        let (data, stride) = plane_rows(&src.index_axis(Axis(2), channel))?;
+        resizer.resize_stride(data.as_gray(), stride, plane.as_gray_mut())?;
+        dst.index_axis_mut(Axis(2), channel).iter_mut().zip(&plane).for_each(|(d, &s)| *d = s);
+    }
+    Ok(dst)
+}
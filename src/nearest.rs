@@ -0,0 +1,73 @@
+use crate::{image_len, Error, Options, Result, Scale, Type};
+use core::num::NonZeroUsize;
+use fallible_collections::FallibleVec;
+use std::vec::Vec;
+
+/// Nearest-neighbor resampler that copies pixels without any arithmetic, for pixel art and UI assets.
+///
+/// Picks the same pixels as [`Type::Point`]. [`Resizer`](crate::Resizer) gathers them without resampling too,
+/// but it converts them to its pixel format, while this works with any pixel type, and copies them as they are.
+#[derive(Debug, Clone)]
+pub struct NearestResizer {
+    w1: NonZeroUsize,
+    h1: NonZeroUsize,
+    /// Source column of every destination column
+    x_map: Vec<usize>,
+    /// Source row of every destination row
+    y_map: Vec<usize>,
+}
+
+impl NearestResizer {
+    /// Create a new resizer instance.
+    #[inline]
+    pub fn new(source_width: usize, source_heigth: usize, dest_width: usize, dest_height: usize) -> Result<Self> {
+        Self::with_options(source_width, source_heigth, dest_width, dest_height, &Options::default())
+    }
+
+    /// Create a new resizer instance with non-default [`Options`]. Only alignment and offset apply to it.
+    pub fn with_options(source_width: usize, source_heigth: usize, dest_width: usize, dest_height: usize, options: &Options) -> Result<Self> {
+        let scale = Scale::with_options(source_width, source_heigth, dest_width, dest_height, &Type::Point, &Type::Point, options)?;
+        if scale.transpose {
+            // Rotation by 90° isn't supported by this resizer
+            return Err(Error::InvalidParameters);
+        }
+        Ok(Self {
+            w1: scale.w1,
+            h1: scale.h1,
+            x_map: index_map(&scale.coeffs_w)?,
+            y_map: index_map(&scale.coeffs_h)?,
+        })
+    }
+
+    /// Resize `src` image data into `dst`.
+    #[inline]
+    pub fn resize<Pixel: Copy>(&self, src: &[Pixel], dst: &mut [Pixel]) -> Result<()> {
+        self.resize_stride(src, self.w1.get(), dst)
+    }
+
+    /// Resize `src` image data into `dst`, skipping `stride` pixels each row.
+    pub fn resize_stride<Pixel: Copy>(&self, src: &[Pixel], src_stride: usize, dst: &mut [Pixel]) -> Result<()> {
+        let (w2, h1) = (self.x_map.len(), self.h1.get());
+        if src_stride < self.w1.get() || !matches!(image_len(self.w1.get(), h1, src_stride), Some(len) if src.len() >= len) || image_len(w2, self.y_map.len(), w2) != Some(dst.len()) {
+            return Err(Error::InvalidParameters);
+        }
+        for (dst_row, &y) in dst.chunks_exact_mut(w2).zip(&self.y_map) {
+            let src_row = &src[y * src_stride..y * src_stride + self.w1.get()];
+            for (dst_px, &x) in dst_row.iter_mut().zip(&self.x_map) {
+                *dst_px = src_row[x];
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Source pixel with the largest weight for every destination pixel
+fn index_map(lines: &[crate::CoeffsLine]) -> Result<Vec<usize>> {
+    let mut map: Vec<usize> = FallibleVec::try_with_capacity(lines.len())?;
+    map.extend(lines.iter().map(|line| {
+        let nearest = line.coeffs.iter().enumerate()
+            .fold((0, f32::NEG_INFINITY), |best, (i, &w)| if w > best.1 { (i, w) } else { best });
+        line.start + nearest.0
+    }));
+    Ok(map)
+}
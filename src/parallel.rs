@@ -0,0 +1,149 @@
+use crate::{Error, PixelFormat, Resizer, Result};
+#[cfg(feature = "threads")]
+use crate::libstd;
+use core::sync::atomic::{AtomicBool, Ordering};
+use fallible_collections::FallibleVec;
+use std::vec::Vec;
+
+/// Runs independent chunks of work, possibly at the same time, for [`Resizer::resize_parallel`].
+///
+/// This keeps the crate free of a thread pool dependency. For example, with rayon:
+///
+/// ```rust,ignore
+/// struct Rayon;
+///
+/// impl resize::Parallelism for Rayon {
+///     fn concurrency(&self) -> usize {
+///         rayon::current_num_threads()
+///     }
+///
+///     fn for_each_chunk<T: Send>(&self, chunks: Vec<T>, work: &(dyn Fn(T) + Sync)) {
+///         use rayon::prelude::*;
+///         chunks.into_par_iter().for_each(work);
+///     }
+/// }
+/// ```
+pub trait Parallelism {
+    /// How many chunks can run at the same time, used to decide how many to split the work into
+    fn concurrency(&self) -> usize;
+    /// Call `work` once with every chunk, in any order, and return when all calls are done
+    fn for_each_chunk<T: Send>(&self, chunks: Vec<T>, work: &(dyn Fn(T) + Sync));
+}
+
+/// Runs all chunks one after another on the calling thread
+#[derive(Debug, Copy, Clone, Default)]
+pub struct SingleThreaded;
+
+impl Parallelism for SingleThreaded {
+    #[inline]
+    fn concurrency(&self) -> usize {
+        1
+    }
+
+    #[inline]
+    fn for_each_chunk<T: Send>(&self, chunks: Vec<T>, work: &(dyn Fn(T) + Sync)) {
+        chunks.into_iter().for_each(work);
+    }
+}
+
+impl<Format: PixelFormat + Sync> Resizer<Format> where Format::InputPixel: Sync, Format::OutputPixel: Send {
+    /// Resize `src` image data into `dst`, split into bands of rows that are resized by `parallelism`.
+    ///
+    /// Every band resamples the source rows it needs on its own, so rows near their edges are resampled horizontally twice.
+    /// This isn't supported with [`Options::multi_step`](crate::Options::multi_step) or [`Options::rotation`](crate::Options::rotation).
+    pub fn resize_parallel<P: Parallelism>(&self, src: &[Format::InputPixel], dst: &mut [Format::OutputPixel], parallelism: &P) -> Result<()> {
+        if self.prescale.is_some() || self.scale.transpose {
+            return Err(Error::InvalidParameters);
+        }
+        let stride = self.scale.w1;
+        let (w2, h2) = (self.scale.w2(), self.scale.h2());
+        self.scale.check_buffers(src.len(), stride, dst.len())?;
+
+        // A few bands per thread even out their differences in speed
+        let bands = match parallelism.concurrency() {
+            0 | 1 => 1,
+            n => n.saturating_mul(2),
+        };
+        let band_height = h2.div_ceil(bands);
+        let mut chunks: Vec<_> = FallibleVec::try_with_capacity(h2.div_ceil(band_height))?;
+        chunks.extend(dst.chunks_mut(band_height * w2).enumerate().map(|(i, band)| (i * band_height, band)));
+
+        // Buffers are already validated, so only allocations can fail
+        let failed = AtomicBool::new(false);
+        let (scale, pix_fmt) = (&self.scale, &self.pix_fmt);
+        parallelism.for_each_chunk(chunks, &|(y, band): (usize, &mut [Format::OutputPixel])| {
+            let resized = scale.tile(0..w2, y..y + band.len() / w2).and_then(|(tile, x, y)| {
+                tile.resample_both_axes(pix_fmt, &mut Vec::new(), &src[y * stride.get() + x..], stride, band)
+            });
+            if resized.is_err() {
+                failed.store(true, Ordering::Relaxed);
+            }
+        });
+        if failed.load(Ordering::Relaxed) { Err(Error::OutOfMemory) } else { Ok(()) }
+    }
+}
+
+/// Runs chunks on scoped threads of the standard library, enabled with the `threads` feature.
+///
+/// The calling thread works on chunks too, so `threads` includes it.
+/// If the OS can't start more threads, the ones that did start do all of the work.
+#[cfg(feature = "threads")]
+#[derive(Debug, Copy, Clone)]
+pub struct ScopedThreads {
+    threads: core::num::NonZeroUsize,
+}
+
+#[cfg(feature = "threads")]
+impl ScopedThreads {
+    /// Use `threads` threads, or as many as the machine has cores if it's 0
+    #[must_use]
+    pub fn new(threads: usize) -> Self {
+        let threads = core::num::NonZeroUsize::new(threads)
+            .or_else(|| libstd::thread::available_parallelism().ok())
+            .unwrap_or(core::num::NonZeroUsize::MIN);
+        Self { threads }
+    }
+}
+
+#[cfg(feature = "threads")]
+impl Parallelism for ScopedThreads {
+    #[inline]
+    fn concurrency(&self) -> usize {
+        self.threads.get()
+    }
+
+    fn for_each_chunk<T: Send>(&self, chunks: Vec<T>, work: &(dyn Fn(T) + Sync)) {
+        // Threads take the next chunk when they finish one, so faster ones do more
+        let chunks = libstd::sync::Mutex::new(chunks.into_iter());
+        let worker = || loop {
+            let chunk = chunks.lock().unwrap_or_else(|e| e.into_inner()).next();
+            match chunk {
+                Some(chunk) => work(chunk),
+                None => break,
+            }
+        };
+        libstd::thread::scope(|scope| {
+            for _ in 1..self.threads.get() {
+                // A thread that failed to start leaves its share to the others
+                let _ = libstd::thread::Builder::new().spawn_scoped(scope, worker);
+            }
+            worker();
+        });
+    }
+}
+
+#[cfg(feature = "threads")]
+impl<Format: PixelFormat + Sync> Resizer<Format> where Format::InputPixel: Sync, Format::OutputPixel: Send {
+    /// Resize `src` image data into `dst` using [`Options::threads`](crate::Options::threads) threads, see [`ScopedThreads`].
+    ///
+    /// ```rust
+    /// # use rgb::FromSlice;
+    /// # let (src, mut dst) = (vec![0u8; 640 * 480 * 3], vec![0u8; 320 * 240 * 3]);
+    /// let resizer = resize::Resizer::build(640, 480, 320, 240).threads(4).construct(resize::Pixel::RGB8)?;
+    /// resizer.resize_threaded(src.as_rgb(), dst.as_rgb_mut())?;
+    /// # Ok::<_, resize::Error>(())
+    /// ```
+    pub fn resize_threaded(&self, src: &[Format::InputPixel], dst: &mut [Format::OutputPixel]) -> Result<()> {
+        self.resize_parallel(src, dst, &ScopedThreads::new(self.options.threads))
+    }
+}
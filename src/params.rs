@@ -0,0 +1,129 @@
+use crate::{Options, PixelFormat, Resizer, Result, Type};
+
+/// Destination size, filter and options of a resize, without the source size and pixel format.
+///
+/// With the `serde` feature it can be stored in configuration files, e.g. as presets of thumbnail sizes.
+/// Filters are written by name, and parameters of the ones that have them are named:
+///
+/// ```json
+/// {"dest_width": 320, "dest_height": 240, "filter": {"cubic": {"b": 0.33, "c": 0.33}}, "options": {"edge": "mirror"}}
+/// ```
+///
+/// Settings missing from `options` keep their defaults. [`Type::Custom`] filters can't be serialized.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ResizeParams {
+    /// Width of the resized image
+    pub dest_width: usize,
+    /// Height of the resized image
+    pub dest_height: usize,
+    /// Filter used for both axes
+    pub filter: Type,
+    /// Other settings
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub options: Options,
+}
+
+impl ResizeParams {
+    /// Resize to the given size using default [`Options`]
+    #[inline]
+    #[must_use]
+    pub fn new(dest_width: usize, dest_height: usize, filter: Type) -> Self {
+        Self { dest_width, dest_height, filter, options: Options::default() }
+    }
+
+    /// Create a resizer for images of the given size
+    #[inline]
+    pub fn resizer<Format: PixelFormat>(&self, source_width: usize, source_heigth: usize, pixel_format: Format) -> Result<Resizer<Format>> {
+        Resizer::with_filters(source_width, source_heigth, self.dest_width, self.dest_height, pixel_format, &self.filter, &self.filter, &self.options)
+    }
+}
+
+impl core::fmt::Debug for ResizeParams {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ResizeParams")
+            .field("dest_width", &self.dest_width)
+            .field("dest_height", &self.dest_height)
+            .field("filter", &self.filter.name())
+            .field("options", &self.options)
+            .finish()
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serialization {
+    use crate::{Type, Window};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    /// Serialized form of [`Type`], with named parameters
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename = "Type", rename_all = "snake_case")]
+    enum TypeRepr {
+        Point,
+        Triangle,
+        Catrom,
+        Mitchell,
+        #[serde(rename = "bspline")]
+        BSpline,
+        Hermite,
+        Cubic { b: f32, c: f32 },
+        Lanczos3,
+        Gaussian { sigma: f32 },
+        WindowedSinc { window: Window, radius: f32 },
+        Spline16,
+        Spline36,
+        Spline64,
+        #[serde(rename = "magic_kernel_sharp_2013")]
+        MagicKernelSharp2013,
+        #[serde(rename = "magic_kernel_sharp_2021")]
+        MagicKernelSharp2021,
+        Area,
+    }
+
+    impl Serialize for Type {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let repr = match *self {
+                Type::Point => TypeRepr::Point,
+                Type::Triangle => TypeRepr::Triangle,
+                Type::Catrom => TypeRepr::Catrom,
+                Type::Mitchell => TypeRepr::Mitchell,
+                Type::BSpline => TypeRepr::BSpline,
+                Type::Hermite => TypeRepr::Hermite,
+                Type::Cubic { b, c } => TypeRepr::Cubic { b, c },
+                Type::Lanczos3 => TypeRepr::Lanczos3,
+                Type::Gaussian(sigma) => TypeRepr::Gaussian { sigma },
+                Type::WindowedSinc(window, radius) => TypeRepr::WindowedSinc { window, radius },
+                Type::Spline16 => TypeRepr::Spline16,
+                Type::Spline36 => TypeRepr::Spline36,
+                Type::Spline64 => TypeRepr::Spline64,
+                Type::MagicKernelSharp2013 => TypeRepr::MagicKernelSharp2013,
+                Type::MagicKernelSharp2021 => TypeRepr::MagicKernelSharp2021,
+                Type::Area => TypeRepr::Area,
+                Type::Custom(_) => return Err(serde::ser::Error::custom("custom filters can't be serialized")),
+            };
+            repr.serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Type {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            Ok(match TypeRepr::deserialize(deserializer)? {
+                TypeRepr::Point => Type::Point,
+                TypeRepr::Triangle => Type::Triangle,
+                TypeRepr::Catrom => Type::Catrom,
+                TypeRepr::Mitchell => Type::Mitchell,
+                TypeRepr::BSpline => Type::BSpline,
+                TypeRepr::Hermite => Type::Hermite,
+                TypeRepr::Cubic { b, c } => Type::Cubic { b, c },
+                TypeRepr::Lanczos3 => Type::Lanczos3,
+                TypeRepr::Gaussian { sigma } => Type::Gaussian(sigma),
+                TypeRepr::WindowedSinc { window, radius } => Type::WindowedSinc(window, radius),
+                TypeRepr::Spline16 => Type::Spline16,
+                TypeRepr::Spline36 => Type::Spline36,
+                TypeRepr::Spline64 => Type::Spline64,
+                TypeRepr::MagicKernelSharp2013 => Type::MagicKernelSharp2013,
+                TypeRepr::MagicKernelSharp2021 => Type::MagicKernelSharp2021,
+                TypeRepr::Area => Type::Area,
+            })
+        }
+    }
+}
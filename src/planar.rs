@@ -0,0 +1,92 @@
+use crate::{Error, PixelFormat, Result, Scale, Type};
+use core::num::NonZeroUsize;
+use fallible_collections::FallibleVec;
+use std::vec::Vec;
+
+/// Source and destination dimensions of a single plane of a planar image.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PlaneDimensions {
+    /// Width of the plane in the source image
+    pub src_width: usize,
+    /// Height of the plane in the source image
+    pub src_height: usize,
+    /// Width of the plane in the destination image
+    pub dest_width: usize,
+    /// Height of the plane in the destination image
+    pub dest_height: usize,
+}
+
+/// Source pixels of a single plane.
+#[derive(Debug, Copy, Clone)]
+pub struct Plane<'a, Pixel> {
+    /// Pixel data, `stride * (height - 1) + width` pixels at least
+    pub data: &'a [Pixel],
+    /// Length of a row in pixels (>= width)
+    pub stride: usize,
+}
+
+impl<'a, Pixel> Plane<'a, Pixel> {
+    /// Describe plane's pixels with rows `stride` pixels apart
+    #[inline]
+    pub fn new(data: &'a [Pixel], stride: usize) -> Self {
+        Self { data, stride }
+    }
+}
+
+/// Resampler for images split into several planes of the same pixel format, e.g. Y, U and V planes of a video frame.
+///
+/// Planes may have different dimensions. Coefficients are shared between planes of equal dimensions,
+/// and a single temporary buffer is used for all of them.
+#[derive(Debug)]
+pub struct PlanarResizer<Format: PixelFormat> {
+    scales: Vec<Scale>,
+    pix_fmt: Format,
+    tmp: Vec<Format::Accumulator>,
+}
+
+impl<Format: PixelFormat> PlanarResizer<Format> {
+    /// Create a new resizer for planes of the given dimensions, all using the same filter.
+    pub fn new(planes: &[PlaneDimensions], pixel_format: Format, filter_type: Type) -> Result<Self> {
+        if planes.is_empty() {
+            return Err(Error::InvalidParameters);
+        }
+        let mut scales: Vec<Scale> = FallibleVec::try_with_capacity(planes.len())?;
+        for (i, dims) in planes.iter().enumerate() {
+            let scale = match planes[..i].iter().position(|prev| prev == dims) {
+                Some(same) => scales[same].clone(),
+                None => Scale::new(dims.src_width, dims.src_height, dims.dest_width, dims.dest_height, &filter_type)?,
+            };
+            scales.push(scale);
+        }
+        Ok(Self {
+            scales,
+            pix_fmt: pixel_format,
+            tmp: Vec::new(),
+        })
+    }
+
+    /// Number of planes this resizer has been configured for
+    #[inline]
+    #[must_use]
+    pub fn planes(&self) -> usize {
+        self.scales.len()
+    }
+
+    /// Resize every plane of `src` into the corresponding plane of `dst`.
+    ///
+    /// Both slices must have exactly as many planes as the resizer has been created with.
+    pub fn resize(&mut self, src: &[Plane<'_, Format::InputPixel>], dst: &mut [&mut [Format::OutputPixel]]) -> Result<()> {
+        if src.len() != self.scales.len() || dst.len() != self.scales.len() {
+            return Err(Error::InvalidParameters);
+        }
+        for ((scale, src), dst) in self.scales.iter().zip(src).zip(dst.iter_mut()) {
+            let stride = NonZeroUsize::new(src.stride).ok_or(Error::InvalidParameters)?;
+            scale.check_buffers(src.data.len(), stride, dst.len())?;
+        }
+        for ((scale, src), dst) in self.scales.iter().zip(src).zip(dst.iter_mut()) {
+            let stride = NonZeroUsize::new(src.stride).ok_or(Error::InvalidParameters)?;
+            scale.resample_both_axes(&self.pix_fmt, &mut self.tmp, src.data, stride, dst)?;
+        }
+        Ok(())
+    }
+}
@@ -1,11 +1,64 @@
 use crate::formats;
+use crate::boxed::average_as;
+use crate::{ceil, exp, floor, log, pow};
+use rgb::ComponentMap;
 pub use rgb::alt::Gray;
+pub use rgb::alt::GrayAlpha;
+pub use rgb::alt::BGR;
+pub use rgb::alt::BGRA;
 pub use rgb::RGB;
 pub use rgb::RGBA;
 
 /// Use [`Pixel`](crate::Pixel) presets to specify pixel format.
 ///
 /// The trait represents a temporary object that adds pixels together.
+///
+/// It's also a supported way of adding your own pixel formats. Resizing happens in two passes:
+///
+/// 1. Each output pixel of the first axis starts from [`new()`](PixelFormat::new),
+///    and gets input pixels added to it via [`add()`](PixelFormat::add).
+/// 2. Each final output pixel starts from `new()`, and gets the accumulators from
+///    the first pass added to it via [`add_acc()`](PixelFormat::add_acc). It's then converted
+///    with [`into_pixel()`](PixelFormat::into_pixel).
+///
+/// Weights of each pass add up to 1, but individual weights may be negative.
+///
+/// ```
+/// use resize::PixelFormat;
+///
+/// /// Pixels packed as `0x00RRGGBB`
+/// struct PackedRgb;
+///
+/// impl PixelFormat for PackedRgb {
+///     type InputPixel = u32;
+///     type OutputPixel = u32;
+///     type Accumulator = [f32; 3];
+///
+///     fn new() -> [f32; 3] { [0.; 3] }
+///
+///     fn add(&self, acc: &mut [f32; 3], inp: u32, coeff: f32) {
+///         for (i, acc) in acc.iter_mut().enumerate() {
+///             *acc += ((inp >> (16 - 8 * i)) & 0xFF) as f32 * coeff;
+///         }
+///     }
+///
+///     fn add_acc(acc: &mut [f32; 3], inp: [f32; 3], coeff: f32) {
+///         for (acc, inp) in acc.iter_mut().zip(inp) {
+///             *acc += inp * coeff;
+///         }
+///     }
+///
+///     fn into_pixel(&self, acc: [f32; 3]) -> u32 {
+///         acc.iter().fold(0, |px, &c| (px << 8) | c.round().max(0.).min(255.) as u32)
+///     }
+/// }
+///
+/// let mut r = resize::new(2, 1, 3, 1, PackedRgb, resize::Type::Triangle)?;
+/// let mut dst = [0; 3];
+/// r.resize(&[0x00FF00, 0x0000FF], &mut dst)?;
+/// assert_eq!(dst, [0x00FF00, 0x008080, 0x0000FF]);
+/// # Ok::<_, resize::Error>(())
+/// ```
 pub trait PixelFormat {
     /// Pixel type in the source image
     type InputPixel: Copy;
@@ -14,17 +67,42 @@ pub trait PixelFormat {
     /// Temporary struct for the pixel in floating-point
     type Accumulator: Copy;
 
-    /// Create new floating-point pixel
+    /// Create new floating-point pixel with all components set to zero
     fn new() -> Self::Accumulator;
     /// Add new pixel with a given weight (first axis)
     fn add(&self, acc: &mut Self::Accumulator, inp: Self::InputPixel, coeff: f32);
     /// Add bunch of accumulated pixels with a weight (second axis)
     fn add_acc(acc: &mut Self::Accumulator, inp: Self::Accumulator, coeff: f32);
-    /// Finalize, convert to output pixel format
+    /// Finalize, convert to output pixel format. This is responsible for rounding and clamping.
+    #[allow(clippy::wrong_self_convention)]
     fn into_pixel(&self, acc: Self::Accumulator) -> Self::OutputPixel;
+    /// Store the finished pixel at column `x` and row `y` of the destination image. Overwrites it with [`into_pixel`](PixelFormat::into_pixel),
+    /// unless the format combines them, like [`Output`], or depends on the position, like [`Dither`].
+    #[inline(always)]
+    fn write_pixel(&self, out: &mut Self::OutputPixel, acc: Self::Accumulator, x: usize, y: usize) {
+        let _ = (x, y);
+        *out = self.into_pixel(acc);
+    }
+
+    /// Average of a block of `factors[0]`×`factors[1]` pixels at the start of `src`, with rows `stride` apart,
+    /// computed with integer arithmetic like [`BoxDownscaler`](crate::BoxDownscaler) does. [`Type::Area`](crate::Type::Area)
+    /// uses it to downscale by integer factors. Returns `None` if the format has no [`IntegerFormat`] for its pixels.
+    #[inline(always)]
+    fn average_block(&self, src: &[Self::InputPixel], stride: usize, factors: [usize; 2]) -> Option<Self::OutputPixel> {
+        let _ = (src, stride, factors);
+        None
+    }
+
+    /// Copy a row of pixels that are resampled with weight 1, if that's the same as converting them.
+    /// Returns `false` if it isn't, e.g. when components change their type, and then pixels are converted one by one.
+    #[inline(always)]
+    fn copy_pixels(&self, src: &[Self::InputPixel], dst: &mut [Self::OutputPixel]) -> bool {
+        let _ = (src, dst);
+        false
+    }
 }
 
-impl<F: ToFloat, T: ToFloat> PixelFormat for formats::Rgb<T, F> {
+impl<F: ToFloat, T: ToFloat> PixelFormat for formats::Rgb<F, T> {
     type InputPixel = RGB<F>;
     type OutputPixel = RGB<T>;
     type Accumulator = RGB<f32>;
@@ -51,14 +129,24 @@ impl<F: ToFloat, T: ToFloat> PixelFormat for formats::Rgb<T, F> {
     #[inline(always)]
     fn into_pixel(&self, acc: Self::Accumulator) -> RGB<T> {
         RGB {
-            r: T::from_float(acc.r),
-            g: T::from_float(acc.g),
-            b: T::from_float(acc.b),
+            r: convert::<F, T>(acc.r),
+            g: convert::<F, T>(acc.g),
+            b: convert::<F, T>(acc.b),
         }
     }
+
+    #[inline(always)]
+    fn copy_pixels(&self, src: &[Self::InputPixel], dst: &mut [Self::OutputPixel]) -> bool {
+        copy_same(src, dst)
+    }
+
+    #[inline(always)]
+    fn average_block(&self, src: &[Self::InputPixel], stride: usize, factors: [usize; 2]) -> Option<Self::OutputPixel> {
+        average_as::<formats::Rgb<u8, u8>, _, _>(src, stride, factors).or_else(|| average_as::<formats::Rgb<u16, u16>, _, _>(src, stride, factors))
+    }
 }
 
-impl<F: ToFloat, T: ToFloat> PixelFormat for formats::Rgba<T, F> {
+impl<F: ToFloat, T: ToFloat> PixelFormat for formats::Rgba<F, T> {
     type InputPixel = RGBA<F>;
     type OutputPixel = RGBA<T>;
     type Accumulator = RGBA<f32>;
@@ -87,15 +175,25 @@ impl<F: ToFloat, T: ToFloat> PixelFormat for formats::Rgba<T, F> {
     #[inline(always)]
     fn into_pixel(&self, acc: Self::Accumulator) -> RGBA<T> {
         RGBA {
-            r: T::from_float(acc.r),
-            g: T::from_float(acc.g),
-            b: T::from_float(acc.b),
-            a: T::from_float(acc.a),
+            r: convert::<F, T>(acc.r),
+            g: convert::<F, T>(acc.g),
+            b: convert::<F, T>(acc.b),
+            a: convert::<F, T>(acc.a),
         }
     }
+
+    #[inline(always)]
+    fn copy_pixels(&self, src: &[Self::InputPixel], dst: &mut [Self::OutputPixel]) -> bool {
+        copy_same(src, dst)
+    }
+
+    #[inline(always)]
+    fn average_block(&self, src: &[Self::InputPixel], stride: usize, factors: [usize; 2]) -> Option<Self::OutputPixel> {
+        average_as::<formats::Rgba<u8, u8>, _, _>(src, stride, factors).or_else(|| average_as::<formats::Rgba<u16, u16>, _, _>(src, stride, factors))
+    }
 }
 
-impl<F: ToFloat, T: ToFloat> PixelFormat for formats::RgbaPremultiply<T, F> {
+impl<F: ToFloat, T: ToFloat> PixelFormat for formats::RgbaPremultiply<F, T> {
     type InputPixel = RGBA<F>;
     type OutputPixel = RGBA<T>;
     type Accumulator = RGBA<f32>;
@@ -127,10 +225,10 @@ impl<F: ToFloat, T: ToFloat> PixelFormat for formats::RgbaPremultiply<T, F> {
         if acc.a > 0. {
             let inv = 1.0 / acc.a;
             RGBA {
-                r: T::from_float(acc.r * inv),
-                g: T::from_float(acc.g * inv),
-                b: T::from_float(acc.b * inv),
-                a: T::from_float(acc.a),
+                r: convert::<F, T>(acc.r * inv),
+                g: convert::<F, T>(acc.g * inv),
+                b: convert::<F, T>(acc.b * inv),
+                a: convert::<F, T>(acc.a),
             }
         } else {
             let zero = T::from_float(0.);
@@ -139,6 +237,215 @@ impl<F: ToFloat, T: ToFloat> PixelFormat for formats::RgbaPremultiply<T, F> {
     }
 }
 
+impl<F: ToFloat, T: ToFloat> PixelFormat for formats::Bgr<F, T> {
+    type InputPixel = BGR<F>;
+    type OutputPixel = BGR<T>;
+    type Accumulator = BGR<f32>;
+
+    #[inline(always)]
+    fn new() -> Self::Accumulator {
+        BGR { b: 0., g: 0., r: 0. }
+    }
+
+    #[inline(always)]
+    fn add(&self, acc: &mut Self::Accumulator, inp: BGR<F>, coeff: f32) {
+        acc.b += inp.b.to_float() * coeff;
+        acc.g += inp.g.to_float() * coeff;
+        acc.r += inp.r.to_float() * coeff;
+    }
+
+    #[inline(always)]
+    fn add_acc(acc: &mut Self::Accumulator, inp: Self::Accumulator, coeff: f32) {
+        acc.b += inp.b * coeff;
+        acc.g += inp.g * coeff;
+        acc.r += inp.r * coeff;
+    }
+
+    #[inline(always)]
+    fn into_pixel(&self, acc: Self::Accumulator) -> BGR<T> {
+        BGR {
+            b: convert::<F, T>(acc.b),
+            g: convert::<F, T>(acc.g),
+            r: convert::<F, T>(acc.r),
+        }
+    }
+
+    #[inline(always)]
+    fn copy_pixels(&self, src: &[Self::InputPixel], dst: &mut [Self::OutputPixel]) -> bool {
+        copy_same(src, dst)
+    }
+
+    #[inline(always)]
+    fn average_block(&self, src: &[Self::InputPixel], stride: usize, factors: [usize; 2]) -> Option<Self::OutputPixel> {
+        average_as::<formats::Bgr<u8, u8>, _, _>(src, stride, factors).or_else(|| average_as::<formats::Bgr<u16, u16>, _, _>(src, stride, factors))
+    }
+}
+
+impl<F: ToFloat, T: ToFloat> PixelFormat for formats::Bgra<F, T> {
+    type InputPixel = BGRA<F>;
+    type OutputPixel = BGRA<T>;
+    type Accumulator = BGRA<f32>;
+
+    #[inline(always)]
+    fn new() -> Self::Accumulator {
+        BGRA { b: 0., g: 0., r: 0., a: 0. }
+    }
+
+    #[inline(always)]
+    fn add(&self, acc: &mut Self::Accumulator, inp: BGRA<F>, coeff: f32) {
+        acc.b += inp.b.to_float() * coeff;
+        acc.g += inp.g.to_float() * coeff;
+        acc.r += inp.r.to_float() * coeff;
+        acc.a += inp.a.to_float() * coeff;
+    }
+
+    #[inline(always)]
+    fn add_acc(acc: &mut Self::Accumulator, inp: Self::Accumulator, coeff: f32) {
+        acc.b += inp.b * coeff;
+        acc.g += inp.g * coeff;
+        acc.r += inp.r * coeff;
+        acc.a += inp.a * coeff;
+    }
+
+    #[inline(always)]
+    fn into_pixel(&self, acc: Self::Accumulator) -> BGRA<T> {
+        BGRA {
+            b: convert::<F, T>(acc.b),
+            g: convert::<F, T>(acc.g),
+            r: convert::<F, T>(acc.r),
+            a: convert::<F, T>(acc.a),
+        }
+    }
+
+    #[inline(always)]
+    fn copy_pixels(&self, src: &[Self::InputPixel], dst: &mut [Self::OutputPixel]) -> bool {
+        copy_same(src, dst)
+    }
+
+    #[inline(always)]
+    fn average_block(&self, src: &[Self::InputPixel], stride: usize, factors: [usize; 2]) -> Option<Self::OutputPixel> {
+        average_as::<formats::Bgra<u8, u8>, _, _>(src, stride, factors).or_else(|| average_as::<formats::Bgra<u16, u16>, _, _>(src, stride, factors))
+    }
+}
+
+impl<F: ToFloat, T: ToFloat> PixelFormat for formats::BgraPremultiply<F, T> {
+    type InputPixel = BGRA<F>;
+    type OutputPixel = BGRA<T>;
+    type Accumulator = BGRA<f32>;
+
+    #[inline(always)]
+    fn new() -> Self::Accumulator {
+        BGRA { b: 0., g: 0., r: 0., a: 0. }
+    }
+
+    #[inline(always)]
+    fn add(&self, acc: &mut Self::Accumulator, inp: BGRA<F>, coeff: f32) {
+        let a_coeff = inp.a.to_float() * coeff;
+        acc.b += inp.b.to_float() * a_coeff;
+        acc.g += inp.g.to_float() * a_coeff;
+        acc.r += inp.r.to_float() * a_coeff;
+        acc.a += a_coeff;
+    }
+
+    #[inline(always)]
+    fn add_acc(acc: &mut Self::Accumulator, inp: Self::Accumulator, coeff: f32) {
+        acc.b += inp.b * coeff;
+        acc.g += inp.g * coeff;
+        acc.r += inp.r * coeff;
+        acc.a += inp.a * coeff;
+    }
+
+    #[inline(always)]
+    fn into_pixel(&self, acc: Self::Accumulator) -> BGRA<T> {
+        if acc.a > 0. {
+            let inv = 1.0 / acc.a;
+            BGRA {
+                b: convert::<F, T>(acc.b * inv),
+                g: convert::<F, T>(acc.g * inv),
+                r: convert::<F, T>(acc.r * inv),
+                a: convert::<F, T>(acc.a),
+            }
+        } else {
+            let zero = T::from_float(0.);
+            BGRA { b: zero, g: zero, r: zero, a: zero }
+        }
+    }
+}
+
+/// CMYK pixel, e.g. from prepress TIFF files
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct CMYK<T> {
+    /// Cyan
+    pub c: T,
+    /// Magenta
+    pub m: T,
+    /// Yellow
+    pub y: T,
+    /// Black
+    pub k: T,
+}
+
+impl<T> CMYK<T> {
+    /// Reinterpret a slice of interleaved components as pixels. Trailing components that don't form a whole pixel are ignored.
+    #[inline]
+    #[must_use]
+    pub fn from_slice(components: &[T]) -> &[Self] {
+        // CMYK<T> is repr(C) with 4 fields of the same type, so it has the same layout as [T; 4]
+        unsafe { core::slice::from_raw_parts(components.as_ptr().cast(), components.len() / 4) }
+    }
+
+    /// Reinterpret a mutable slice of interleaved components as pixels. Trailing components that don't form a whole pixel are ignored.
+    #[inline]
+    #[must_use]
+    pub fn from_slice_mut(components: &mut [T]) -> &mut [Self] {
+        unsafe { core::slice::from_raw_parts_mut(components.as_mut_ptr().cast(), components.len() / 4) }
+    }
+}
+
+impl<F: ToFloat, T: ToFloat> PixelFormat for formats::Cmyk<F, T> {
+    type InputPixel = CMYK<F>;
+    type OutputPixel = CMYK<T>;
+    type Accumulator = CMYK<f32>;
+
+    #[inline(always)]
+    fn new() -> Self::Accumulator {
+        CMYK { c: 0., m: 0., y: 0., k: 0. }
+    }
+
+    #[inline(always)]
+    fn add(&self, acc: &mut Self::Accumulator, inp: CMYK<F>, coeff: f32) {
+        acc.c += inp.c.to_float() * coeff;
+        acc.m += inp.m.to_float() * coeff;
+        acc.y += inp.y.to_float() * coeff;
+        acc.k += inp.k.to_float() * coeff;
+    }
+
+    #[inline(always)]
+    fn add_acc(acc: &mut Self::Accumulator, inp: Self::Accumulator, coeff: f32) {
+        acc.c += inp.c * coeff;
+        acc.m += inp.m * coeff;
+        acc.y += inp.y * coeff;
+        acc.k += inp.k * coeff;
+    }
+
+    #[inline(always)]
+    fn into_pixel(&self, acc: Self::Accumulator) -> CMYK<T> {
+        CMYK {
+            c: convert::<F, T>(acc.c),
+            m: convert::<F, T>(acc.m),
+            y: convert::<F, T>(acc.y),
+            k: convert::<F, T>(acc.k),
+        }
+    }
+
+    #[inline(always)]
+    fn copy_pixels(&self, src: &[Self::InputPixel], dst: &mut [Self::OutputPixel]) -> bool {
+        copy_same(src, dst)
+    }
+}
+
+#[allow(deprecated)]
 impl<F: ToFloat, T: ToFloat> PixelFormat for formats::Gray<F, T> {
     type InputPixel = Gray<F>;
     type OutputPixel = Gray<T>;
@@ -161,61 +468,1908 @@ impl<F: ToFloat, T: ToFloat> PixelFormat for formats::Gray<F, T> {
 
     #[inline(always)]
     fn into_pixel(&self, acc: Self::Accumulator) -> Gray<T> {
-        Gray::new(T::from_float(acc.0))
+        Gray::new(convert::<F, T>(acc.0))
+    }
+
+    #[inline(always)]
+    fn copy_pixels(&self, src: &[Self::InputPixel], dst: &mut [Self::OutputPixel]) -> bool {
+        copy_same(src, dst)
+    }
+
+    #[inline(always)]
+    fn average_block(&self, src: &[Self::InputPixel], stride: usize, factors: [usize; 2]) -> Option<Self::OutputPixel> {
+        average_as::<formats::Gray<u8, u8>, _, _>(src, stride, factors).or_else(|| average_as::<formats::Gray<u16, u16>, _, _>(src, stride, factors))
     }
 }
 
-use self::f::ToFloat;
-mod f {
-    use crate::round;
+#[allow(deprecated)]
+impl<F: ToFloat, T: ToFloat> PixelFormat for formats::Coverage<F, T> {
+    type InputPixel = Gray<F>;
+    type OutputPixel = Gray<T>;
+    type Accumulator = f32;
 
-    /// Internal, please don't use
-    pub trait ToFloat: Sized + Copy + 'static {
-        fn to_float(self) -> f32;
-        fn from_float(f: f32) -> Self;
+    #[inline(always)]
+    fn new() -> Self::Accumulator {
+        0.
     }
 
-    impl ToFloat for u8 {
-        #[inline(always)]
-        fn to_float(self) -> f32 {
-            self as f32
-        }
+    #[inline(always)]
+    fn add(&self, acc: &mut Self::Accumulator, inp: Gray<F>, coeff: f32) {
+        *acc += inp.0.to_float() * coeff;
+    }
 
-        #[inline(always)]
-        fn from_float(f: f32) -> Self {
-            unsafe {
-                (0f32).max(round(f as f64) as f32).min(255.).to_int_unchecked()
-            }
-        }
+    #[inline(always)]
+    fn add_acc(acc: &mut Self::Accumulator, inp: Self::Accumulator, coeff: f32) {
+        *acc += inp * coeff;
     }
 
-    impl ToFloat for u16 {
-        #[inline(always)]
-        fn to_float(self) -> f32 {
-            self as f32
-        }
+    #[inline(always)]
+    fn into_pixel(&self, acc: Self::Accumulator) -> Gray<T> {
+        Gray::new(convert::<F, T>(acc.max(0.).min(F::MAX)))
+    }
+}
 
-        #[inline(always)]
-        fn from_float(f: f32) -> Self {
-            unsafe {
-                (0f32).max(round(f as f64) as f32).min(65535.).to_int_unchecked()
+macro_rules! impl_precise_format {
+    ($($fmt:ident => $px:ident [$($c:ident),+]),*) => {$(
+        impl<F: ToFloat, T: ToFloat> PixelFormat for formats::$fmt<F, T> {
+            type InputPixel = $px<F>;
+            type OutputPixel = $px<T>;
+            type Accumulator = $px<f64>;
+
+            #[inline(always)]
+            fn new() -> Self::Accumulator {
+                $px { $($c: 0.),+ }
+            }
+
+            #[inline(always)]
+            fn add(&self, acc: &mut Self::Accumulator, inp: $px<F>, coeff: f32) {
+                $(acc.$c += inp.$c.to_f64() * coeff as f64;)+
+            }
+
+            #[inline(always)]
+            fn add_acc(acc: &mut Self::Accumulator, inp: Self::Accumulator, coeff: f32) {
+                $(acc.$c += inp.$c * coeff as f64;)+
+            }
+
+            #[inline(always)]
+            fn into_pixel(&self, acc: Self::Accumulator) -> $px<T> {
+                $px { $($c: convert_f64::<F, T>(acc.$c)),+ }
             }
         }
+    )*}
+}
+
+impl_precise_format!(PreciseRgb => RGB[r, g, b], PreciseRgba => RGBA[r, g, b, a]);
+
+#[allow(deprecated)]
+impl<F: ToFloat, T: ToFloat> PixelFormat for formats::PreciseGray<F, T> {
+    type InputPixel = Gray<F>;
+    type OutputPixel = Gray<T>;
+    type Accumulator = f64;
+
+    #[inline(always)]
+    fn new() -> Self::Accumulator {
+        0.
     }
 
-    impl ToFloat for f32 {
-        #[inline(always)]
-        fn to_float(self) -> f32 {
-            self
+    #[inline(always)]
+    fn add(&self, acc: &mut Self::Accumulator, inp: Gray<F>, coeff: f32) {
+        *acc += inp.0.to_f64() * coeff as f64;
+    }
+
+    #[inline(always)]
+    fn add_acc(acc: &mut Self::Accumulator, inp: Self::Accumulator, coeff: f32) {
+        *acc += inp * coeff as f64;
+    }
+
+    #[inline(always)]
+    fn into_pixel(&self, acc: Self::Accumulator) -> Gray<T> {
+        Gray::new(convert_f64::<F, T>(acc))
+    }
+}
+
+impl<F: ToFloat, T: ToFloat> PixelFormat for formats::Duo<F, T> {
+    type InputPixel = [F; 2];
+    type OutputPixel = [T; 2];
+    type Accumulator = [f32; 2];
+
+    #[inline(always)]
+    fn new() -> Self::Accumulator {
+        [0.; 2]
+    }
+
+    #[inline(always)]
+    fn add(&self, acc: &mut Self::Accumulator, inp: [F; 2], coeff: f32) {
+        acc[0] += inp[0].to_float() * coeff;
+        acc[1] += inp[1].to_float() * coeff;
+    }
+
+    #[inline(always)]
+    fn add_acc(acc: &mut Self::Accumulator, inp: Self::Accumulator, coeff: f32) {
+        acc[0] += inp[0] * coeff;
+        acc[1] += inp[1] * coeff;
+    }
+
+    #[inline(always)]
+    fn into_pixel(&self, acc: Self::Accumulator) -> [T; 2] {
+        [convert::<F, T>(acc[0]), convert::<F, T>(acc[1])]
+    }
+
+    #[inline(always)]
+    fn copy_pixels(&self, src: &[Self::InputPixel], dst: &mut [Self::OutputPixel]) -> bool {
+        copy_same(src, dst)
+    }
+}
+
+impl<F: ToFloat, T: ToFloat, const N: usize> PixelFormat for formats::Channels<F, T, N> {
+    type InputPixel = [F; N];
+    type OutputPixel = [T; N];
+    type Accumulator = [f32; N];
+
+    #[inline(always)]
+    fn new() -> Self::Accumulator {
+        [0.; N]
+    }
+
+    #[inline(always)]
+    fn add(&self, acc: &mut Self::Accumulator, inp: [F; N], coeff: f32) {
+        for (acc, inp) in acc.iter_mut().zip(inp.iter()) {
+            *acc += inp.to_float() * coeff;
         }
+    }
 
-        #[inline(always)]
-        fn from_float(f: f32) -> Self {
-            f
+    #[inline(always)]
+    fn add_acc(acc: &mut Self::Accumulator, inp: Self::Accumulator, coeff: f32) {
+        for (acc, inp) in acc.iter_mut().zip(inp.iter()) {
+            *acc += inp * coeff;
+        }
+    }
+
+    #[inline(always)]
+    fn into_pixel(&self, acc: Self::Accumulator) -> [T; N] {
+        let mut out = [T::from_float(0.); N];
+        for (out, acc) in out.iter_mut().zip(acc.iter()) {
+            *out = convert::<F, T>(*acc);
+        }
+        out
+    }
+
+    #[inline(always)]
+    fn copy_pixels(&self, src: &[Self::InputPixel], dst: &mut [Self::OutputPixel]) -> bool {
+        copy_same(src, dst)
+    }
+}
+
+#[allow(deprecated)]
+impl<F: ToFloat, T: ToFloat> PixelFormat for formats::GrayAlpha<F, T> {
+    type InputPixel = GrayAlpha<F>;
+    type OutputPixel = GrayAlpha<T>;
+    type Accumulator = GrayAlpha<f32>;
+
+    #[inline(always)]
+    fn new() -> Self::Accumulator {
+        GrayAlpha(0., 0.)
+    }
+
+    #[inline(always)]
+    fn add(&self, acc: &mut Self::Accumulator, inp: GrayAlpha<F>, coeff: f32) {
+        acc.0 += inp.0.to_float() * coeff;
+        acc.1 += inp.1.to_float() * coeff;
+    }
+
+    #[inline(always)]
+    fn add_acc(acc: &mut Self::Accumulator, inp: Self::Accumulator, coeff: f32) {
+        acc.0 += inp.0 * coeff;
+        acc.1 += inp.1 * coeff;
+    }
+
+    #[inline(always)]
+    fn into_pixel(&self, acc: Self::Accumulator) -> GrayAlpha<T> {
+        GrayAlpha(convert::<F, T>(acc.0), convert::<F, T>(acc.1))
+    }
+
+    #[inline(always)]
+    fn copy_pixels(&self, src: &[Self::InputPixel], dst: &mut [Self::OutputPixel]) -> bool {
+        copy_same(src, dst)
+    }
+
+    #[inline(always)]
+    fn average_block(&self, src: &[Self::InputPixel], stride: usize, factors: [usize; 2]) -> Option<Self::OutputPixel> {
+        average_as::<formats::GrayAlpha<u8, u8>, _, _>(src, stride, factors).or_else(|| average_as::<formats::GrayAlpha<u16, u16>, _, _>(src, stride, factors))
+    }
+}
+
+#[allow(deprecated)]
+impl<F: ToFloat, T: ToFloat> PixelFormat for formats::GrayAlphaPremultiply<F, T> {
+    type InputPixel = GrayAlpha<F>;
+    type OutputPixel = GrayAlpha<T>;
+    type Accumulator = GrayAlpha<f32>;
+
+    #[inline(always)]
+    fn new() -> Self::Accumulator {
+        GrayAlpha(0., 0.)
+    }
+
+    #[inline(always)]
+    fn add(&self, acc: &mut Self::Accumulator, inp: GrayAlpha<F>, coeff: f32) {
+        let a_coeff = inp.1.to_float() * coeff;
+        acc.0 += inp.0.to_float() * a_coeff;
+        acc.1 += a_coeff;
+    }
+
+    #[inline(always)]
+    fn add_acc(acc: &mut Self::Accumulator, inp: Self::Accumulator, coeff: f32) {
+        acc.0 += inp.0 * coeff;
+        acc.1 += inp.1 * coeff;
+    }
+
+    #[inline(always)]
+    fn into_pixel(&self, acc: Self::Accumulator) -> GrayAlpha<T> {
+        if acc.1 > 0. {
+            GrayAlpha(convert::<F, T>(acc.0 / acc.1), convert::<F, T>(acc.1))
+        } else {
+            let zero = T::from_float(0.);
+            GrayAlpha(zero, zero)
+        }
+    }
+}
+
+/// Blends resampled pixels with a fill color wherever weights don't add up to 1,
+/// i.e. outside of the image with [`Edge::Constant`](crate::Edge::Constant).
+#[derive(Debug, Copy, Clone)]
+pub struct Fill<Format: PixelFormat> {
+    format: Format,
+    color: Format::Accumulator,
+}
+
+impl<Format: PixelFormat> Fill<Format> {
+    /// Use `color` for pixels outside of the image
+    #[inline]
+    pub fn new(format: Format, color: Format::InputPixel) -> Self {
+        let mut acc = Format::new();
+        format.add(&mut acc, color, 1.);
+        Self { format, color: acc }
+    }
+}
+
+impl<Format: PixelFormat> PixelFormat for Fill<Format> {
+    type InputPixel = Format::InputPixel;
+    type OutputPixel = Format::OutputPixel;
+    type Accumulator = Format::Accumulator;
+
+    #[inline(always)]
+    fn new() -> Self::Accumulator {
+        Format::new()
+    }
+
+    // Accumulates difference from the fill color, so the missing weight is filled with it at the end
+    #[inline(always)]
+    fn add(&self, acc: &mut Self::Accumulator, inp: Self::InputPixel, coeff: f32) {
+        self.format.add(acc, inp, coeff);
+        Format::add_acc(acc, self.color, -coeff);
+    }
+
+    #[inline(always)]
+    fn add_acc(acc: &mut Self::Accumulator, inp: Self::Accumulator, coeff: f32) {
+        Format::add_acc(acc, inp, coeff)
+    }
+
+    #[inline(always)]
+    fn into_pixel(&self, mut acc: Self::Accumulator) -> Self::OutputPixel {
+        Format::add_acc(&mut acc, self.color, 1.);
+        self.format.into_pixel(acc)
+    }
+
+    #[inline(always)]
+    fn write_pixel(&self, out: &mut Self::OutputPixel, mut acc: Self::Accumulator, x: usize, y: usize) {
+        Format::add_acc(&mut acc, self.color, 1.);
+        self.format.write_pixel(out, acc, x, y)
+    }
+}
+
+/// 8-bit pixel formats that can be resampled with integer arithmetic by [`FixedPointResizer`](crate::FixedPointResizer)
+/// and [`CompatResizer`](crate::CompatResizer).
+///
+/// Components are added independently, so formats that premultiply alpha aren't supported.
+pub trait FixedPointFormat {
+    /// Pixel type in the source and destination image
+    type Pixel: Copy;
+    /// Components of the pixel multiplied by fixed-point weights, e.g. `[i32; 3]` for RGB
+    type Accumulator: Copy + AsRef<[i32]> + AsMut<[i32]>;
+
+    /// Create accumulator with all components set to zero
+    fn new() -> Self::Accumulator;
+    /// Add pixel's components multiplied by fixed-point `coeff`
+    fn add(acc: &mut Self::Accumulator, inp: Self::Pixel, coeff: i32);
+    /// Convert components that have already been rounded and clamped to 0-255
+    fn into_pixel(acc: Self::Accumulator) -> Self::Pixel;
+}
+
+macro_rules! impl_fixed_point_format {
+    ($($fmt:ident => $px:ident [$($c:tt),+] $n:literal),*) => {$(
+        #[allow(deprecated)]
+        impl FixedPointFormat for formats::$fmt<u8, u8> {
+            type Pixel = $px<u8>;
+            type Accumulator = [i32; $n];
+
+            #[inline(always)]
+            fn new() -> [i32; $n] {
+                [0; $n]
+            }
+
+            #[inline(always)]
+            fn add(acc: &mut [i32; $n], inp: $px<u8>, coeff: i32) {
+                let mut acc = acc.iter_mut();
+                $( *acc.next().unwrap() += i32::from(inp.$c) * coeff; )+
+            }
+
+            #[inline(always)]
+            fn into_pixel(acc: [i32; $n]) -> $px<u8> {
+                let mut out = $px::<u8>::default();
+                let mut acc = acc.iter();
+                $( out.$c = *acc.next().unwrap() as u8; )+
+                out
+            }
+        }
+    )*};
+}
+
+impl_fixed_point_format!(Gray => Gray[0] 1, GrayAlpha => GrayAlpha[0, 1] 2,
+    Rgb => RGB[r, g, b] 3, Bgr => BGR[b, g, r] 3, Rgba => RGBA[r, g, b, a] 4, Bgra => BGRA[b, g, r, a] 4);
+
+/// Pixel formats with integer components that can be averaged exactly by [`BoxDownscaler`](crate::BoxDownscaler).
+///
+/// Components are averaged independently, so formats that premultiply alpha aren't supported.
+pub trait IntegerFormat {
+    /// Pixel type in the source and destination image
+    type Pixel: Copy;
+    /// Sums of components, e.g. `[u64; 3]` for RGB
+    type Sums: Copy + AsRef<[u64]> + AsMut<[u64]>;
+
+    /// Create sums with all components set to zero
+    fn new() -> Self::Sums;
+    /// Add pixel's components to the sums
+    fn add(sums: &mut Self::Sums, inp: Self::Pixel);
+    /// Convert averages, which are already in the range of the component type
+    fn into_pixel(averages: Self::Sums) -> Self::Pixel;
+}
+
+macro_rules! impl_integer_format {
+    ($($fmt:ident => $px:ident [$($c:tt),+] $n:literal),*) => {$(
+        impl_integer_format!(@impl u8, $fmt, $px, [$($c),+], $n);
+        impl_integer_format!(@impl u16, $fmt, $px, [$($c),+], $n);
+    )*};
+    (@impl $sub:ty, $fmt:ident, $px:ident, [$($c:tt),+], $n:literal) => {
+        #[allow(deprecated)]
+        impl IntegerFormat for formats::$fmt<$sub, $sub> {
+            type Pixel = $px<$sub>;
+            type Sums = [u64; $n];
+
+            #[inline(always)]
+            fn new() -> [u64; $n] {
+                [0; $n]
+            }
+
+            #[inline(always)]
+            fn add(sums: &mut [u64; $n], inp: $px<$sub>) {
+                let mut sums = sums.iter_mut();
+                $( *sums.next().unwrap() += u64::from(inp.$c); )+
+            }
+
+            #[inline(always)]
+            fn into_pixel(averages: [u64; $n]) -> $px<$sub> {
+                let mut out = $px::<$sub>::default();
+                let mut averages = averages.iter();
+                $( out.$c = *averages.next().unwrap() as $sub; )+
+                out
+            }
+        }
+    };
+}
+
+impl_integer_format!(Gray => Gray[0] 1, GrayAlpha => GrayAlpha[0, 1] 2,
+    Rgb => RGB[r, g, b] 3, Bgr => BGR[b, g, r] 3, Rgba => RGBA[r, g, b, a] 4, Bgra => BGRA[b, g, r, a] 4);
+
+/// Outputs accumulators of `Format` as they are, to be resampled again with [`FromAccumulator`]
+pub(crate) struct KeepAccumulator<'a, Format>(pub &'a Format);
+
+impl<Format: PixelFormat> PixelFormat for KeepAccumulator<'_, Format> {
+    type InputPixel = Format::InputPixel;
+    type OutputPixel = Format::Accumulator;
+    type Accumulator = Format::Accumulator;
+
+    #[inline(always)]
+    fn new() -> Self::Accumulator {
+        Format::new()
+    }
+
+    #[inline(always)]
+    fn add(&self, acc: &mut Self::Accumulator, inp: Self::InputPixel, coeff: f32) {
+        self.0.add(acc, inp, coeff)
+    }
+
+    #[inline(always)]
+    fn add_acc(acc: &mut Self::Accumulator, inp: Self::Accumulator, coeff: f32) {
+        Format::add_acc(acc, inp, coeff)
+    }
+
+    #[inline(always)]
+    fn into_pixel(&self, acc: Self::Accumulator) -> Self::Accumulator {
+        acc
+    }
+}
+
+/// Resamples accumulators made by [`KeepAccumulator`] into output pixels of `Format`
+pub(crate) struct FromAccumulator<'a, Format>(pub &'a Format);
+
+impl<Format: PixelFormat> PixelFormat for FromAccumulator<'_, Format> {
+    type InputPixel = Format::Accumulator;
+    type OutputPixel = Format::OutputPixel;
+    type Accumulator = Format::Accumulator;
+
+    #[inline(always)]
+    fn new() -> Self::Accumulator {
+        Format::new()
+    }
+
+    #[inline(always)]
+    fn add(&self, acc: &mut Self::Accumulator, inp: Self::Accumulator, coeff: f32) {
+        Format::add_acc(acc, inp, coeff)
+    }
+
+    #[inline(always)]
+    fn add_acc(acc: &mut Self::Accumulator, inp: Self::Accumulator, coeff: f32) {
+        Format::add_acc(acc, inp, coeff)
+    }
+
+    #[inline(always)]
+    fn into_pixel(&self, acc: Self::Accumulator) -> Self::OutputPixel {
+        self.0.into_pixel(acc)
+    }
+
+    #[inline(always)]
+    fn write_pixel(&self, out: &mut Self::OutputPixel, acc: Self::Accumulator, x: usize, y: usize) {
+        self.0.write_pixel(out, acc, x, y)
+    }
+}
+
+/// How [`Output`] combines resampled pixels with the destination image
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum OutputMode {
+    /// Overwrite the destination, same as without [`Output`]
+    #[default]
+    Replace,
+    /// Add the resampled pixels to the destination, e.g. for stacking exposures. All components are added, including alpha.
+    Add,
+    /// Composite the resampled pixels over the destination using their alpha ("source over").
+    /// Same as `Replace` for formats without alpha.
+    Blend,
+}
+
+/// Combines resampled pixels with the existing contents of the destination image, instead of overwriting them.
+///
+/// Works with formats implementing [`BlendFormat`]: the 8-bit, 16-bit and float `Gray`, `GrayAlpha`, `Rgb`, `Rgba`
+/// and `Bgr`/`Bgra` presets, straight and premultiplied.
+///
+/// ```
+/// use resize::px::{Output, OutputMode};
+/// use rgb::FromSlice;
+/// let mut dst = [10u8, 20];
+/// resize::new(4, 1, 2, 1, Output::new(resize::Pixel::Gray8, OutputMode::Add), resize::Type::Area)?
+///     .resize([100, 100, 50, 50][..].as_gray(), dst.as_gray_mut())?;
+/// assert_eq!(dst, [110, 70]);
+/// # Ok::<_, resize::Error>(())
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct Output<Format> {
+    format: Format,
+    mode: OutputMode,
+}
+
+impl<Format> Output<Format> {
+    /// Write pixels of `format` according to `mode`
+    #[inline]
+    pub fn new(format: Format, mode: OutputMode) -> Self {
+        Self { format, mode }
+    }
+}
+
+impl<Format: BlendFormat> PixelFormat for Output<Format> {
+    type InputPixel = Format::InputPixel;
+    type OutputPixel = Format::OutputPixel;
+    type Accumulator = Format::Accumulator;
+
+    #[inline(always)]
+    fn new() -> Self::Accumulator {
+        Format::new()
+    }
+
+    #[inline(always)]
+    fn add(&self, acc: &mut Self::Accumulator, inp: Self::InputPixel, coeff: f32) {
+        self.format.add(acc, inp, coeff)
+    }
+
+    #[inline(always)]
+    fn add_acc(acc: &mut Self::Accumulator, inp: Self::Accumulator, coeff: f32) {
+        Format::add_acc(acc, inp, coeff)
+    }
+
+    #[inline(always)]
+    fn into_pixel(&self, acc: Self::Accumulator) -> Self::OutputPixel {
+        self.format.into_pixel(acc)
+    }
+
+    #[inline(always)]
+    fn write_pixel(&self, out: &mut Self::OutputPixel, acc: Self::Accumulator, x: usize, y: usize) {
+        match self.mode {
+            OutputMode::Replace => self.format.write_pixel(out, acc, x, y),
+            mode => self.format.combine(out, acc, mode),
+        }
+    }
+}
+
+/// Pixel formats that can combine resampled pixels with existing output pixels, see [`Output`]
+pub trait BlendFormat: PixelFormat {
+    /// Add `acc` to, or blend it over `out`. Never called with [`OutputMode::Replace`].
+    fn combine(&self, out: &mut Self::OutputPixel, acc: Self::Accumulator, mode: OutputMode);
+}
+
+/// Value of an output component in the units of the input's range
+#[inline(always)]
+fn unconvert<F: ToFloat, T: ToFloat>(c: T) -> f32 {
+    if F::MAX == T::MAX { c.to_float() } else { c.to_float() * (F::MAX / T::MAX) }
+}
+
+macro_rules! impl_blend_format {
+    (opaque: $($fmt:ident => $px:ident [$($c:tt),+]),*) => {$(
+        #[allow(deprecated)]
+        impl<F: ToFloat, T: ToFloat> BlendFormat for formats::$fmt<F, T> {
+            #[inline(always)]
+            fn combine(&self, out: &mut $px<T>, mut acc: $px<f32>, mode: OutputMode) {
+                if mode == OutputMode::Add {
+                    $(acc.$c += unconvert::<F, T>(out.$c);)+
+                }
+                *out = self.into_pixel(acc);
+            }
+        }
+    )*};
+    (straight: $($fmt:ident => $px:ident [$($c:tt),+] $a:tt),*) => {$(
+        #[allow(deprecated)]
+        impl<F: ToFloat, T: ToFloat> BlendFormat for formats::$fmt<F, T> {
+            #[inline(always)]
+            fn combine(&self, out: &mut $px<T>, mut acc: $px<f32>, mode: OutputMode) {
+                if mode == OutputMode::Add {
+                    $(acc.$c += unconvert::<F, T>(out.$c);)+
+                    acc.$a += unconvert::<F, T>(out.$a);
+                } else {
+                    let src_a = (acc.$a / F::MAX).clamp(0., 1.);
+                    let dst_a = (unconvert::<F, T>(out.$a) / F::MAX) * (1. - src_a);
+                    let a = src_a + dst_a;
+                    if a > 0. {
+                        $(acc.$c = (acc.$c * src_a + unconvert::<F, T>(out.$c) * dst_a) / a;)+
+                    }
+                    acc.$a = a * F::MAX;
+                }
+                *out = self.into_pixel(acc);
+            }
+        }
+    )*};
+    (premultiplied: $($fmt:ident => $px:ident [$($c:tt),+] $a:tt),*) => {$(
+        #[allow(deprecated)]
+        impl<F: ToFloat, T: ToFloat> BlendFormat for formats::$fmt<F, T> {
+            #[inline(always)]
+            fn combine(&self, out: &mut $px<T>, mut acc: $px<f32>, mode: OutputMode) {
+                let dst_a = unconvert::<F, T>(out.$a);
+                // Accumulators of these formats are premultiplied
+                let weight = if mode == OutputMode::Add { 1. } else { 1. - (acc.$a / F::MAX).clamp(0., 1.) };
+                $(acc.$c += unconvert::<F, T>(out.$c) * dst_a * weight;)+
+                acc.$a += dst_a * weight;
+                *out = self.into_pixel(acc);
+            }
+        }
+    )*};
+}
+
+impl_blend_format!(opaque: Gray => Gray[0], Rgb => RGB[r, g, b], Bgr => BGR[b, g, r]);
+impl_blend_format!(straight: GrayAlpha => GrayAlpha[0] 1, Rgba => RGBA[r, g, b] a, Bgra => BGRA[b, g, r] a);
+impl_blend_format!(premultiplied: GrayAlphaPremultiply => GrayAlpha[0] 1, RgbaPremultiply => RGBA[r, g, b] a, BgraPremultiply => BGRA[b, g, r] a);
+
+/// Pixel formats with an alpha channel, which can be resampled with its own filter by [`AlphaResizer`](crate::AlphaResizer).
+pub trait AlphaFormat: PixelFormat {
+    /// Alpha of the input pixel, in the input's own range (e.g. 0-255 for 8-bit)
+    fn alpha(&self, inp: Self::InputPixel) -> f32;
+    /// Replace alpha of an already resampled pixel with `alpha` from the same range as [`alpha()`](AlphaFormat::alpha)
+    fn set_alpha(&self, out: &mut Self::OutputPixel, alpha: f32);
+}
+
+macro_rules! impl_alpha_format {
+    ($($fmt:ident => $px:ident.$a:tt),*) => {$(
+        #[allow(deprecated)]
+        impl<F: ToFloat, T: ToFloat> AlphaFormat for formats::$fmt<F, T> {
+            #[inline(always)]
+            fn alpha(&self, inp: $px<F>) -> f32 {
+                inp.$a.to_float()
+            }
+
+            #[inline(always)]
+            fn set_alpha(&self, out: &mut $px<T>, alpha: f32) {
+                out.$a = convert::<F, T>(alpha);
+            }
+        }
+    )*};
+}
+
+impl_alpha_format!(Rgba => RGBA.a, RgbaPremultiply => RGBA.a, Bgra => BGRA.a, BgraPremultiply => BGRA.a,
+    GrayAlpha => GrayAlpha.1, GrayAlphaPremultiply => GrayAlpha.1);
+
+/// Weights of R, G and B components used when converting color to grayscale.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LumaWeights {
+    /// Weight of the red component
+    pub r: f32,
+    /// Weight of the green component
+    pub g: f32,
+    /// Weight of the blue component
+    pub b: f32,
+}
+
+impl LumaWeights {
+    /// ITU-R BT.601 (SD video, JPEG)
+    pub const BT601: Self = Self { r: 0.299, g: 0.587, b: 0.114 };
+    /// ITU-R BT.709 (HD video, sRGB)
+    pub const BT709: Self = Self { r: 0.2126, g: 0.7152, b: 0.0722 };
+}
+
+#[allow(deprecated)]
+impl<F: ToFloat, T: ToFloat> PixelFormat for formats::RgbToGray<F, T> {
+    type InputPixel = RGB<F>;
+    type OutputPixel = Gray<T>;
+    type Accumulator = Gray<f32>;
+
+    #[inline(always)]
+    fn new() -> Self::Accumulator {
+        Gray::new(0.)
+    }
+
+    #[inline(always)]
+    fn add(&self, acc: &mut Self::Accumulator, inp: RGB<F>, coeff: f32) {
+        let w = &self.0;
+        acc.0 += (inp.r.to_float() * w.r + inp.g.to_float() * w.g + inp.b.to_float() * w.b) * coeff;
+    }
+
+    #[inline(always)]
+    fn add_acc(acc: &mut Self::Accumulator, inp: Self::Accumulator, coeff: f32) {
+        acc.0 += inp.0 * coeff;
+    }
+
+    #[inline(always)]
+    fn into_pixel(&self, acc: Self::Accumulator) -> Gray<T> {
+        Gray::new(convert::<F, T>(acc.0))
+    }
+}
+
+#[allow(deprecated)]
+impl<F: ToFloat, T: ToFloat> PixelFormat for formats::RgbaToGray<F, T> {
+    type InputPixel = RGBA<F>;
+    type OutputPixel = Gray<T>;
+    type Accumulator = Gray<f32>;
+
+    #[inline(always)]
+    fn new() -> Self::Accumulator {
+        Gray::new(0.)
+    }
+
+    #[inline(always)]
+    fn add(&self, acc: &mut Self::Accumulator, inp: RGBA<F>, coeff: f32) {
+        let w = &self.0;
+        acc.0 += (inp.r.to_float() * w.r + inp.g.to_float() * w.g + inp.b.to_float() * w.b) * coeff;
+    }
+
+    #[inline(always)]
+    fn add_acc(acc: &mut Self::Accumulator, inp: Self::Accumulator, coeff: f32) {
+        acc.0 += inp.0 * coeff;
+    }
+
+    #[inline(always)]
+    fn into_pixel(&self, acc: Self::Accumulator) -> Gray<T> {
+        Gray::new(convert::<F, T>(acc.0))
+    }
+}
+
+/// Limits output of a 16-bit format to a smaller bit depth, e.g. 10-bit or 12-bit video stored in `u16`.
+///
+/// ```
+/// use resize::px::BitDepth;
+/// let format = BitDepth::new(resize::Pixel::RGB16, 10);
+/// # resize::new(1, 1, 1, 1, format, resize::Type::Triangle).unwrap();
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct BitDepth<Format> {
+    format: Format,
+    max: u16,
+}
+
+impl<Format> BitDepth<Format> {
+    /// Output components will be clamped to `2^bits - 1`. `bits` outside of 1..=16 are clamped to that range.
+    #[inline]
+    #[must_use]
+    pub fn new(format: Format, bits: u8) -> Self {
+        let bits = bits.clamp(1, 16);
+        Self { format, max: (u32::MAX >> (32 - bits)) as u16 }
+    }
+
+    /// Largest value a component can have
+    #[inline]
+    #[must_use]
+    pub fn max_value(&self) -> u16 {
+        self.max
+    }
+}
+
+impl<Format: PixelFormat> PixelFormat for BitDepth<Format>
+where Format::OutputPixel: ComponentMap<Format::OutputPixel, u16, u16> {
+    type InputPixel = Format::InputPixel;
+    type OutputPixel = Format::OutputPixel;
+    type Accumulator = Format::Accumulator;
+
+    #[inline(always)]
+    fn new() -> Self::Accumulator {
+        Format::new()
+    }
+
+    #[inline(always)]
+    fn add(&self, acc: &mut Self::Accumulator, inp: Self::InputPixel, coeff: f32) {
+        self.format.add(acc, inp, coeff)
+    }
+
+    #[inline(always)]
+    fn add_acc(acc: &mut Self::Accumulator, inp: Self::Accumulator, coeff: f32) {
+        Format::add_acc(acc, inp, coeff)
+    }
+
+    #[inline(always)]
+    fn into_pixel(&self, acc: Self::Accumulator) -> Self::OutputPixel {
+        let max = self.max;
+        self.format.into_pixel(acc).map(|c| c.min(max))
+    }
+
+    #[inline(always)]
+    fn write_pixel(&self, out: &mut Self::OutputPixel, acc: Self::Accumulator, x: usize, y: usize) {
+        let max = self.max;
+        self.format.write_pixel(out, acc, x, y);
+        *out = out.map(|c| c.min(max));
+    }
+}
+
+/// Reads and writes 16-bit components as big-endian, e.g. 16-bit PNG or TIFF data, regardless of the native byte order.
+///
+/// Components are byte-swapped on the fly, so there's no need to convert the whole buffer before and after resizing.
+#[derive(Debug, Copy, Clone)]
+pub struct BigEndian<Format>(pub Format);
+
+impl<Format: PixelFormat> PixelFormat for BigEndian<Format>
+where
+    Format::InputPixel: ComponentMap<Format::InputPixel, u16, u16>,
+    Format::OutputPixel: ComponentMap<Format::OutputPixel, u16, u16>,
+{
+    type InputPixel = Format::InputPixel;
+    type OutputPixel = Format::OutputPixel;
+    type Accumulator = Format::Accumulator;
+
+    #[inline(always)]
+    fn new() -> Self::Accumulator {
+        Format::new()
+    }
+
+    #[inline(always)]
+    fn add(&self, acc: &mut Self::Accumulator, inp: Self::InputPixel, coeff: f32) {
+        self.0.add(acc, inp.map(u16::from_be), coeff)
+    }
+
+    #[inline(always)]
+    fn add_acc(acc: &mut Self::Accumulator, inp: Self::Accumulator, coeff: f32) {
+        Format::add_acc(acc, inp, coeff)
+    }
+
+    #[inline(always)]
+    fn into_pixel(&self, acc: Self::Accumulator) -> Self::OutputPixel {
+        self.0.into_pixel(acc).map(u16::to_be)
+    }
+
+    #[inline(always)]
+    fn write_pixel(&self, out: &mut Self::OutputPixel, acc: Self::Accumulator, x: usize, y: usize) {
+        // Formats that blend read the existing pixel
+        *out = out.map(u16::from_be);
+        self.0.write_pixel(out, acc, x, y);
+        *out = out.map(u16::to_be);
+    }
+}
+
+/// Accumulator with `f32` components that can be accessed as a slice.
+pub trait Components {
+    /// All components
+    fn components(&self) -> &[f32];
+    /// All components, mutable
+    fn components_mut(&mut self) -> &mut [f32];
+    /// Whether the last component is alpha
+    const ALPHA: bool = false;
+}
+
+macro_rules! impl_components {
+    ($($ty:ty => $n:expr, $alpha:expr);*) => {$(
+        impl Components for $ty {
+            const ALPHA: bool = $alpha;
+
+            #[inline(always)]
+            fn components(&self) -> &[f32] {
+                // repr(C) struct with only f32 fields
+                unsafe { core::slice::from_raw_parts((self as *const Self).cast(), $n) }
+            }
+
+            #[inline(always)]
+            fn components_mut(&mut self) -> &mut [f32] {
+                unsafe { core::slice::from_raw_parts_mut((self as *mut Self).cast(), $n) }
+            }
+        }
+    )*}
+}
+
+impl_components!(Gray<f32> => 1, false; GrayAlpha<f32> => 2, true; RGB<f32> => 3, false; BGR<f32> => 3, false;
+    RGBA<f32> => 4, true; BGRA<f32> => 4, true; CMYK<f32> => 4, false);
+
+impl<const N: usize> Components for [f32; N] {
+    #[inline(always)]
+    fn components(&self) -> &[f32] {
+        self
+    }
+
+    #[inline(always)]
+    fn components_mut(&mut self) -> &mut [f32] {
+        self
+    }
+}
+
+/// Prevents overshoot ("halos") around hard edges by clamping each output pixel
+/// to the range of source pixels under its filter footprint.
+///
+/// Works with formats whose accumulator implements [`Components`]. Clamping is done separately in each pass.
+///
+/// ```
+/// use resize::px::AntiRinging;
+/// let format = AntiRinging(resize::Pixel::RGB8);
+/// # resize::new(1, 1, 1, 1, format, resize::Type::Lanczos3).unwrap();
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct AntiRinging<Format>(pub Format);
+
+impl<Format: PixelFormat> PixelFormat for AntiRinging<Format>
+where Format::Accumulator: Components {
+    type InputPixel = Format::InputPixel;
+    type OutputPixel = Format::OutputPixel;
+    /// Value, and min and max of the footprint
+    type Accumulator = (Format::Accumulator, Format::Accumulator, Format::Accumulator);
+
+    #[inline(always)]
+    fn new() -> Self::Accumulator {
+        let (mut lo, mut hi) = (Format::new(), Format::new());
+        lo.components_mut().iter_mut().for_each(|c| *c = f32::INFINITY);
+        hi.components_mut().iter_mut().for_each(|c| *c = f32::NEG_INFINITY);
+        (Format::new(), lo, hi)
+    }
+
+    #[inline(always)]
+    fn add(&self, acc: &mut Self::Accumulator, inp: Self::InputPixel, coeff: f32) {
+        self.0.add(&mut acc.0, inp, coeff);
+        let mut px = Format::new();
+        self.0.add(&mut px, inp, 1.);
+        extend_range(&mut acc.1, &mut acc.2, &px);
+    }
+
+    #[inline(always)]
+    fn add_acc(acc: &mut Self::Accumulator, inp: Self::Accumulator, coeff: f32) {
+        let (mut px, lo, hi) = inp;
+        clamp_to_range(&mut px, &lo, &hi);
+        Format::add_acc(&mut acc.0, px, coeff);
+        extend_range(&mut acc.1, &mut acc.2, &px);
+    }
+
+    #[inline(always)]
+    fn into_pixel(&self, acc: Self::Accumulator) -> Self::OutputPixel {
+        let (mut px, lo, hi) = acc;
+        clamp_to_range(&mut px, &lo, &hi);
+        self.0.into_pixel(px)
+    }
+
+    #[inline(always)]
+    fn write_pixel(&self, out: &mut Self::OutputPixel, acc: Self::Accumulator, x: usize, y: usize) {
+        let (mut px, lo, hi) = acc;
+        clamp_to_range(&mut px, &lo, &hi);
+        self.0.write_pixel(out, px, x, y)
+    }
+}
+
+/// Adds a little noise before quantizing, so that gradients don't have visible bands.
+///
+/// `step` is the size of a single output level in accumulator's units, e.g. 1 for 8-bit input and output,
+/// 257 for 16-bit input and 8-bit output, or `1./255.` for float input and 8-bit output.
+/// This is ordered dithering: the noise comes from an 8x8 Bayer matrix tiled over the destination image,
+/// so the result only depends on the pixel's position, and it's the same for every component.
+/// [`into_pixel`](PixelFormat::into_pixel) alone doesn't know the position, so it doesn't dither.
+///
+/// ```
+/// use resize::px::Dither;
+/// let format = Dither::new(resize::Pixel::RGB16, 1.);
+/// # resize::new(1, 1, 1, 1, format, resize::Type::Lanczos3).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Dither<Format> {
+    format: Format,
+    step: f32,
+}
+
+/// Thresholds of ordered dithering, 0..64 in an order that spreads neighbors apart
+const BAYER: [[u8; 8]; 8] = [
+    [0, 32, 8, 40, 2, 34, 10, 42],
+    [48, 16, 56, 24, 50, 18, 58, 26],
+    [12, 44, 4, 36, 14, 46, 6, 38],
+    [60, 28, 52, 20, 62, 30, 54, 22],
+    [3, 35, 11, 43, 1, 33, 9, 41],
+    [51, 19, 59, 27, 49, 17, 57, 25],
+    [15, 47, 7, 39, 13, 45, 5, 37],
+    [63, 31, 55, 23, 61, 29, 53, 21],
+];
+
+impl<Format> Dither<Format> {
+    /// Dither output of `format`, where `step` is the difference between output levels
+    #[inline]
+    pub fn new(format: Format, step: f32) -> Self {
+        Self { format, step }
+    }
+
+    /// Noise in `-0.5..0.5` range for the pixel at `x`, `y`
+    #[inline(always)]
+    fn noise(x: usize, y: usize) -> f32 {
+        (BAYER[y % 8][x % 8] as f32 + 0.5) / 64. - 0.5
+    }
+}
+
+impl<Format: PixelFormat> PixelFormat for Dither<Format>
+where Format::Accumulator: Components {
+    type InputPixel = Format::InputPixel;
+    type OutputPixel = Format::OutputPixel;
+    type Accumulator = Format::Accumulator;
+
+    #[inline(always)]
+    fn new() -> Self::Accumulator {
+        Format::new()
+    }
+
+    #[inline(always)]
+    fn add(&self, acc: &mut Self::Accumulator, inp: Self::InputPixel, coeff: f32) {
+        self.format.add(acc, inp, coeff)
+    }
+
+    #[inline(always)]
+    fn add_acc(acc: &mut Self::Accumulator, inp: Self::Accumulator, coeff: f32) {
+        Format::add_acc(acc, inp, coeff)
+    }
+
+    #[inline(always)]
+    fn into_pixel(&self, acc: Self::Accumulator) -> Self::OutputPixel {
+        self.format.into_pixel(acc)
+    }
+
+    #[inline(always)]
+    fn write_pixel(&self, out: &mut Self::OutputPixel, mut acc: Self::Accumulator, x: usize, y: usize) {
+        let noise = Self::noise(x, y) * self.step;
+        for c in acc.components_mut() {
+            *c += noise;
+        }
+        self.format.write_pixel(out, acc, x, y)
+    }
+}
+
+/// Policy of converting accumulated values to integer output levels, see [`Rounding`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RoundingMode {
+    /// Halves round up, e.g. 2.5 to 3
+    HalfUp,
+    /// Halves round to the even neighbour, e.g. 2.5 to 2, 3.5 to 4
+    HalfEven,
+    /// Fraction is discarded, e.g. 2.9 to 2
+    Truncate,
+}
+
+/// Rounds output with the given [`RoundingMode`], for parity with other scalers. By default output is rounded half away from zero.
+///
+/// `step` is the size of a single output level in accumulator's units, same as for [`Dither`].
+/// Rounding is applied to the accumulator, so for formats that premultiply alpha it's exact only for alpha.
+///
+/// ```
+/// use resize::px::{Rounding, RoundingMode};
+/// let format = Rounding::new(resize::Pixel::RGB8, RoundingMode::Truncate, 1.);
+/// # resize::new(1, 1, 1, 1, format, resize::Type::Lanczos3).unwrap();
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct Rounding<Format> {
+    format: Format,
+    mode: RoundingMode,
+    step: f32,
+}
+
+impl<Format> Rounding<Format> {
+    /// Round output of `format` using `mode`, where `step` is the difference between output levels
+    #[inline]
+    pub fn new(format: Format, mode: RoundingMode, step: f32) -> Self {
+        Self { format, mode, step }
+    }
+
+    #[inline]
+    fn round<A: Components>(&self, acc: &mut A) {
+        for c in acc.components_mut() {
+            let levels = (*c / self.step) as f64;
+            let rounded = unsafe {
+                match self.mode {
+                    RoundingMode::HalfUp => floor(levels + 0.5),
+                    RoundingMode::HalfEven => {
+                        let r = floor(levels + 0.5);
+                        if r - levels == 0.5 && r % 2. != 0. { r - 1. } else { r }
+                    },
+                    RoundingMode::Truncate => if levels < 0. { ceil(levels) } else { floor(levels) },
+                }
+            };
+            *c = rounded as f32 * self.step;
+        }
+    }
+}
+
+impl<Format: PixelFormat> PixelFormat for Rounding<Format>
+where Format::Accumulator: Components {
+    type InputPixel = Format::InputPixel;
+    type OutputPixel = Format::OutputPixel;
+    type Accumulator = Format::Accumulator;
+
+    #[inline(always)]
+    fn new() -> Self::Accumulator {
+        Format::new()
+    }
+
+    #[inline(always)]
+    fn add(&self, acc: &mut Self::Accumulator, inp: Self::InputPixel, coeff: f32) {
+        self.format.add(acc, inp, coeff)
+    }
+
+    #[inline(always)]
+    fn add_acc(acc: &mut Self::Accumulator, inp: Self::Accumulator, coeff: f32) {
+        Format::add_acc(acc, inp, coeff)
+    }
+
+    #[inline(always)]
+    fn into_pixel(&self, mut acc: Self::Accumulator) -> Self::OutputPixel {
+        self.round(&mut acc);
+        self.format.into_pixel(acc)
+    }
+
+    #[inline(always)]
+    fn write_pixel(&self, out: &mut Self::OutputPixel, mut acc: Self::Accumulator, x: usize, y: usize) {
+        self.round(&mut acc);
+        self.format.write_pixel(out, acc, x, y)
+    }
+}
+
+/// Range of values used by video, see [`VideoRange`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Range {
+    /// All values are used, e.g. 0-255 for 8 bits
+    Full,
+    /// Limited (studio, TV) range, e.g. 16-235 for 8-bit luma and 16-240 for chroma
+    Limited,
+}
+
+/// Which plane of a YUV/YCbCr image the pixels belong to, see [`VideoRange`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum VideoPlane {
+    /// Y
+    Luma,
+    /// U/V or Cb/Cr, which are centered around the middle of the range
+    Chroma,
+}
+
+/// Clips output to the video range, and optionally converts between limited and full range.
+///
+/// `bits` is the bit depth of values in the accumulator, i.e. of the input. Limited range scales with it,
+/// e.g. 10-bit luma is 64-940.
+///
+/// ```
+/// use resize::px::{Range, VideoPlane, VideoRange};
+/// let format = VideoRange::new(resize::Pixel::Gray8, VideoPlane::Luma, Range::Limited, Range::Full, 8);
+/// # resize::new(1, 1, 1, 1, format, resize::Type::Lanczos3).unwrap();
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct VideoRange<Format> {
+    format: Format,
+    input_anchor: f32,
+    output_anchor: f32,
+    scale: f32,
+    lo: f32,
+    hi: f32,
+}
+
+impl<Format> VideoRange<Format> {
+    /// Convert `plane` from `input` range to `output` range, clipping it to the output range.
+    ///
+    /// `bits` outside of 8..=16 are clamped to that range.
+    #[inline]
+    pub fn new(format: Format, plane: VideoPlane, input: Range, output: Range, bits: u8) -> Self {
+        let bits = bits.clamp(8, 16);
+        let shift = f32::from(1u16 << (bits - 8));
+        let full_max = ((1u32 << bits) - 1) as f32;
+        // Range is described by a point that maps to itself, and its extent from that point
+        let anchor_extent = |range| match (range, plane) {
+            (Range::Full, VideoPlane::Luma) => (0., full_max),
+            (Range::Limited, VideoPlane::Luma) => (16. * shift, 219. * shift),
+            (Range::Full, VideoPlane::Chroma) => (128. * shift, 128. * shift),
+            (Range::Limited, VideoPlane::Chroma) => (128. * shift, 112. * shift),
+        };
+        let (input_anchor, input_extent) = anchor_extent(input);
+        let (output_anchor, output_extent) = anchor_extent(output);
+        let (lo, hi) = match (output, plane) {
+            (Range::Full, _) => (0., full_max),
+            (Range::Limited, VideoPlane::Luma) => (16. * shift, 235. * shift),
+            (Range::Limited, VideoPlane::Chroma) => (16. * shift, 240. * shift),
+        };
+        Self { format, input_anchor, output_anchor, scale: output_extent / input_extent, lo, hi }
+    }
+
+    #[inline]
+    fn convert<A: Components>(&self, acc: &mut A) {
+        for c in acc.components_mut() {
+            *c = ((*c - self.input_anchor) * self.scale + self.output_anchor).max(self.lo).min(self.hi);
+        }
+    }
+}
+
+impl<Format: PixelFormat> PixelFormat for VideoRange<Format>
+where Format::Accumulator: Components {
+    type InputPixel = Format::InputPixel;
+    type OutputPixel = Format::OutputPixel;
+    type Accumulator = Format::Accumulator;
+
+    #[inline(always)]
+    fn new() -> Self::Accumulator {
+        Format::new()
+    }
+
+    #[inline(always)]
+    fn add(&self, acc: &mut Self::Accumulator, inp: Self::InputPixel, coeff: f32) {
+        self.format.add(acc, inp, coeff)
+    }
+
+    #[inline(always)]
+    fn add_acc(acc: &mut Self::Accumulator, inp: Self::Accumulator, coeff: f32) {
+        Format::add_acc(acc, inp, coeff)
+    }
+
+    #[inline(always)]
+    fn into_pixel(&self, mut acc: Self::Accumulator) -> Self::OutputPixel {
+        self.convert(&mut acc);
+        self.format.into_pixel(acc)
+    }
+
+    #[inline(always)]
+    fn write_pixel(&self, out: &mut Self::OutputPixel, mut acc: Self::Accumulator, x: usize, y: usize) {
+        self.convert(&mut acc);
+        self.format.write_pixel(out, acc, x, y)
+    }
+}
+
+/// Reads indexed pixels of paletted images, e.g. GIF or PNG8, and expands them with the palette while resampling.
+///
+/// Input pixels are indices into the palette, and output pixels are those of `Format`.
+/// Indices beyond the end of the palette are treated as if all of their components were 0.
+///
+/// ```
+/// use resize::px::{Palette, RGBA};
+/// use rgb::FromSlice;
+/// let palette = [RGBA::new(255u8, 0, 0, 255), RGBA::new(0, 0, 255, 0)];
+/// let mut dst = [RGBA::new(0u8, 0, 0, 0); 1];
+/// resize::new(2, 1, 1, 1, Palette::new(resize::Pixel::RGBA8P, &palette), resize::Type::Triangle)?.resize(&[0, 1], &mut dst)?;
+/// assert_eq!(dst[0], RGBA::new(255, 0, 0, 128));
+/// # Ok::<_, resize::Error>(())
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct Palette<'a, Format: PixelFormat> {
+    format: Format,
+    palette: &'a [Format::InputPixel],
+}
+
+impl<'a, Format: PixelFormat> Palette<'a, Format> {
+    /// Expand indices with `palette` into pixels of `format`
+    #[inline]
+    pub fn new(format: Format, palette: &'a [Format::InputPixel]) -> Self {
+        Self { format, palette }
+    }
+}
+
+impl<Format: PixelFormat> PixelFormat for Palette<'_, Format> {
+    type InputPixel = u8;
+    type OutputPixel = Format::OutputPixel;
+    type Accumulator = Format::Accumulator;
+
+    #[inline(always)]
+    fn new() -> Self::Accumulator {
+        Format::new()
+    }
+
+    #[inline(always)]
+    fn add(&self, acc: &mut Self::Accumulator, inp: u8, coeff: f32) {
+        if let Some(&px) = self.palette.get(inp as usize) {
+            self.format.add(acc, px, coeff);
+        }
+    }
+
+    #[inline(always)]
+    fn add_acc(acc: &mut Self::Accumulator, inp: Self::Accumulator, coeff: f32) {
+        Format::add_acc(acc, inp, coeff)
+    }
+
+    #[inline(always)]
+    fn into_pixel(&self, acc: Self::Accumulator) -> Self::OutputPixel {
+        self.format.into_pixel(acc)
+    }
+
+    #[inline(always)]
+    fn write_pixel(&self, out: &mut Self::OutputPixel, acc: Self::Accumulator, x: usize, y: usize) {
+        self.format.write_pixel(out, acc, x, y)
+    }
+}
+
+/// Resizes sRGB-encoded pixels in linear light, which keeps the brightness of fine detail and edges.
+///
+/// Input is decoded from sRGB before filtering, and output is encoded back. Data that's already linear,
+/// e.g. decoded EXR or rendered buffers, can be declared with [`linear_input`](Gamma::linear_input) and
+/// [`linear_output`](Gamma::linear_output), which skip the decoding or encoding, so mixed cases are expressible too.
+///
+/// `max` is the full intensity in the format's units, e.g. 255 for 8-bit input, or 1 for float input.
+/// Alpha of formats that have it is kept linear, unless [`include_alpha`](Gamma::include_alpha) is used.
+///
+/// ```
+/// use resize::{formats::Rgb, px::Gamma};
+/// // Linear float rendering to an 8-bit sRGB image
+/// let format = Gamma::new(Rgb::<f32, u8>::instance(), 1.).linear_input();
+/// # resize::new(1, 1, 1, 1, format, resize::Type::Lanczos3).unwrap();
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct Gamma<Format> {
+    format: Format,
+    max: f32,
+    decode: bool,
+    encode: bool,
+    /// Whether the last component is left unchanged, or `None` if only when it's alpha
+    keep_alpha: Option<bool>,
+}
+
+impl<Format> Gamma<Format> {
+    /// Decode sRGB input and encode sRGB output of `format`, whose full intensity is `max`
+    #[inline]
+    pub fn new(format: Format, max: f32) -> Self {
+        Self { format, max, decode: true, encode: true, keep_alpha: None }
+    }
+
+    /// Input is already linear, and isn't decoded
+    #[inline]
+    #[must_use]
+    pub fn linear_input(mut self) -> Self {
+        self.decode = false;
+        self
+    }
+
+    /// Output stays linear, and isn't encoded
+    #[inline]
+    #[must_use]
+    pub fn linear_output(mut self) -> Self {
+        self.encode = false;
+        self
+    }
+
+    /// Leave the last component unchanged, even if the format doesn't know it's alpha, e.g. [`Channels`](crate::formats::Channels)
+    #[inline]
+    #[must_use]
+    pub fn keep_alpha(mut self) -> Self {
+        self.keep_alpha = Some(true);
+        self
+    }
+
+    /// Decode and encode alpha too, like color components
+    #[inline]
+    #[must_use]
+    pub fn include_alpha(mut self) -> Self {
+        self.keep_alpha = Some(false);
+        self
+    }
+
+    #[inline]
+    fn encode<A: Components>(&self, acc: &mut A) {
+        if self.encode {
+            // Negative lobes can undershoot below 0, which has no sRGB encoding
+            self.map_color(acc, |c| linear_to_srgb(c.max(0.)));
+        }
+    }
+
+    #[inline]
+    fn map_color<A: Components>(&self, px: &mut A, f: impl Fn(f64) -> f64) {
+        let components = px.components_mut();
+        let len = components.len() - usize::from(self.keep_alpha.unwrap_or(A::ALPHA));
+        for c in &mut components[..len] {
+            *c = (f((*c / self.max) as f64) as f32) * self.max;
+        }
+    }
+}
+
+#[inline]
+fn srgb_to_linear(c: f64) -> f64 {
+    if c <= 0.04045 { c / 12.92 } else { unsafe { pow((c + 0.055) / 1.055, 2.4) } }
+}
+
+#[inline]
+fn linear_to_srgb(c: f64) -> f64 {
+    if c <= 0.003_130_8 { c * 12.92 } else { 1.055 * unsafe { pow(c, 1. / 2.4) } - 0.055 }
+}
+
+impl<Format: PixelFormat> PixelFormat for Gamma<Format>
+where Format::Accumulator: Components {
+    type InputPixel = Format::InputPixel;
+    type OutputPixel = Format::OutputPixel;
+    type Accumulator = Format::Accumulator;
+
+    #[inline(always)]
+    fn new() -> Self::Accumulator {
+        Format::new()
+    }
+
+    #[inline(always)]
+    fn add(&self, acc: &mut Self::Accumulator, inp: Self::InputPixel, coeff: f32) {
+        if !self.decode {
+            return self.format.add(acc, inp, coeff);
+        }
+        let mut px = Format::new();
+        self.format.add(&mut px, inp, 1.);
+        self.map_color(&mut px, srgb_to_linear);
+        Format::add_acc(acc, px, coeff);
+    }
+
+    #[inline(always)]
+    fn add_acc(acc: &mut Self::Accumulator, inp: Self::Accumulator, coeff: f32) {
+        Format::add_acc(acc, inp, coeff)
+    }
+
+    #[inline(always)]
+    fn into_pixel(&self, mut acc: Self::Accumulator) -> Self::OutputPixel {
+        self.encode(&mut acc);
+        self.format.into_pixel(acc)
+    }
+
+    #[inline(always)]
+    fn write_pixel(&self, out: &mut Self::OutputPixel, mut acc: Self::Accumulator, x: usize, y: usize) {
+        self.encode(&mut acc);
+        self.format.write_pixel(out, acc, x, y)
+    }
+}
+
+/// Filters in a sigmoidal (S-curve) space, like ImageMagick's `+sigmoidal-contrast` resizing.
+///
+/// Contrast of the input is flattened with an inverse sigmoid before filtering, and restored afterwards.
+/// This greatly reduces halos of sharp filters around high-contrast edges when downscaling, and keeps the contrast of fine detail.
+///
+/// `contrast` is the steepness of the curve and must be positive, ImageMagick suggests 6.5 for Lanczos.
+/// `max` is the full intensity in the format's units, like in [`Gamma`]. Wrap a [`Gamma`] format to use the sigmoid in linear light.
+///
+/// ```
+/// use resize::{px::{Gamma, Sigmoidal}, Pixel::RGB8};
+/// let format = Sigmoidal::new(Gamma::new(RGB8, 255.), 255., 6.5);
+/// # resize::new(1, 1, 1, 1, format, resize::Type::Lanczos3).unwrap();
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct Sigmoidal<Format> {
+    format: Format,
+    max: f32,
+    contrast: f64,
+    midpoint: f64,
+    keep_alpha: bool,
+}
+
+impl<Format> Sigmoidal<Format> {
+    /// Use a sigmoid of the given `contrast` centered at half of `max`
+    #[inline]
+    pub fn new(format: Format, max: f32, contrast: f32) -> Self {
+        Self { format, max, contrast: contrast as f64, midpoint: 0.5, keep_alpha: false }
+    }
+
+    /// Center of the curve, as a fraction of the full intensity. The default is 0.5.
+    #[inline]
+    #[must_use]
+    pub fn midpoint(mut self, midpoint: f32) -> Self {
+        self.midpoint = midpoint as f64;
+        self
+    }
+
+    /// Leave the last component unchanged, for formats with alpha
+    #[inline]
+    #[must_use]
+    pub fn keep_alpha(mut self) -> Self {
+        self.keep_alpha = true;
+        self
+    }
+
+    #[inline]
+    fn sigmoid(&self, x: f64) -> f64 {
+        1. / (1. + unsafe { exp(self.contrast * (self.midpoint - x)) })
+    }
+
+    /// Increases contrast, mapping 0 and 1 to themselves
+    #[inline]
+    fn contrast_up(&self, x: f64) -> f64 {
+        let (low, high) = (self.sigmoid(0.), self.sigmoid(1.));
+        (self.sigmoid(x) - low) / (high - low)
+    }
+
+    /// Inverse of `contrast_up`, defined only for 0..=1
+    #[inline]
+    fn contrast_down(&self, x: f64) -> f64 {
+        let (low, high) = (self.sigmoid(0.), self.sigmoid(1.));
+        let y = x.clamp(0., 1.) * (high - low) + low;
+        self.midpoint - unsafe { log(1. / y - 1.) } / self.contrast
+    }
+
+    #[inline]
+    fn map_color<A: Components>(&self, px: &mut A, f: impl Fn(f64) -> f64) {
+        let components = px.components_mut();
+        let len = components.len() - usize::from(self.keep_alpha);
+        for c in &mut components[..len] {
+            *c = (f((*c / self.max) as f64) as f32) * self.max;
+        }
+    }
+}
+
+impl<Format: PixelFormat> PixelFormat for Sigmoidal<Format>
+where Format::Accumulator: Components {
+    type InputPixel = Format::InputPixel;
+    type OutputPixel = Format::OutputPixel;
+    type Accumulator = Format::Accumulator;
+
+    #[inline(always)]
+    fn new() -> Self::Accumulator {
+        Format::new()
+    }
+
+    #[inline(always)]
+    fn add(&self, acc: &mut Self::Accumulator, inp: Self::InputPixel, coeff: f32) {
+        let mut px = Format::new();
+        self.format.add(&mut px, inp, 1.);
+        self.map_color(&mut px, |c| self.contrast_down(c));
+        Format::add_acc(acc, px, coeff);
+    }
+
+    #[inline(always)]
+    fn add_acc(acc: &mut Self::Accumulator, inp: Self::Accumulator, coeff: f32) {
+        Format::add_acc(acc, inp, coeff)
+    }
+
+    #[inline(always)]
+    fn into_pixel(&self, mut acc: Self::Accumulator) -> Self::OutputPixel {
+        self.map_color(&mut acc, |c| self.contrast_up(c));
+        self.format.into_pixel(acc)
+    }
+
+    #[inline(always)]
+    fn write_pixel(&self, out: &mut Self::OutputPixel, mut acc: Self::Accumulator, x: usize, y: usize) {
+        self.map_color(&mut acc, |c| self.contrast_up(c));
+        self.format.write_pixel(out, acc, x, y)
+    }
+}
+
+/// Maps HDR values into the displayable range with an exposure multiplier and a tone curve, instead of clipping them.
+///
+/// Use it with float input and integer output, e.g. for 8-bit previews of f32 HDR images.
+/// The curve takes and returns values where 1.0 is the full intensity. Output stays linear.
+///
+/// ```
+/// use resize::{formats::Rgb, px::ToneMap};
+/// let format = ToneMap::aces(Rgb::<f32, u8>::instance(), 2.);
+/// # resize::new(1, 1, 1, 1, format, resize::Type::Lanczos3).unwrap();
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct ToneMap<Format> {
+    format: Format,
+    exposure: f32,
+    curve: fn(f32) -> f32,
+    keep_alpha: bool,
+}
+
+impl<Format> ToneMap<Format> {
+    /// Multiply components by `exposure`, and then apply `curve` to them
+    #[inline]
+    pub fn new(format: Format, exposure: f32, curve: fn(f32) -> f32) -> Self {
+        Self { format, exposure, curve, keep_alpha: false }
+    }
+
+    /// Reinhard curve, `x / (1 + x)`, which compresses highlights gently and never reaches 1.0
+    #[inline]
+    pub fn reinhard(format: Format, exposure: f32) -> Self {
+        Self::new(format, exposure, |x| x / (1. + x))
+    }
+
+    /// Filmic curve approximating the ACES reference rendering transform, by Krzysztof Narkowicz
+    #[inline]
+    pub fn aces(format: Format, exposure: f32) -> Self {
+        Self::new(format, exposure, |x| (x * (2.51 * x + 0.03) / (x * (2.43 * x + 0.59) + 0.14)).clamp(0., 1.))
+    }
+
+    /// Leave the last component unchanged, for formats with alpha
+    #[inline]
+    #[must_use]
+    pub fn keep_alpha(mut self) -> Self {
+        self.keep_alpha = true;
+        self
+    }
+
+    #[inline]
+    fn map<A: Components>(&self, acc: &mut A) {
+        let components = acc.components_mut();
+        let len = components.len() - usize::from(self.keep_alpha);
+        for c in &mut components[..len] {
+            *c = (self.curve)(*c * self.exposure);
+        }
+    }
+}
+
+impl<Format: PixelFormat> PixelFormat for ToneMap<Format>
+where Format::Accumulator: Components {
+    type InputPixel = Format::InputPixel;
+    type OutputPixel = Format::OutputPixel;
+    type Accumulator = Format::Accumulator;
+
+    #[inline(always)]
+    fn new() -> Self::Accumulator {
+        Format::new()
+    }
+
+    #[inline(always)]
+    fn add(&self, acc: &mut Self::Accumulator, inp: Self::InputPixel, coeff: f32) {
+        self.format.add(acc, inp, coeff)
+    }
+
+    #[inline(always)]
+    fn add_acc(acc: &mut Self::Accumulator, inp: Self::Accumulator, coeff: f32) {
+        Format::add_acc(acc, inp, coeff)
+    }
+
+    #[inline(always)]
+    fn into_pixel(&self, mut acc: Self::Accumulator) -> Self::OutputPixel {
+        self.map(&mut acc);
+        self.format.into_pixel(acc)
+    }
+
+    #[inline(always)]
+    fn write_pixel(&self, out: &mut Self::OutputPixel, mut acc: Self::Accumulator, x: usize, y: usize) {
+        self.map(&mut acc);
+        self.format.write_pixel(out, acc, x, y)
+    }
+}
+
+/// Ignores invalid source pixels, such as nodata holes in depth maps and rasters,
+/// instead of smearing them into their neighbors.
+///
+/// Weights of the remaining pixels are renormalized for every output pixel. Output pixels that have
+/// no valid pixels under their filter footprint have all components set to NaN, which is 0 for integer outputs.
+///
+/// ```
+/// use resize::px::Masked;
+/// use rgb::FromSlice;
+/// let src = [2., f32::NAN, 2., 6.];
+/// let mut dst = [0.; 2];
+/// let format = Masked::nan(resize::Pixel::GrayF32);
+/// resize::new(4, 1, 2, 1, format, resize::Type::Triangle)?.resize(src.as_gray(), dst.as_gray_mut())?;
+/// assert!((dst[0] - 2.).abs() < 1e-6);
+/// # Ok::<_, resize::Error>(())
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct Masked<Format: PixelFormat> {
+    format: Format,
+    valid: fn(Format::InputPixel) -> bool,
+}
+
+impl<Format: PixelFormat> Masked<Format> {
+    /// Skip pixels for which `valid` returns `false`, e.g. ones equal to a sentinel value
+    #[inline]
+    pub fn new(format: Format, valid: fn(Format::InputPixel) -> bool) -> Self {
+        Self { format, valid }
+    }
+}
+
+impl<Format: PixelFormat> Masked<Format>
+where Format::InputPixel: Components {
+    /// Skip pixels that have any NaN component
+    #[inline]
+    pub fn nan(format: Format) -> Self {
+        Self::new(format, |px| !px.components().iter().any(|c| c.is_nan()))
+    }
+}
+
+impl<Format: PixelFormat> PixelFormat for Masked<Format>
+where Format::Accumulator: Components {
+    type InputPixel = Format::InputPixel;
+    type OutputPixel = Format::OutputPixel;
+    /// Value, and the total weight of valid pixels
+    type Accumulator = (Format::Accumulator, f32);
+
+    #[inline(always)]
+    fn new() -> Self::Accumulator {
+        (Format::new(), 0.)
+    }
+
+    #[inline(always)]
+    fn add(&self, acc: &mut Self::Accumulator, inp: Self::InputPixel, coeff: f32) {
+        if (self.valid)(inp) {
+            self.format.add(&mut acc.0, inp, coeff);
+            acc.1 += coeff;
+        }
+    }
+
+    #[inline(always)]
+    fn add_acc(acc: &mut Self::Accumulator, inp: Self::Accumulator, coeff: f32) {
+        Format::add_acc(&mut acc.0, inp.0, coeff);
+        acc.1 += inp.1 * coeff;
+    }
+
+    #[inline(always)]
+    fn into_pixel(&self, (px, weight): Self::Accumulator) -> Self::OutputPixel {
+        self.format.into_pixel(normalize(px, weight))
+    }
+
+    #[inline(always)]
+    fn write_pixel(&self, out: &mut Self::OutputPixel, (px, weight): Self::Accumulator, x: usize, y: usize) {
+        self.format.write_pixel(out, normalize(px, weight), x, y)
+    }
+}
+
+/// Divide by the total weight of valid pixels
+#[inline]
+fn normalize<A: Components>(mut px: A, weight: f32) -> A {
+    // Valid pixels that only have tiny or negative weights would blow up
+    let scale = if weight > 1e-3 { 1. / weight } else { f32::NAN };
+    px.components_mut().iter_mut().for_each(|c| *c *= scale);
+    px
+}
+
+#[inline(always)]
+fn extend_range<A: Components>(lo: &mut A, hi: &mut A, px: &A) {
+    for ((lo, hi), &c) in lo.components_mut().iter_mut().zip(hi.components_mut().iter_mut()).zip(px.components()) {
+        *lo = lo.min(c);
+        *hi = hi.max(c);
+    }
+}
+
+#[inline(always)]
+fn clamp_to_range<A: Components>(px: &mut A, lo: &A, hi: &A) {
+    for ((c, &lo), &hi) in px.components_mut().iter_mut().zip(lo.components()).zip(hi.components()) {
+        if lo <= hi {
+            *c = c.max(lo).min(hi);
+        }
+    }
+}
+
+pub use self::f::ToFloat;
+use self::f::{convert, convert_f64};
+
+/// Copies pixels as they are if `P` and `Q` are the same type, which is what converting them would give
+#[inline(always)]
+fn copy_same<P: Copy + 'static, Q: Copy + 'static>(src: &[P], dst: &mut [Q]) -> bool {
+    if core::any::TypeId::of::<P>() != core::any::TypeId::of::<Q>() {
+        return false;
+    }
+    // Safety: both slices have elements of the same type
+    let src = unsafe { core::slice::from_raw_parts(src.as_ptr().cast::<Q>(), src.len()) };
+    dst.copy_from_slice(src);
+    true
+}
+mod f {
+    use crate::round;
+
+    /// Type of components of pixels, which makes formats like [`formats::Rgb`](crate::formats::Rgb) usable with it.
+    ///
+    /// Implement it for your own component types, e.g. fixed-point numbers:
+    ///
+    /// ```
+    /// use resize::{formats, px::{ToFloat, RGB}};
+    ///
+    /// /// Percentage from 0 to 100
+    /// #[derive(Debug, Copy, Clone, PartialEq)]
+    /// struct Percent(u8);
+    ///
+    /// impl ToFloat for Percent {
+    ///     const MAX: f32 = 100.;
+    ///     fn to_float(self) -> f32 { self.0 as f32 }
+    ///     fn from_float(f: f32) -> Self { Percent(f.round().max(0.).min(100.) as u8) }
+    /// }
+    ///
+    /// // Converted to 8-bit, where 100% is 255
+    /// let format = formats::Rgb::<Percent, u8>::default();
+    /// let mut r = resize::new(1, 1, 2, 1, format, resize::Type::Triangle)?;
+    /// let mut dst = [RGB::new(0, 0, 0); 2];
+    /// r.resize(&[RGB::new(Percent(0), Percent(50), Percent(100))], &mut dst)?;
+    /// assert_eq!(dst[0], RGB::new(0, 128, 255));
+    /// # Ok::<_, resize::Error>(())
+    /// ```
+    pub trait ToFloat: Sized + Copy + 'static {
+        /// Value of full intensity, e.g. 255 for `u8` and 1 for `f32`. Components are converted between types in proportion to it.
+        const MAX: f32;
+        /// The value, in the `0..=MAX` range
+        fn to_float(self) -> f32;
+        /// Value from a float in the `0..=MAX` range, which can be out of range, so it should round and clamp as needed.
+        /// `from_float(x.to_float())` should be `x`, since pixels that aren't resampled are copied without converting them.
+        fn from_float(f: f32) -> Self;
+
+        /// Same as [`ToFloat::to_float`], in double precision
+        #[inline(always)]
+        fn to_f64(self) -> f64 {
+            self.to_float() as f64
+        }
+
+        /// Same as [`ToFloat::from_float`], in double precision
+        #[inline(always)]
+        fn from_f64(f: f64) -> Self {
+            Self::from_float(f as f32)
+        }
+    }
+
+    /// Converts from the range of `F` to the range of `T`
+    #[inline(always)]
+    pub fn convert<F: ToFloat, T: ToFloat>(f: f32) -> T {
+        if F::MAX == T::MAX {
+            T::from_float(f)
+        } else {
+            T::from_float(f * (T::MAX / F::MAX))
+        }
+    }
+
+    /// Same as [`convert`], in double precision
+    #[inline(always)]
+    pub fn convert_f64<F: ToFloat, T: ToFloat>(f: f64) -> T {
+        if F::MAX == T::MAX {
+            T::from_f64(f)
+        } else {
+            T::from_f64(f * (T::MAX as f64 / F::MAX as f64))
+        }
+    }
+
+    impl ToFloat for u8 {
+        const MAX: f32 = 255.;
+
+        #[inline(always)]
+        fn to_float(self) -> f32 {
+            self as f32
+        }
+
+        #[inline(always)]
+        fn from_float(f: f32) -> Self {
+            unsafe {
+                (0f32).max(round(f as f64) as f32).min(255.).to_int_unchecked()
+            }
+        }
+
+        #[inline(always)]
+        fn from_f64(f: f64) -> Self {
+            unsafe {
+                (0f64).max(round(f)).min(255.).to_int_unchecked()
+            }
+        }
+    }
+
+    impl ToFloat for u16 {
+        const MAX: f32 = 65535.;
+
+        #[inline(always)]
+        fn to_float(self) -> f32 {
+            self as f32
+        }
+
+        #[inline(always)]
+        fn from_float(f: f32) -> Self {
+            unsafe {
+                (0f32).max(round(f as f64) as f32).min(65535.).to_int_unchecked()
+            }
+        }
+
+        #[inline(always)]
+        fn from_f64(f: f64) -> Self {
+            unsafe {
+                (0f64).max(round(f)).min(65535.).to_int_unchecked()
+            }
+        }
+    }
+
+    impl ToFloat for i16 {
+        const MAX: f32 = 32767.;
+
+        #[inline(always)]
+        fn to_float(self) -> f32 {
+            self as f32
+        }
+
+        #[inline(always)]
+        fn from_float(f: f32) -> Self {
+            unsafe {
+                (-32768f32).max(round(f as f64) as f32).min(32767.).to_int_unchecked()
+            }
+        }
+
+        #[inline(always)]
+        fn from_f64(f: f64) -> Self {
+            unsafe {
+                (-32768f64).max(round(f)).min(32767.).to_int_unchecked()
+            }
+        }
+    }
+
+    impl ToFloat for u32 {
+        const MAX: f32 = u32::MAX as f32;
+
+        #[inline(always)]
+        fn to_float(self) -> f32 {
+            self as f32
+        }
+
+        #[inline(always)]
+        fn from_float(f: f32) -> Self {
+            Self::from_f64(f as f64)
+        }
+
+        #[inline(always)]
+        fn to_f64(self) -> f64 {
+            self as f64
+        }
+
+        #[inline(always)]
+        fn from_f64(f: f64) -> Self {
+            unsafe {
+                (0f64).max(round(f)).min(u32::MAX as f64).to_int_unchecked()
+            }
+        }
+    }
+
+    impl ToFloat for f32 {
+        const MAX: f32 = 1.;
+
+        #[inline(always)]
+        fn to_float(self) -> f32 {
+            self
+        }
+
+        #[inline(always)]
+        fn from_float(f: f32) -> Self {
+            f
+        }
+    }
+
+    #[cfg(feature = "half")]
+    impl ToFloat for half::f16 {
+        const MAX: f32 = 1.;
+
+        #[inline(always)]
+        fn to_float(self) -> f32 {
+            self.to_f32()
+        }
+
+        #[inline(always)]
+        fn from_float(f: f32) -> Self {
+            half::f16::from_f32(f)
         }
     }
 
     impl ToFloat for f64 {
+        const MAX: f32 = 1.;
+
         #[inline(always)]
         fn to_float(self) -> f32 {
             self as f32
@@ -225,5 +2379,15 @@ mod f {
         fn from_float(f: f32) -> Self {
             f as f64
         }
+
+        #[inline(always)]
+        fn to_f64(self) -> f64 {
+            self
+        }
+
+        #[inline(always)]
+        fn from_f64(f: f64) -> Self {
+            f
+        }
     }
 }
@@ -0,0 +1,148 @@
+use crate::{check_area, CoeffsLine, Error, PixelFormat, Resizer, Result, Scale};
+use core::convert::TryInto;
+use core::num::NonZeroUsize;
+use fallible_collections::FallibleVec;
+use std::vec::Vec;
+
+/// Start of every saved table, followed by the version of the layout
+const MAGIC: [u8; 4] = *b"RSZC";
+const VERSION: u32 = 1;
+
+impl<Format: PixelFormat> Resizer<Format> {
+    /// Save the computed coefficients, so that an identical resizer can be restored with [`Resizer::from_coefficient_bytes`]
+    /// without computing them again, e.g. to skip start-up time of large custom kernels.
+    ///
+    /// The bytes are versioned and portable between platforms, but don't include the pixel format, filter or [`Options`](crate::Options).
+    pub fn coefficients_to_bytes(&self) -> Result<Vec<u8>> {
+        let len = 8 + scale_len(&self.scale) + 1 + self.prescale.as_ref().map_or(0, scale_len);
+        let mut out: Vec<u8> = FallibleVec::try_with_capacity(len)?;
+        out.extend_from_slice(&MAGIC);
+        out.extend_from_slice(&VERSION.to_le_bytes());
+        write_scale(&mut out, &self.scale);
+        match &self.prescale {
+            Some(prescale) => {
+                out.push(1);
+                write_scale(&mut out, prescale);
+            },
+            None => out.push(0),
+        }
+        Ok(out)
+    }
+
+    /// Restore a resizer saved with [`Resizer::coefficients_to_bytes`].
+    ///
+    /// The data is validated, and fails with [`Error::InvalidParameters`] if it's corrupted or from an unknown version.
+    /// Like resizers made with [`Resizer::from_coefficients`], restored ones can't change their filter or dimensions.
+    pub fn from_coefficient_bytes(data: &[u8], pixel_format: Format) -> Result<Self> {
+        let mut reader = Reader(data);
+        if reader.bytes()? != MAGIC || u32::from_le_bytes(reader.bytes()?) != VERSION {
+            return Err(Error::InvalidParameters);
+        }
+        let scale = reader.scale()?;
+        let prescale = match reader.byte()? {
+            0 => None,
+            1 => Some(reader.scale()?),
+            _ => return Err(Error::InvalidParameters),
+        };
+        if !reader.0.is_empty() {
+            return Err(Error::InvalidParameters);
+        }
+        if let Some(prescale) = &prescale {
+            // The box reduction feeds the main scale
+            if prescale.transpose || prescale.w2() != scale.w1.get() || prescale.h2() != scale.h1.get() {
+                return Err(Error::InvalidParameters);
+            }
+        }
+        let mut resizer = Self::with_scale(scale, pixel_format)?;
+        resizer.prescale = prescale;
+        Ok(resizer)
+    }
+}
+
+fn scale_len(scale: &Scale) -> usize {
+    let lines = scale.coeffs_w.iter().chain(&scale.coeffs_h);
+    8 + 8 + 1 + 8 + 8 + lines.map(|line| 8 + 8 + 4 * line.coeffs.len()).sum::<usize>()
+}
+
+fn write_scale(out: &mut Vec<u8>, scale: &Scale) {
+    out.extend_from_slice(&(scale.w1.get() as u64).to_le_bytes());
+    out.extend_from_slice(&(scale.h1.get() as u64).to_le_bytes());
+    out.push(scale.transpose.into());
+    for lines in [&scale.coeffs_w, &scale.coeffs_h] {
+        out.extend_from_slice(&(lines.len() as u64).to_le_bytes());
+        for line in lines {
+            out.extend_from_slice(&(line.start as u64).to_le_bytes());
+            out.extend_from_slice(&(line.coeffs.len() as u64).to_le_bytes());
+            for w in line.coeffs.iter() {
+                out.extend_from_slice(&w.to_le_bytes());
+            }
+        }
+    }
+}
+
+/// Remaining bytes of saved coefficients
+struct Reader<'a>(&'a [u8]);
+
+impl Reader<'_> {
+    fn bytes<const N: usize>(&mut self) -> Result<[u8; N]> {
+        if self.0.len() < N {
+            return Err(Error::InvalidParameters);
+        }
+        let (bytes, rest) = self.0.split_at(N);
+        self.0 = rest;
+        bytes.try_into().map_err(|_| Error::InvalidParameters)
+    }
+
+    fn byte(&mut self) -> Result<u8> {
+        Ok(self.bytes::<1>()?[0])
+    }
+
+    fn size(&mut self) -> Result<usize> {
+        u64::from_le_bytes(self.bytes()?).try_into().map_err(|_| Error::InvalidParameters)
+    }
+
+    fn scale(&mut self) -> Result<Scale> {
+        let w1 = NonZeroUsize::new(self.size()?).ok_or(Error::InvalidParameters)?;
+        let h1 = NonZeroUsize::new(self.size()?).ok_or(Error::InvalidParameters)?;
+        let transpose = match self.byte()? {
+            0 => false,
+            1 => true,
+            _ => return Err(Error::InvalidParameters),
+        };
+        let scale = Scale::from_lines(w1, h1, transpose, self.lines(w1)?, self.lines(h1)?)?;
+        check_area(w1.get(), h1.get())?;
+        check_area(scale.w2(), scale.h2())?;
+        check_area(scale.w2(), h1.get())?;
+        Ok(scale)
+    }
+
+    /// Table of weights for an axis of `source` pixels, checked the same way as [`Resizer::from_coefficients`]
+    fn lines(&mut self, source: NonZeroUsize) -> Result<Vec<CoeffsLine>> {
+        let count = self.size()?;
+        // Every line takes at least 20 bytes, so corrupted counts can't reserve more than the data
+        if count == 0 || count > self.0.len() / 20 {
+            return Err(Error::InvalidParameters);
+        }
+        let mut lines: Vec<CoeffsLine> = FallibleVec::try_with_capacity(count)?;
+        let mut weights: Vec<f32> = Vec::new();
+        for _ in 0..count {
+            let start = self.size()?;
+            let len = self.size()?;
+            let end = start.checked_add(len).ok_or(Error::InvalidParameters)?;
+            if len == 0 || end > source.get() || len > self.0.len() / 4 {
+                return Err(Error::InvalidParameters);
+            }
+            weights.clear();
+            FallibleVec::try_reserve(&mut weights, len)?;
+            for _ in 0..len {
+                let w = f32::from_le_bytes(self.bytes()?);
+                if !w.is_finite() {
+                    return Err(Error::InvalidParameters);
+                }
+                weights.push(w);
+            }
+            lines.push(CoeffsLine { start, coeffs: weights[..].into() });
+        }
+        Ok(lines)
+    }
+}
@@ -0,0 +1,100 @@
+use crate::px::{FromAccumulator, KeepAccumulator};
+use crate::{Error, PixelFormat, Resizer, Result, RowOrder};
+use core::num::NonZeroUsize;
+
+/// Resizing done a few rows at a time, made by [`Resizer::resize_steps`].
+///
+/// Each call to `next` writes at most the given number of rows and returns the number of rows done so far,
+/// out of [`ResizeSteps::total`]. Between the steps the caller can do other work, e.g. yield to an async runtime,
+/// so that resizing large images doesn't block it for long. The destination is complete when the iterator ends.
+///
+/// ```rust
+/// # use rgb::FromSlice;
+/// # let (src, mut dst) = (vec![0u8; 640 * 480 * 3], vec![0u8; 320 * 240 * 3]);
+/// let mut resizer = resize::new(640, 480, 320, 240, resize::Pixel::RGB8, resize::Type::Lanczos3)?;
+/// let steps = resizer.resize_steps(src.as_rgb(), dst.as_rgb_mut(), 16)?;
+/// let total = steps.total();
+/// for done in steps {
+///     println!("{done}/{total}");
+///     // e.g. `tokio::task::yield_now().await;`
+/// }
+/// # Ok::<_, resize::Error>(())
+/// ```
+pub struct ResizeSteps<'a, Format: PixelFormat> {
+    resizer: &'a mut Resizer<Format>,
+    src: &'a [Format::InputPixel],
+    dst: &'a mut [Format::OutputPixel],
+    rows_per_step: NonZeroUsize,
+    /// Rows written by both steps of multi-step resizing
+    done: usize,
+    /// Rows resampled by the horizontal pass of the current step
+    filled: usize,
+}
+
+impl<Format: PixelFormat> core::fmt::Debug for ResizeSteps<'_, Format> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ResizeSteps")
+            .field("done", &self.done)
+            .field("total", &self.total())
+            .finish()
+    }
+}
+
+impl<Format: PixelFormat> Resizer<Format> {
+    /// Resize `src` image data into `dst` in steps of at most `rows_per_step` rows. See [`ResizeSteps`].
+    ///
+    /// Buffers are validated and allocated up front, so the steps themselves can't fail.
+    pub fn resize_steps<'a>(&'a mut self, src: &'a [Format::InputPixel], dst: &'a mut [Format::OutputPixel], rows_per_step: usize) -> Result<ResizeSteps<'a, Format>> {
+        let rows_per_step = NonZeroUsize::new(rows_per_step).ok_or(Error::InvalidParameters)?;
+        self.check_buffers(src.len(), self.source_stride(), dst.len(), self.dest_stride(dst.len())?)?;
+        self.reserve_scratch()?;
+        Ok(ResizeSteps { resizer: self, src, dst, rows_per_step, done: 0, filled: 0 })
+    }
+}
+
+impl<Format: PixelFormat> ResizeSteps<'_, Format> {
+    /// Number of rows when all steps are done. Multi-step resizing counts rows of both steps.
+    #[inline]
+    #[must_use]
+    pub fn total(&self) -> usize {
+        self.resizer.progress_rows()
+    }
+}
+
+impl<Format: PixelFormat> Iterator for ResizeSteps<'_, Format> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let total = self.total();
+        if self.done == total {
+            return None;
+        }
+        let end = total.min(self.done + self.rows_per_step.get());
+        let src_stride = self.resizer.source_stride();
+        let Resizer { scale, prescale, pix_fmt, tmp, .. } = &mut *self.resizer;
+        let order = RowOrder::default();
+        match prescale {
+            Some(prescale) => {
+                let (prescaled, tmp) = tmp.split_at_mut(scale.w1.get() * scale.h1.get());
+                for y in self.done..end {
+                    if y < prescale.h2() {
+                        prescale.resample_row(&KeepAccumulator(&*pix_fmt), tmp, &mut self.filled, self.src, src_stride, prescaled, prescale.w2(), order, 0..prescale.w2(), y);
+                        if y + 1 == prescale.h2() {
+                            self.filled = 0;
+                        }
+                    } else {
+                        let dst_stride = scale.dest_dimensions().0;
+                        scale.resample_row(&FromAccumulator(&*pix_fmt), tmp, &mut self.filled, prescaled, scale.w1, self.dst, dst_stride, order, 0..scale.w2(), y - prescale.h2());
+                    }
+                }
+            },
+            None => {
+                for y in self.done..end {
+                    scale.resample_row(&*pix_fmt, tmp, &mut self.filled, self.src, src_stride, self.dst, scale.dest_dimensions().0, order, 0..scale.w2(), y);
+                }
+            },
+        }
+        self.done = end;
+        Some(end)
+    }
+}
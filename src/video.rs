@@ -0,0 +1,227 @@
+use crate::formats;
+use crate::px::Gray;
+use crate::{ChromaSiting, Error, Options, PixelFormat, Plane, Result, RowOrder, Scale, Type};
+use core::marker::PhantomData;
+use core::num::NonZeroUsize;
+use core::ops::ControlFlow;
+use fallible_collections::FallibleVec;
+use std::vec::Vec;
+
+/// How rows of a video picture were captured, which may change from one picture of a stream to the next.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PictureStructure {
+    /// All rows of the frame were captured at once
+    Progressive,
+    /// The frame interleaves two fields: even rows are the top field, and odd rows the bottom field.
+    /// Top field first and bottom field first frames are scaled the same way.
+    Interlaced,
+    /// The picture is only the top field of a frame, half of the frame's height
+    TopField,
+    /// The picture is only the bottom field of a frame, half of the frame's height
+    BottomField,
+}
+
+/// Destination pixels of a single plane.
+#[derive(Debug)]
+pub struct PlaneMut<'a, Pixel> {
+    /// Pixel data, `stride * (height - 1) + width` pixels at least
+    pub data: &'a mut [Pixel],
+    /// Length of a row in pixels (>= width)
+    pub stride: usize,
+}
+
+impl<'a, Pixel> PlaneMut<'a, Pixel> {
+    /// Describe plane's pixels with rows `stride` pixels apart
+    #[inline]
+    pub fn new(data: &'a mut [Pixel], stride: usize) -> Self {
+        Self { data, stride }
+    }
+}
+
+/// Planes of an 8-bit YUV 4:2:0 source picture, with chroma planes half of the luma size, rounded up
+#[derive(Debug, Copy, Clone)]
+pub enum VideoFrame<'a> {
+    /// Y, U and V planes, in that order
+    I420([Plane<'a, Gray<u8>>; 3]),
+    /// Y plane, and a plane of interleaved U and V samples
+    Nv12(Plane<'a, Gray<u8>>, Plane<'a, [u8; 2]>),
+}
+
+/// Planes of an 8-bit YUV 4:2:0 destination picture, see [`VideoFrame`]
+#[derive(Debug)]
+pub enum VideoFrameMut<'a> {
+    /// Y, U and V planes, in that order
+    I420([PlaneMut<'a, Gray<u8>>; 3]),
+    /// Y plane, and a plane of interleaved U and V samples
+    Nv12(PlaneMut<'a, Gray<u8>>, PlaneMut<'a, [u8; 2]>),
+}
+
+/// Luma and chroma coefficients for one kind of picture
+#[derive(Debug)]
+struct Scales {
+    luma: Scale,
+    chroma: Scale,
+}
+
+/// Scaler for a whole stream of 8-bit YUV 4:2:0 video, e.g. held by an encoder wrapper.
+///
+/// Every picture can have different strides, plane layout ([`VideoFrame`]) and [`PictureStructure`],
+/// as long as the frame size stays the same. Coefficients for each picture structure are computed the first time
+/// it's seen, and reused for the rest of the stream, along with temporary buffers.
+///
+/// Interlaced frames and single fields are scaled one field at a time, keeping fields at their positions
+/// like [`InterlacedResizer`](crate::InterlacedResizer) does. These need frame heights that are multiples of 4,
+/// so that chroma planes have an even number of rows.
+///
+/// ```rust
+/// use resize::{ChromaSiting, Plane, PlaneMut, PictureStructure, Type, VideoFrame, VideoFrameMut, VideoScaler};
+/// use rgb::FromSlice;
+/// let mut scaler = VideoScaler::new(1920, 1080, 1280, 720, ChromaSiting::Left, Type::Catrom)?;
+/// // Rows of the source are padded to 2048 bytes
+/// let (y, uv) = (vec![16u8; 2048 * 1080], vec![[128u8; 2]; 1024 * 540]);
+/// let (mut dst_y, mut dst_uv) = (vec![0u8; 1280 * 720], vec![[0u8; 2]; 640 * 360]);
+/// scaler.scale(
+///     VideoFrame::Nv12(Plane::new(y.as_gray(), 2048), Plane::new(&uv, 1024)),
+///     VideoFrameMut::Nv12(PlaneMut::new(dst_y.as_gray_mut(), 1280), PlaneMut::new(&mut dst_uv, 640)),
+///     PictureStructure::Interlaced,
+/// )?;
+/// # Ok::<_, resize::Error>(())
+/// ```
+pub struct VideoScaler {
+    src_width: usize,
+    src_height: usize,
+    dest_width: usize,
+    dest_height: usize,
+    siting: ChromaSiting,
+    filter: Type,
+    /// Progressive, top field and bottom field coefficients, created on first use
+    scales: [Option<Scales>; 3],
+    tmp_luma: Vec<Gray<f32>>,
+    tmp_chroma: Vec<[f32; 2]>,
+}
+
+#[inline]
+fn chroma_size(luma_size: usize) -> usize {
+    luma_size.div_ceil(2)
+}
+
+impl VideoScaler {
+    /// Create a new scaler for frames with the given luma dimensions.
+    ///
+    /// Coefficients for progressive frames are computed right away, so that invalid dimensions are reported early.
+    pub fn new(src_width: usize, src_height: usize, dest_width: usize, dest_height: usize, siting: ChromaSiting, filter_type: Type) -> Result<Self> {
+        let mut scaler = Self {
+            src_width,
+            src_height,
+            dest_width,
+            dest_height,
+            siting,
+            filter: filter_type,
+            scales: [None, None, None],
+            tmp_luma: Vec::new(),
+            tmp_chroma: Vec::new(),
+        };
+        scaler.scales(PictureStructure::Progressive)?;
+        Ok(scaler)
+    }
+
+    /// Coefficients for pictures of the given structure, where `Interlaced` frames use the top field ones
+    fn scales(&mut self, structure: PictureStructure) -> Result<&Scales> {
+        let (index, field_offset) = match structure {
+            PictureStructure::Progressive => (0, 0.),
+            PictureStructure::Interlaced | PictureStructure::TopField => (1, 1.),
+            PictureStructure::BottomField => (2, -1.),
+        };
+        if self.scales[index].is_none() {
+            let (w1, mut h1, w2, mut h2) = (self.src_width, self.src_height, self.dest_width, self.dest_height);
+            if index != 0 {
+                if !h1.is_multiple_of(4) || !h2.is_multiple_of(4) {
+                    return Err(Error::InvalidParameters);
+                }
+                h1 /= 2;
+                h2 /= 2;
+            }
+            let (cw1, ch1, cw2, ch2) = (chroma_size(w1), chroma_size(h1), chroma_size(w2), chroma_size(h2));
+            // Left-sited chroma is a quarter of a chroma pixel left of the center,
+            // and fields are a quarter of a field row above or below it, which shrinks or grows with the scale
+            let offset_x = match self.siting {
+                ChromaSiting::Center => 0.,
+                ChromaSiting::Left => 0.25 * (1. - cw1 as f64 / cw2 as f64),
+            };
+            let luma_offset_y = field_offset * 0.25 * (1. - h1 as f64 / h2 as f64);
+            let chroma_offset_y = field_offset * 0.25 * (1. - ch1 as f64 / ch2 as f64);
+            let filter = &self.filter;
+            let luma = Scale::with_options(w1, h1, w2, h2, filter, filter, &Options::default().offset(0., luma_offset_y))?;
+            let chroma = Scale::with_options(cw1, ch1, cw2, ch2, filter, filter, &Options::default().offset(offset_x, chroma_offset_y))?;
+            self.scales[index] = Some(Scales { luma, chroma });
+        }
+        self.scales[index].as_ref().ok_or(Error::InvalidParameters)
+    }
+
+    /// Scale `src` picture into `dst`. Both must use the same plane layout.
+    ///
+    /// For [`TopField`](PictureStructure::TopField) and [`BottomField`](PictureStructure::BottomField) pictures,
+    /// `src` and `dst` are single fields, half of the frame heights given to [`VideoScaler::new`].
+    pub fn scale(&mut self, src: VideoFrame<'_>, dst: VideoFrameMut<'_>, structure: PictureStructure) -> Result<()> {
+        // Coefficients of both fields are ready before either field is written
+        if structure == PictureStructure::Interlaced {
+            self.scales(PictureStructure::BottomField)?;
+        }
+        self.scales(structure)?;
+        let Self { scales, tmp_luma, tmp_chroma, .. } = self;
+        let scales = |index: usize| scales[index].as_ref().ok_or(Error::InvalidParameters);
+        // Fields of interlaced frames are every other row, starting at row 0 for the top field and row 1 for the bottom one
+        let (fields, step) = match structure {
+            PictureStructure::Progressive => ([(scales(0)?, 0), (scales(0)?, 0)], 1),
+            PictureStructure::Interlaced => ([(scales(1)?, 0), (scales(2)?, 1)], 2),
+            PictureStructure::TopField => ([(scales(1)?, 0), (scales(1)?, 0)], 1),
+            PictureStructure::BottomField => ([(scales(2)?, 0), (scales(2)?, 0)], 1),
+        };
+        let gray = formats::Gray::<u8, u8>(PhantomData);
+        match (src, dst) {
+            (VideoFrame::I420(src), VideoFrameMut::I420(mut dst)) => {
+                for &(scales, row) in &fields[..step] {
+                    scale_plane(&scales.luma, &gray, tmp_luma, &src[0], &mut dst[0], row, step)?;
+                    scale_plane(&scales.chroma, &gray, tmp_luma, &src[1], &mut dst[1], row, step)?;
+                    scale_plane(&scales.chroma, &gray, tmp_luma, &src[2], &mut dst[2], row, step)?;
+                }
+            },
+            (VideoFrame::Nv12(src_y, src_uv), VideoFrameMut::Nv12(mut dst_y, mut dst_uv)) => {
+                for &(scales, row) in &fields[..step] {
+                    scale_plane(&scales.luma, &gray, tmp_luma, &src_y, &mut dst_y, row, step)?;
+                    scale_plane(&scales.chroma, &formats::Duo::<u8, u8>(PhantomData), tmp_chroma, &src_uv, &mut dst_uv, row, step)?;
+                }
+            },
+            _ => return Err(Error::InvalidParameters),
+        }
+        Ok(())
+    }
+}
+
+impl core::fmt::Debug for VideoScaler {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("VideoScaler")
+            .field("src_width", &self.src_width)
+            .field("src_height", &self.src_height)
+            .field("dest_width", &self.dest_width)
+            .field("dest_height", &self.dest_height)
+            .field("siting", &self.siting)
+            .field("filter", &self.filter.name())
+            .finish()
+    }
+}
+
+/// Scales every `step`th row of the plane, starting at `row`
+fn scale_plane<Format: PixelFormat>(scale: &Scale, pix_fmt: &Format, tmp: &mut Vec<Format::Accumulator>, src: &Plane<'_, Format::InputPixel>, dst: &mut PlaneMut<'_, Format::OutputPixel>, row: usize, step: usize) -> Result<()> {
+    let src_stride = NonZeroUsize::new(src.stride.checked_mul(step).ok_or(Error::InvalidParameters)?).ok_or(Error::InvalidParameters)?;
+    let dst_stride = NonZeroUsize::new(dst.stride.checked_mul(step).ok_or(Error::InvalidParameters)?).ok_or(Error::InvalidParameters)?;
+    let src_data = src.data.get(src.stride.checked_mul(row).ok_or(Error::InvalidParameters)?..).ok_or(Error::InvalidParameters)?;
+    let dst_data = dst.data.get_mut(dst.stride.checked_mul(row).ok_or(Error::InvalidParameters)?..).ok_or(Error::InvalidParameters)?;
+    scale.check_strided_buffers(src_data.len(), src_stride, dst_data.len(), dst_stride)?;
+    let tmp_len = scale.tmp_len();
+    if let Some(missing) = tmp_len.checked_sub(tmp.len()).filter(|&n| n > 0) {
+        FallibleVec::try_reserve(tmp, missing)?;
+        tmp.resize(tmp_len, Format::new());
+    }
+    scale.resample_into(pix_fmt, tmp, src_data, src_stride, dst_data, dst_stride.get(), RowOrder::default(), &mut |_| ControlFlow::Continue(())).map(drop)
+}
@@ -0,0 +1,114 @@
+use crate::px::KeepAccumulator;
+use crate::{Error, PixelFormat, Result, RowOrder, Scale, Type};
+use core::num::NonZeroUsize;
+use core::ops::ControlFlow;
+use fallible_collections::FallibleVec;
+use std::vec::Vec;
+
+/// Resampler for 3D volumes, e.g. medical scans or simulation grids, made of `depth` slices of `width`×`height` voxels.
+///
+/// Voxels are stored slice by slice, and row by row within a slice. Each slice is resampled in 2D first,
+/// and then the slices are combined along the depth axis. The same filter is used for all three axes.
+///
+/// ```rust
+/// use resize::{Pixel::GrayF32, Type::Triangle, VolumeResizer};
+/// use rgb::FromSlice;
+/// let scan = vec![0f32; 256 * 256 * 128];
+/// let mut preview = vec![0f32; 64 * 64 * 32];
+/// let mut resizer = VolumeResizer::new((256, 256, 128), (64, 64, 32), GrayF32, Triangle)?;
+/// resizer.resize(scan.as_gray(), preview.as_gray_mut())?;
+/// # Ok::<_, resize::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct VolumeResizer<Format: PixelFormat> {
+    slice: Scale,
+    /// Only the horizontal axis is used
+    depth: Scale,
+    pix_fmt: Format,
+    tmp: Vec<Format::Accumulator>,
+    /// Resampled source slices
+    slices: Vec<Format::Accumulator>,
+}
+
+/// Distances between voxels of a volume, in voxels
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct VolumeStrides {
+    /// Distance between the starts of rows (>= width)
+    pub row: usize,
+    /// Distance between the starts of slices (>= row × height)
+    pub slice: usize,
+}
+
+impl<Format: PixelFormat> VolumeResizer<Format> {
+    /// Create a resizer of volumes from `(width, height, depth)` of `source` to that of `dest`.
+    pub fn new(source: (usize, usize, usize), dest: (usize, usize, usize), pixel_format: Format, filter_type: Type) -> Result<Self> {
+        Ok(Self {
+            slice: Scale::new(source.0, source.1, dest.0, dest.1, &filter_type)?,
+            depth: Scale::new(source.2, 1, dest.2, 1, &filter_type)?,
+            pix_fmt: pixel_format,
+            tmp: Vec::new(),
+            slices: Vec::new(),
+        })
+    }
+
+    /// Resize contiguous `src` volume into `dst`.
+    #[inline]
+    pub fn resize(&mut self, src: &[Format::InputPixel], dst: &mut [Format::OutputPixel]) -> Result<()> {
+        let (w1, h1) = (self.slice.w1.get(), self.slice.h1.get());
+        let (w2, h2) = (self.slice.w2(), self.slice.h2());
+        self.resize_stride(src, VolumeStrides { row: w1, slice: w1 * h1 }, dst, VolumeStrides { row: w2, slice: w2 * h2 })
+    }
+
+    /// Resize `src` volume into `dst`, with rows and slices the given distances apart.
+    pub fn resize_stride(&mut self, src: &[Format::InputPixel], src_strides: VolumeStrides, dst: &mut [Format::OutputPixel], dst_strides: VolumeStrides) -> Result<()> {
+        let (w1, h1, d1) = (self.slice.w1.get(), self.slice.h1.get(), self.depth.w1.get());
+        let (w2, h2, d2) = (self.slice.w2(), self.slice.h2(), self.depth.w2());
+        check_volume(src.len(), (w1, h1, d1), src_strides)?;
+        check_volume(dst.len(), (w2, h2, d2), dst_strides)?;
+        let src_row = NonZeroUsize::new(src_strides.row).ok_or(Error::InvalidParameters)?;
+
+        let slice_len = w2 * h2;
+        let slices_len = slice_len.checked_mul(d1).ok_or(Error::InvalidParameters)?;
+        let tmp_len = self.slice.tmp_len();
+        for (buf, len) in [(&mut self.tmp, tmp_len), (&mut self.slices, slices_len)] {
+            if let Some(missing) = len.checked_sub(buf.len()).filter(|&n| n > 0) {
+                FallibleVec::try_reserve(buf, missing)?;
+                buf.resize(len, Format::new());
+            }
+        }
+
+        // Each source slice in 2D
+        let keep = KeepAccumulator(&self.pix_fmt);
+        for (z, slice) in self.slices[..slices_len].chunks_exact_mut(slice_len).enumerate() {
+            let src = &src[z * src_strides.slice..];
+            self.slice.resample_into(&keep, &mut self.tmp, src, src_row, slice, w2, RowOrder::default(), &mut |_| ControlFlow::Continue(())).map(drop)?;
+        }
+
+        // Slices combined along the depth
+        for (z, line) in self.depth.coeffs_w.iter().enumerate() {
+            let slices = &self.slices[line.start * slice_len..];
+            for y in 0..h2 {
+                let dst_row = &mut dst[z * dst_strides.slice + y * dst_strides.row..][..w2];
+                for (x, dst_px) in dst_row.iter_mut().enumerate() {
+                    let mut accum = Format::new();
+                    for (&coeff, slice) in line.coeffs.iter().zip(slices.chunks_exact(slice_len)) {
+                        Format::add_acc(&mut accum, slice[y * w2 + x], coeff);
+                    }
+                    // Slices are stacked like rows of one tall image
+                    self.pix_fmt.write_pixel(dst_px, accum, x, z * h2 + y);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Checks that a buffer of `len` voxels holds the whole volume
+fn check_volume(len: usize, (width, height, depth): (usize, usize, usize), strides: VolumeStrides) -> Result<()> {
+    let slice_len = strides.row.checked_mul(height - 1).and_then(|n| n.checked_add(width));
+    let volume_len = strides.slice.checked_mul(depth - 1).and_then(|n| n.checked_add(slice_len?));
+    match (slice_len, volume_len) {
+        (Some(slice_len), Some(volume_len)) if strides.row >= width && strides.slice >= slice_len && len >= volume_len => Ok(()),
+        _ => Err(Error::InvalidParameters),
+    }
+}
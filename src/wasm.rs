@@ -0,0 +1,122 @@
+//! JavaScript API, enabled with the `wasm` feature.
+//!
+//! ```js
+//! import { Resizer, PixelFormat, Filter } from "resize";
+//! const resizer = new Resizer(640, 480, 320, 240, PixelFormat.RGBA8, Filter.Lanczos3);
+//! const thumbnail = resizer.resize(imageData.data); // Uint8Array of 320×240 RGBA pixels
+//! ```
+use crate::{Error, Resizer as RustResizer, Result, Type};
+use core::mem::{align_of, size_of};
+use fallible_collections::FallibleVec;
+use std::boxed::Box;
+use std::vec::Vec;
+use wasm_bindgen::prelude::*;
+
+/// Layout of pixels in the byte arrays
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum PixelFormat {
+    Gray8,
+    GrayAlpha8,
+    RGB8,
+    RGBA8,
+    RGBA8P,
+    BGR8,
+    BGRA8,
+}
+
+/// Resampling filter, see [`Type`]
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum Filter {
+    Point,
+    Triangle,
+    Catrom,
+    Mitchell,
+    Lanczos3,
+    Area,
+}
+
+/// Resizer of 8-bit images stored in `Uint8Array`s
+#[wasm_bindgen]
+pub struct Resizer {
+    inner: Box<dyn Run>,
+    dest_len: usize,
+}
+
+trait Run {
+    fn run(&mut self, src: &[u8], dst: &mut [u8]) -> Result<()>;
+}
+
+/// Reinterprets bytes as pixels made of bytes
+fn cast<P>(bytes: &[u8]) -> Result<&[P]> {
+    if size_of::<P>() == 0 || align_of::<P>() != 1 || !bytes.len().is_multiple_of(size_of::<P>()) {
+        return Err(Error::InvalidParameters);
+    }
+    // Safety: pixels of the 8-bit formats are plain structs of `u8`s
+    Ok(unsafe { core::slice::from_raw_parts(bytes.as_ptr().cast(), bytes.len() / size_of::<P>()) })
+}
+
+fn cast_mut<P>(bytes: &mut [u8]) -> Result<&mut [P]> {
+    if size_of::<P>() == 0 || align_of::<P>() != 1 || !bytes.len().is_multiple_of(size_of::<P>()) {
+        return Err(Error::InvalidParameters);
+    }
+    Ok(unsafe { core::slice::from_raw_parts_mut(bytes.as_mut_ptr().cast(), bytes.len() / size_of::<P>()) })
+}
+
+impl<F: crate::PixelFormat> Run for RustResizer<F> {
+    fn run(&mut self, src: &[u8], dst: &mut [u8]) -> Result<()> {
+        self.resize(cast(src)?, cast_mut(dst)?)
+    }
+}
+
+fn boxed<F: crate::PixelFormat + 'static>(w1: usize, h1: usize, w2: usize, h2: usize, pixel_format: F, filter: Type) -> Result<(Box<dyn Run>, usize)> {
+    let bytes = size_of::<F::OutputPixel>();
+    let len = w2.checked_mul(h2).and_then(|n| n.checked_mul(bytes)).ok_or(Error::InvalidParameters)?;
+    Ok((Box::new(RustResizer::new(w1, h1, w2, h2, pixel_format, filter)?), len))
+}
+
+#[wasm_bindgen]
+impl Resizer {
+    /// Create a resizer from `w1`×`h1` to `w2`×`h2` pixels
+    #[wasm_bindgen(constructor)]
+    pub fn new(w1: usize, h1: usize, w2: usize, h2: usize, format: PixelFormat, filter: Filter) -> core::result::Result<Resizer, JsError> {
+        use crate::Pixel;
+        let filter = match filter {
+            Filter::Point => Type::Point,
+            Filter::Triangle => Type::Triangle,
+            Filter::Catrom => Type::Catrom,
+            Filter::Mitchell => Type::Mitchell,
+            Filter::Lanczos3 => Type::Lanczos3,
+            Filter::Area => Type::Area,
+        };
+        let (inner, dest_len) = match format {
+            PixelFormat::Gray8 => boxed(w1, h1, w2, h2, Pixel::Gray8, filter),
+            PixelFormat::GrayAlpha8 => boxed(w1, h1, w2, h2, Pixel::GrayAlpha8, filter),
+            PixelFormat::RGB8 => boxed(w1, h1, w2, h2, Pixel::RGB8, filter),
+            PixelFormat::RGBA8 => boxed(w1, h1, w2, h2, Pixel::RGBA8, filter),
+            PixelFormat::RGBA8P => boxed(w1, h1, w2, h2, Pixel::RGBA8P, filter),
+            PixelFormat::BGR8 => boxed(w1, h1, w2, h2, Pixel::BGR8, filter),
+            PixelFormat::BGRA8 => boxed(w1, h1, w2, h2, Pixel::BGRA8, filter),
+        }.map_err(js_error)?;
+        Ok(Self { inner, dest_len })
+    }
+
+    /// Resize pixels of the source image, and return pixels of the destination image
+    pub fn resize(&mut self, src: &[u8]) -> core::result::Result<Vec<u8>, JsError> {
+        let mut dst = Vec::new();
+        FallibleVec::try_reserve(&mut dst, self.dest_len).map_err(|e| js_error(e.into()))?;
+        dst.resize(self.dest_len, 0);
+        self.inner.run(src, &mut dst).map_err(js_error)?;
+        Ok(dst)
+    }
+}
+
+fn js_error(err: Error) -> JsError {
+    JsError::new(match err {
+        Error::OutOfMemory => "out of memory",
+        Error::InvalidParameters => "invalid parameters",
+    })
+}
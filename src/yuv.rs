@@ -0,0 +1,168 @@
+use crate::formats;
+use crate::px::Gray;
+use crate::{Error, Options, PixelFormat, Plane, Result, Scale, Type};
+use core::marker::PhantomData;
+use core::num::NonZeroUsize;
+use std::vec::Vec;
+
+/// Horizontal position of chroma samples relative to luma samples in 4:2:0 images.
+///
+/// Vertically chroma is always assumed to lie halfway between two luma rows.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ChromaSiting {
+    /// Chroma sample is in the middle of two luma samples (JPEG, MPEG-1)
+    Center,
+    /// Chroma sample is co-sited with the left luma sample (MPEG-2, H.264, HEVC)
+    Left,
+}
+
+/// Resampler for 8-bit YUV 4:2:0 frames, either planar (I420) or semi-planar (NV12).
+///
+/// Chroma planes are half of the luma size, rounded up.
+/// Chroma sample positions are taken into account when computing coefficients,
+/// so that the chroma stays aligned with the luma after resizing.
+#[derive(Debug)]
+pub struct Yuv420Resizer {
+    luma: Scale,
+    chroma: Scale,
+    tmp_luma: Vec<Gray<f32>>,
+    tmp_chroma: Vec<[f32; 2]>,
+}
+
+#[inline]
+fn chroma_size(luma_size: usize) -> usize {
+    luma_size.div_ceil(2)
+}
+
+impl Yuv420Resizer {
+    /// Create a new resizer for frames with the given luma dimensions.
+    pub fn new(src_width: usize, src_height: usize, dest_width: usize, dest_height: usize, siting: ChromaSiting, filter_type: Type) -> Result<Self> {
+        let (cw1, ch1) = (chroma_size(src_width), chroma_size(src_height));
+        let (cw2, ch2) = (chroma_size(dest_width), chroma_size(dest_height));
+        // Left-sited chroma is a quarter of a chroma pixel left of the center,
+        // and that distance shrinks or grows with the scale
+        let offset_x = match siting {
+            ChromaSiting::Center => 0.,
+            ChromaSiting::Left => 0.25 * (1. - cw1 as f64 / cw2 as f64),
+        };
+        Ok(Self {
+            luma: Scale::new(src_width, src_height, dest_width, dest_height, &filter_type)?,
+            chroma: Scale::with_options(cw1, ch1, cw2, ch2, &filter_type, &filter_type, &Options::default().offset(offset_x, 0.))?,
+            tmp_luma: Vec::new(),
+            tmp_chroma: Vec::new(),
+        })
+    }
+
+    /// Resize a planar frame. `src` and `dst` are Y, U and V planes, in that order.
+    pub fn resize_i420(&mut self, src: [Plane<'_, Gray<u8>>; 3], dst: [&mut [Gray<u8>]; 3]) -> Result<()> {
+        let [src_y, src_u, src_v] = src;
+        let [dst_y, dst_u, dst_v] = dst;
+        let pix_fmt = formats::Gray::<u8, u8>(PhantomData);
+        let (y_stride, u_stride, v_stride) = (stride(&src_y)?, stride(&src_u)?, stride(&src_v)?);
+        self.luma.check_buffers(src_y.data.len(), y_stride, dst_y.len())?;
+        self.chroma.check_buffers(src_u.data.len(), u_stride, dst_u.len())?;
+        self.chroma.check_buffers(src_v.data.len(), v_stride, dst_v.len())?;
+        self.luma.resample_both_axes(&pix_fmt, &mut self.tmp_luma, src_y.data, y_stride, dst_y)?;
+        self.chroma.resample_both_axes(&pix_fmt, &mut self.tmp_luma, src_u.data, u_stride, dst_u)?;
+        self.chroma.resample_both_axes(&pix_fmt, &mut self.tmp_luma, src_v.data, v_stride, dst_v)
+    }
+
+    /// Resize a semi-planar frame, with the Y plane followed by a plane of interleaved U and V samples.
+    pub fn resize_nv12(&mut self, src_y: Plane<'_, Gray<u8>>, src_uv: Plane<'_, [u8; 2]>, dst_y: &mut [Gray<u8>], dst_uv: &mut [[u8; 2]]) -> Result<()> {
+        let (y_stride, uv_stride) = (stride(&src_y)?, stride(&src_uv)?);
+        self.luma.check_buffers(src_y.data.len(), y_stride, dst_y.len())?;
+        self.chroma.check_buffers(src_uv.data.len(), uv_stride, dst_uv.len())?;
+        self.luma.resample_both_axes(&formats::Gray::<u8, u8>(PhantomData), &mut self.tmp_luma, src_y.data, y_stride, dst_y)?;
+        self.chroma.resample_both_axes(&formats::Duo::<u8, u8>(PhantomData), &mut self.tmp_chroma, src_uv.data, uv_stride, dst_uv)
+    }
+}
+
+#[inline]
+fn stride<P>(plane: &Plane<'_, P>) -> Result<NonZeroUsize> {
+    NonZeroUsize::new(plane.stride).ok_or(Error::InvalidParameters)
+}
+
+/// Chroma subsampling of a YUV (YCbCr) image
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Subsampling {
+    /// Full resolution chroma
+    Yuv444,
+    /// Chroma at half of the horizontal resolution
+    Yuv422,
+    /// Chroma at half of the horizontal and vertical resolution
+    Yuv420,
+}
+
+impl Subsampling {
+    /// How many luma pixels share a chroma sample horizontally and vertically
+    #[inline]
+    fn factors(self) -> (usize, usize) {
+        match self {
+            Self::Yuv444 => (1, 1),
+            Self::Yuv422 => (2, 1),
+            Self::Yuv420 => (2, 2),
+        }
+    }
+
+    /// Dimensions of chroma planes of an image with the given luma dimensions, rounded up
+    #[inline]
+    #[must_use]
+    pub fn chroma_dimensions(self, luma_width: usize, luma_height: usize) -> (usize, usize) {
+        let (fx, fy) = self.factors();
+        (luma_width.div_ceil(fx), luma_height.div_ceil(fy))
+    }
+}
+
+/// Converts chroma planes between subsamplings, e.g. 4:2:0 to 4:4:4, without changing the size of the image.
+///
+/// Chroma sample positions are taken into account, so that the chroma stays aligned with the luma.
+/// Vertically chroma is always assumed to lie halfway between two luma rows.
+/// Use a soft filter like [`Type::Mitchell`] for downsampling to avoid aliasing, and [`Type::Triangle`] or [`Type::Catrom`]
+/// for upsampling to avoid ringing.
+///
+/// ```rust
+/// use resize::{ChromaResizer, ChromaSiting, Pixel::Gray8, Subsampling, Type::Catrom};
+/// use rgb::FromSlice;
+/// let (u_420, mut u_444) = (vec![128u8; 960 * 540], vec![0u8; 1920 * 1080]);
+/// let mut resizer = ChromaResizer::new(1920, 1080, Subsampling::Yuv420, Subsampling::Yuv444, ChromaSiting::Left, Gray8, Catrom)?;
+/// resizer.resize(u_420.as_gray(), u_444.as_gray_mut())?;
+/// # Ok::<_, resize::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct ChromaResizer<Format: PixelFormat> {
+    scale: Scale,
+    pix_fmt: Format,
+    tmp: Vec<Format::Accumulator>,
+}
+
+impl<Format: PixelFormat> ChromaResizer<Format> {
+    /// Create a resizer of chroma planes of `luma_width`×`luma_height` images from subsampling `from` to `to`.
+    pub fn new(luma_width: usize, luma_height: usize, from: Subsampling, to: Subsampling, siting: ChromaSiting, pixel_format: Format, filter_type: Type) -> Result<Self> {
+        let (w1, h1) = from.chroma_dimensions(luma_width, luma_height);
+        let (w2, h2) = to.chroma_dimensions(luma_width, luma_height);
+        let (f1, f2) = (from.factors().0 as f64, to.factors().0 as f64);
+        // Left-sited chroma is at the first luma sample it covers, instead of the middle of them
+        let offset_x = match siting {
+            ChromaSiting::Center => 0.,
+            ChromaSiting::Left => (f1 - f2) / (2. * f1),
+        };
+        Ok(Self {
+            scale: Scale::with_options(w1, h1, w2, h2, &filter_type, &filter_type, &Options::default().offset(offset_x, 0.))?,
+            pix_fmt: pixel_format,
+            tmp: Vec::new(),
+        })
+    }
+
+    /// Resize `src` chroma plane into `dst`.
+    #[inline]
+    pub fn resize(&mut self, src: &[Format::InputPixel], dst: &mut [Format::OutputPixel]) -> Result<()> {
+        self.resize_stride(src, self.scale.w1.get(), dst)
+    }
+
+    /// Resize `src` chroma plane into `dst`, skipping `stride` pixels each row.
+    pub fn resize_stride(&mut self, src: &[Format::InputPixel], src_stride: usize, dst: &mut [Format::OutputPixel]) -> Result<()> {
+        let src_stride = NonZeroUsize::new(src_stride).ok_or(Error::InvalidParameters)?;
+        self.scale.check_buffers(src.len(), src_stride, dst.len())?;
+        self.scale.resample_both_axes(&self.pix_fmt, &mut self.tmp, src, src_stride, dst)
+    }
+}
@@ -0,0 +1,32 @@
+#![cfg(feature = "cli")]
+
+use std::fs::File;
+use std::path::Path;
+use std::process::Command;
+
+fn run(args: &[&str]) -> bool {
+    Command::new(env!("CARGO_BIN_EXE_resize-cli")).args(args).status().unwrap().success()
+}
+
+fn png_size(path: &Path) -> (u32, u32) {
+    let (info, _) = png::Decoder::new(File::open(path).unwrap()).read_info().unwrap();
+    (info.width, info.height)
+}
+
+#[test]
+fn resize_png() {
+    let input = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples/tiger.png");
+    let (w1, h1) = png_size(&input);
+    let output = Path::new(env!("CARGO_TARGET_TMPDIR")).join("cli-tiger.png");
+    let _ = std::fs::remove_file(&output);
+
+    assert!(run(&[input.to_str().unwrap(), "100x50", output.to_str().unwrap()]));
+    assert_eq!((100, 50), png_size(&output));
+
+    assert!(run(&["--filter", "cubic:0.33,0.33", input.to_str().unwrap(), "200x", output.to_str().unwrap()]));
+    assert_eq!((200, (h1 * 200 + w1 / 2) / w1), png_size(&output));
+
+    assert!(!run(&["--filter", "sinc", input.to_str().unwrap(), "100x50", output.to_str().unwrap()]));
+    assert!(!run(&[input.to_str().unwrap(), "100", output.to_str().unwrap()]));
+    assert!(!run(&["missing.png", "100x50", output.to_str().unwrap()]));
+}
@@ -29,9 +29,9 @@ fn img_diff(a: &[u8], b: &[u8]) -> f64 {
 fn assert_equals(img: &[u8], w2: usize, h2: usize, expected_filename: &str) {
     assert_eq!(img.len(), w2 * h2);
     assert!(w2 > 0 && h2 > 0);
-    let (_, _, expected) = load_png(&fs::read(&expected_filename).expect(expected_filename)).expect(expected_filename);
+    let (_, _, expected) = load_png(&fs::read(expected_filename).expect(expected_filename)).expect(expected_filename);
 
-    let diff = img_diff(&img, &expected);
+    let diff = img_diff(img, &expected);
     if diff > 0.0004 {
         let bad_file = Path::new(expected_filename).with_extension("failed-test.png");
         write_png(&bad_file, w2, h2, img);
@@ -57,7 +57,7 @@ fn test_width(w2: usize) {
     use rgb::FromSlice;
 
     let tiger = &include_bytes!("../examples/tiger.png")[..];
-    let (w1, h1, src) = load_png(&tiger).unwrap();
+    let (w1, h1, src) = load_png(tiger).unwrap();
     let mut res1 = vec![];
     let mut res2 = vec![];
     let mut res3 = vec![0; 80*120];